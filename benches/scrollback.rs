@@ -0,0 +1,47 @@
+use bevy_console::bench::{parse_ansi, style_line, Dispatcher};
+use bevy_console::clap::{Arg, Command};
+use bevy_console::{ConsoleConfiguration, PrintConsoleLine};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const PLAIN: &str = "Spawned entity 12v1 at (1.0, 2.0, 3.0)";
+const STYLED: &str = "\x1b[1m\x1b[31merror:\x1b[0m unexpected argument '\x1b[33m--force\x1b[0m' \
+    found, see \x1b]8;;https://docs.rs/bevy_console\x1b\\the docs\x1b]8;;\x1b\\";
+
+fn parse(c: &mut Criterion) {
+    c.bench_function("parse_ansi plain", |b| {
+        b.iter(|| parse_ansi(black_box(PLAIN)))
+    });
+    c.bench_function("parse_ansi styled", |b| {
+        b.iter(|| parse_ansi(black_box(STYLED)))
+    });
+}
+
+fn style(c: &mut Criterion) {
+    let config = ConsoleConfiguration::default();
+    let plain = PrintConsoleLine::new(PLAIN);
+    let styled = PrintConsoleLine::channel("net", STYLED);
+    c.bench_function("style_line plain", |b| {
+        b.iter(|| style_line(black_box(&plain), &config))
+    });
+    c.bench_function("style_line styled", |b| {
+        b.iter(|| style_line(black_box(&styled), &config))
+    });
+}
+
+fn dispatch(c: &mut Criterion) {
+    let mut config = ConsoleConfiguration::default();
+    config.commands.insert(
+        "tp",
+        Command::new("tp").args(["entity", "x", "y", "z"].map(Arg::new)),
+    );
+    let mut dispatcher = Dispatcher::new(config);
+    c.bench_function("dispatch", |b| {
+        b.iter(|| dispatcher.dispatch(black_box("tp 12v1 1.0 '2.0' 3.0")))
+    });
+    c.bench_function("dispatch unknown", |b| {
+        b.iter(|| dispatcher.dispatch(black_box("teleport 12v1")))
+    });
+}
+
+criterion_group!(benches, parse, style, dispatch);
+criterion_main!(benches);
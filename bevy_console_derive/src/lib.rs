@@ -1,27 +1,131 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, DeriveInput, ItemFn};
 
-#[proc_macro_derive(ConsoleCommand, attributes(command))]
+#[proc_macro_derive(ConsoleCommand, attributes(command, console_command))]
 pub fn derive_clap_command(input: TokenStream) -> TokenStream {
     let derive_input = parse_macro_input!(input as DeriveInput);
 
     let name_string = get_command_name(&derive_input);
+    let examples = get_examples(&derive_input);
     let name = &derive_input.ident;
     let generics = derive_input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let registration = auto_register_command(name, &generics);
 
     TokenStream::from(quote! {
         impl #impl_generics bevy_console::NamedCommand for #name #ty_generics #where_clause {
             fn name() -> &'static str {
                 #name_string
             }
+
+            fn examples() -> &'static [&'static str] {
+                &[#(#examples),*]
+            }
         }
 
-        impl #impl_generics bevy::prelude::Resource for #name #ty_generics #where_clause {};
+        impl #impl_generics bevy::prelude::Resource for #name #ty_generics #where_clause {}
+
+        #registration
     })
 }
 
+/// Marks a system as the handler of a command, so the `auto_register` feature adds it along
+/// with the command.
+///
+/// The command is the type parameter of the `ConsoleCommand` argument, or can be given as in
+/// `#[console_handler(LogCommand)]`. Without the `auto_register` feature the function is left
+/// as is.
+#[proc_macro_attribute]
+pub fn console_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item_fn = parse_macro_input!(item as ItemFn);
+    let command = if attr.is_empty() {
+        handled_command(&item_fn)
+    } else {
+        parse_macro_input!(attr as syn::Type)
+    };
+    let registration = auto_register_handler(&item_fn.sig.ident, &command);
+
+    TokenStream::from(quote! {
+        #item_fn
+
+        #registration
+    })
+}
+
+/// Whether derived commands and handlers are registered, commands of bevy_console itself are
+/// added by its plugin instead
+fn auto_register() -> bool {
+    cfg!(feature = "auto_register")
+        && std::env::var("CARGO_CRATE_NAME").map_or(true, |krate| krate != "bevy_console")
+}
+
+/// Submits the command to the inventory of auto registered commands, generic commands can't be
+fn auto_register_command(name: &syn::Ident, generics: &syn::Generics) -> proc_macro2::TokenStream {
+    if !auto_register() || !generics.params.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        const _: () = {
+            fn register(app: &mut bevy::prelude::App) {
+                bevy_console::AutoCommand::register::<#name>(app);
+            }
+
+            bevy_console::inventory::submit! {
+                bevy_console::AutoCommand::new(::std::any::TypeId::of::<#name>, register)
+            }
+        };
+    }
+}
+
+/// Submits the handler to the inventory of auto registered handlers
+fn auto_register_handler(handler: &syn::Ident, command: &syn::Type) -> proc_macro2::TokenStream {
+    if !auto_register() {
+        return quote! {};
+    }
+    quote! {
+        const _: () = {
+            fn register(app: &mut bevy::prelude::App) {
+                bevy_console::AutoHandler::register(app, #handler);
+            }
+
+            bevy_console::inventory::submit! {
+                bevy_console::AutoHandler::new(::std::any::TypeId::of::<#command>, register)
+            }
+        };
+    }
+}
+
+/// The command type of the `ConsoleCommand<T>` argument of a handler
+fn handled_command(item_fn: &ItemFn) -> syn::Type {
+    item_fn
+        .sig
+        .inputs
+        .iter()
+        .find_map(|input| {
+            let syn::FnArg::Typed(arg) = input else {
+                return None;
+            };
+            let syn::Type::Path(path) = &*arg.ty else {
+                return None;
+            };
+            let segment = path.path.segments.last()?;
+            if segment.ident != "ConsoleCommand" {
+                return None;
+            }
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return None;
+            };
+            args.args.iter().find_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            })
+        })
+        .unwrap_or_else(|| {
+            panic!("Expected a ConsoleCommand<T> argument, or the command as in #[console_handler(LogCommand)]")
+        })
+}
+
 fn get_command_name(input: &DeriveInput) -> syn::LitStr {
     input
         .attrs
@@ -49,3 +153,75 @@ fn get_command_name(input: &DeriveInput) -> syn::LitStr {
         })
         .unwrap_or_else(|| syn::LitStr::new(&input.ident.to_string(), input.ident.span()))
 }
+
+/// Collects every `#[console_command(example = "...")]` entry, in order
+fn get_examples(input: &DeriveInput) -> Vec<syn::LitStr> {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("console_command"))
+        .flat_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => list.nested.into_iter().collect::<Vec<_>>(),
+            _ => panic!("Expected #[console_command(example = \"...\")]"),
+        })
+        .map(|meta| match meta {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("example") => {
+                match nv.lit {
+                    syn::Lit::Str(str) => str,
+                    _ => panic!("Expected string literal as command example"),
+                }
+            }
+            _ => panic!("Unknown console_command attribute, expected `example = \"...\"`"),
+        })
+        .collect()
+}
+
+/// Wraps a function taking a parsed command into a `ConsoleCommand<T>` handler system.
+///
+/// The first argument is the command, the others are system parameters. The function is run for
+/// every invocation parsed this frame, and its output is replied with `HandlerOutput`, e.g. `()`
+/// prints `[ok]` and an `Err` prints the error followed by `[failed]`.
+///
+/// Put it above `#[console_handler]` when both are used.
+#[proc_macro_attribute]
+pub fn console_command_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        panic!("#[console_command_handler] takes no arguments");
+    }
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(item as ItemFn);
+    let mut inputs = sig.inputs.iter();
+    let Some(syn::FnArg::Typed(command)) = inputs.next() else {
+        panic!("Expected the command as the first argument, as in `fn log(cmd: LogCommand)`");
+    };
+    let command_pat = &command.pat;
+    let command_ty = &command.ty;
+    let params = inputs.collect::<Vec<_>>();
+    let output = match &sig.output {
+        syn::ReturnType::Default => quote! { () },
+        syn::ReturnType::Type(_, ty) => quote! { #ty },
+    };
+    let name = &sig.ident;
+    let (impl_generics, _, where_clause) = sig.generics.split_for_impl();
+
+    TokenStream::from(quote! {
+        #(#attrs)*
+        #vis fn #name #impl_generics(
+            mut __console_command: bevy_console::ConsoleCommand<#command_ty>,
+            #(#params),*
+        ) #where_clause {
+            #[allow(unused_mut)]
+            let mut handler = |#command_pat: #command_ty| -> #output #block;
+            while let Some(command) = __console_command.take() {
+                let Ok(command) = command else {
+                    continue;
+                };
+                bevy_console::HandlerOutput::reply(handler(command), &mut __console_command);
+            }
+        }
+    })
+}
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+use bevy_console::{console_handler, reply, ConsoleCommand, ConsolePlugin};
+use clap::Parser;
+
+fn main() {
+    // no `add_console_command`, the commands and their handlers are found by the plugin
+    App::new()
+        .add_plugins((DefaultPlugins, ConsolePlugin::new()))
+        .run();
+}
+
+/// Prints given arguments to the console
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "log")]
+#[console_command(example = "log hello")]
+struct LogCommand {
+    /// Message to print
+    msg: String,
+}
+
+#[console_handler]
+fn log_command(mut log: ConsoleCommand<LogCommand>) {
+    while let Some(Ok(LogCommand { msg })) = log.take() {
+        reply!(log, "{msg}");
+        log.ok();
+    }
+}
+
+/// Greets the player
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "hello")]
+struct HelloCommand;
+
+#[console_handler(HelloCommand)]
+fn hello_command(mut hello: ConsoleCommand<HelloCommand>) {
+    while let Some(Ok(HelloCommand)) = hello.take() {
+        reply!(hello, "Hello!");
+        hello.ok();
+    }
+}
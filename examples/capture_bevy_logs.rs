@@ -10,7 +10,7 @@ fn main() {
                 filter: "error,capture_bevy_logs=info".to_owned(),
                 custom_layer: make_layer,
             }),
-            ConsolePlugin,
+            ConsolePlugin::new(),
         ))
         .add_systems(Startup, || {
             log::info!("Hi!");
@@ -1,12 +1,12 @@
 use bevy::input::keyboard::NativeKeyCode;
 use bevy::prelude::*;
-use bevy_console::{ConsoleConfiguration, ConsolePlugin};
+use bevy_console::ConsolePlugin;
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, ConsolePlugin))
-        .insert_resource(ConsoleConfiguration {
-            keys: vec![
+        .add_plugins((
+            DefaultPlugins,
+            ConsolePlugin::new().with_toggle_keys([
                 // Console key on a swedish keyboard
                 KeyCode::Unidentified(NativeKeyCode::Android(41)),
                 KeyCode::Unidentified(NativeKeyCode::MacOS(41)),
@@ -16,8 +16,7 @@ fn main() {
                 KeyCode::Backquote,
                 // F1 key
                 KeyCode::F1,
-            ],
-            ..Default::default()
-        })
+            ]),
+        ))
         .run();
 }
@@ -5,7 +5,7 @@ use clap::Parser;
 fn main() {
     App::new()
         // set background to red
-        .add_plugins((DefaultPlugins, ConsolePlugin))
+        .add_plugins((DefaultPlugins, ConsolePlugin::new()))
         .add_console_command::<LogCommand, _>(log_command)
         .run();
 }
@@ -13,6 +13,7 @@ fn main() {
 /// Prints given arguments to the console
 #[derive(Parser, ConsoleCommand)]
 #[command(name = "log")]
+#[console_command(example = "log hello", example = "log \"hello world\" 3")]
 struct LogCommand {
     /// Message to print
     msg: String,
@@ -21,7 +22,7 @@ struct LogCommand {
 }
 
 fn log_command(mut log: ConsoleCommand<LogCommand>) {
-    if let Some(Ok(LogCommand { msg, num })) = log.take() {
+    while let Some(Ok(LogCommand { msg, num })) = log.take() {
         let repeat_count = num.unwrap_or(1);
 
         for _ in 0..repeat_count {
@@ -0,0 +1,45 @@
+//! Prints 10,000 lines per second to measure the throughput of the scrollback,
+//! e.g. while profiling. Run with `--release`, the frame time is logged every second.
+
+use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy_console::{ConsoleOpen, ConsolePlugin, ConsoleSet, PrintConsoleLine};
+
+const LINES_PER_SECOND: f64 = 10_000.0;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            ConsolePlugin::new().with_scrollback_budget(8 * 1024 * 1024),
+            FrameTimeDiagnosticsPlugin,
+            LogDiagnosticsPlugin::default(),
+        ))
+        .insert_resource(ConsoleOpen { open: true })
+        .add_systems(Update, flood_console.after(ConsoleSet::ConsoleUI))
+        .run();
+}
+
+/// Prints the lines due since the last frame, alternating plain and colored ones
+fn flood_console(
+    time: Res<Time>,
+    mut due: Local<f64>,
+    mut printed: Local<u64>,
+    mut console_line: EventWriter<PrintConsoleLine>,
+) {
+    *due += time.delta_seconds_f64() * LINES_PER_SECOND;
+    let count = due.floor() as u64;
+    *due -= count as f64;
+    console_line.send_batch((0..count).map(|_| {
+        *printed += 1;
+        let line = if *printed % 2 == 0 {
+            format!("line {} of the stress test", *printed)
+        } else {
+            format!(
+                "\x1b[32mline\x1b[0m {} \x1b[1mof the stress test\x1b[0m",
+                *printed
+            )
+        };
+        PrintConsoleLine::new(line)
+    }));
+}
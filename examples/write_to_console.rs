@@ -3,7 +3,7 @@ use bevy_console::{ConsolePlugin, ConsoleSet, PrintConsoleLine};
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, ConsolePlugin))
+        .add_plugins((DefaultPlugins, ConsolePlugin::new()))
         // NOTE: this wouldn't work for this particular case,
         // systems in the [`ConsoleSet::Commands`] do not run if there are no console commands entered
         // .add_systems(Update, write_to_console.in_set(ConsoleSet::Commands))
@@ -13,5 +13,5 @@ fn main() {
 }
 
 fn write_to_console(mut console_line: EventWriter<PrintConsoleLine>) {
-    console_line.send(PrintConsoleLine::new("Hello".into()));
+    console_line.send(PrintConsoleLine::new("Hello"));
 }
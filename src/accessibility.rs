@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+
+use crate::throttle::ThrottledLines;
+use crate::{strip_ansi, CommandSource, PrintConsoleLine};
+
+/// Function called with every [`Announcement`], e.g. to pass it to a text-to-speech engine
+pub type AnnounceFn = Box<dyn Fn(&Announcement) + Send + Sync>;
+
+/// Console output announced to assistive technology, see [`ConsoleAccessibility`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Announcement {
+    /// A line printed to the console window, without ANSI escape codes
+    Line(String),
+    /// The suggestions shown below the input changed, empty once they are hidden
+    Suggestions(Vec<String>),
+}
+
+impl Announcement {
+    /// Text of the announcement as it should be spoken
+    pub fn text(&self) -> String {
+        match self {
+            Self::Line(line) => line.clone(),
+            Self::Suggestions(suggestions) => suggestions.join(", "),
+        }
+    }
+}
+
+/// Announces newly printed lines and changed suggestions for screen readers.
+///
+/// Announcements are passed to the callback set with [`on_announce`](Self::on_announce), and
+/// with [`egui_output`](Self::egui_output) to egui's output events, which its AccessKit
+/// integration and screen reader support read out.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_console::ConsoleAccessibility;
+/// let mut accessibility = ConsoleAccessibility::default();
+/// accessibility.egui_output = true;
+/// accessibility.on_announce(|announcement| info!("speak: {}", announcement.text()));
+/// App::new().insert_resource(accessibility);
+/// ```
+#[derive(Default, Resource)]
+pub struct ConsoleAccessibility {
+    /// Forward announcements to egui's output events
+    pub egui_output: bool,
+    on_announce: Option<AnnounceFn>,
+    /// Announcements not yet forwarded to egui
+    pub(crate) pending: Vec<Announcement>,
+    /// Suggestions announced last
+    pub(crate) suggestions: Vec<String>,
+}
+
+impl ConsoleAccessibility {
+    /// Calls `f` with every announcement, replacing any previous callback.
+    pub fn on_announce(&mut self, f: impl Fn(&Announcement) + Send + Sync + 'static) -> &mut Self {
+        self.on_announce = Some(Box::new(f));
+        self
+    }
+
+    /// Whether announcements are passed on at all
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.egui_output || self.on_announce.is_some()
+    }
+
+    /// Passes the announcement to the callback, and queues it for egui
+    pub(crate) fn announce(&mut self, announcement: Announcement) {
+        if let Some(on_announce) = &self.on_announce {
+            on_announce(&announcement);
+        }
+        if self.egui_output {
+            self.pending.push(announcement);
+        }
+    }
+
+    /// Announces the suggestions if they changed since they were last announced
+    pub(crate) fn announce_suggestions(&mut self, suggestions: &[String]) {
+        if self.is_enabled() && self.suggestions != suggestions {
+            self.suggestions = suggestions.to_vec();
+            self.announce(Announcement::Suggestions(suggestions.to_vec()));
+        }
+    }
+}
+
+/// Announces the lines printed to the console window this frame
+pub(crate) fn announce_lines(
+    mut accessibility: ResMut<ConsoleAccessibility>,
+    throttled: Res<ThrottledLines>,
+    mut lines: EventReader<PrintConsoleLine>,
+) {
+    if !accessibility.is_enabled() {
+        lines.clear();
+        return;
+    }
+    for line in throttled.read(&mut lines) {
+        let shown = line
+            .target
+            .as_ref()
+            .map_or(true, |target| *target == CommandSource::LocalUi);
+        if shown && line.pin.is_none() {
+            let text = strip_ansi(&line.line);
+            accessibility.announce(Announcement::Line(text));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn test_announce_lines() {
+        let announced = Arc::new(Mutex::new(Vec::new()));
+        let mut accessibility = ConsoleAccessibility::default();
+        let recorder = announced.clone();
+        accessibility.on_announce(move |announcement| {
+            recorder.lock().unwrap().push(announcement.clone());
+        });
+
+        let mut app = App::new();
+        app.add_event::<PrintConsoleLine>()
+            .insert_resource(accessibility)
+            .init_resource::<ThrottledLines>()
+            .add_systems(Update, announce_lines);
+        app.world_mut().send_event_batch([
+            PrintConsoleLine::new("\x1b[31mfps:\x1b[0m 60".to_owned()),
+            PrintConsoleLine::new("terminal".to_owned()).with_target(CommandSource::Terminal),
+        ]);
+        app.update();
+
+        let mut accessibility = app.world_mut().resource_mut::<ConsoleAccessibility>();
+        let suggestions = ["clear".to_owned(), "cls".to_owned()];
+        accessibility.announce_suggestions(&suggestions);
+        accessibility.announce_suggestions(&suggestions);
+
+        assert_eq!(
+            *announced.lock().unwrap(),
+            [
+                Announcement::Line("fps: 60".to_owned()),
+                Announcement::Suggestions(suggestions.to_vec()),
+            ]
+        );
+        assert!(accessibility.pending.is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+use std::any::TypeId;
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::console::add_command_info;
+use crate::{Command, ConsoleSet};
+
+/// A command derived with [`ConsoleCommand`](derive@crate::ConsoleCommand), registered by the
+/// plugin with the `auto_register` feature
+#[doc(hidden)]
+pub struct AutoCommand {
+    command: fn() -> TypeId,
+    register: fn(&mut App),
+}
+
+impl AutoCommand {
+    pub const fn new(command: fn() -> TypeId, register: fn(&mut App)) -> Self {
+        Self { command, register }
+    }
+
+    /// Adds the command to the configuration, so `help` and completion know about it
+    pub fn register<T: Command>(app: &mut App) {
+        add_command_info::<T>(app);
+    }
+}
+
+inventory::collect!(AutoCommand);
+
+/// A handler marked with [`console_handler`](crate::console_handler), registered by the plugin
+/// with the `auto_register` feature
+#[doc(hidden)]
+pub struct AutoHandler {
+    command: fn() -> TypeId,
+    register: fn(&mut App),
+}
+
+impl AutoHandler {
+    pub const fn new(command: fn() -> TypeId, register: fn(&mut App)) -> Self {
+        Self { command, register }
+    }
+
+    /// Adds the handler system like [`add_console_command`](crate::AddConsoleCommand)
+    pub fn register<Params>(app: &mut App, system: impl IntoSystemConfigs<Params>) {
+        app.add_systems(Update, system.in_set(ConsoleSet::Commands));
+    }
+}
+
+inventory::collect!(AutoHandler);
+
+/// Commands added with [`add_console_command`](crate::AddConsoleCommand::add_console_command),
+/// which aren't registered again
+#[derive(Default, Resource)]
+pub(crate) struct RegisteredCommands(pub(crate) HashSet<TypeId>);
+
+/// Registers the derived commands and their handlers which weren't added by hand
+pub(crate) fn register_auto_commands(app: &mut App) {
+    let registered = app
+        .world_mut()
+        .remove_resource::<RegisteredCommands>()
+        .unwrap_or_default();
+    for command in inventory::iter::<AutoCommand> {
+        if !registered.0.contains(&(command.command)()) {
+            (command.register)(app);
+        }
+    }
+    for handler in inventory::iter::<AutoHandler> {
+        if !registered.0.contains(&(handler.command)()) {
+            (handler.register)(app);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::{ConsoleOpen, PrintConsoleLine};
+
+/// Function producing the message of the day
+pub type MotdFn = Arc<dyn Fn(&World) -> Vec<String> + Send + Sync>;
+
+/// Text printed once, when the console is opened for the first time.
+///
+/// Set with [`ConsolePlugin::with_banner`](crate::ConsolePlugin::with_banner), a banner inserted
+/// as a resource before the plugin takes precedence.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_console::ConsolePlugin;
+/// App::new().add_plugins(
+///     ConsolePlugin::new()
+///         .with_banner("\x1b[1mMy Game\x1b[0m\nPress ` to close the console")
+///         .with_motd(|world| vec![format!("{} entities", world.entities().len())]),
+/// );
+/// ```
+#[derive(Clone, Default, Resource)]
+pub struct ConsoleBanner {
+    /// Static banner, may contain multiple lines and ANSI styling
+    pub banner: Option<String>,
+    /// Message of the day, printed after the banner
+    pub motd: Option<MotdFn>,
+}
+
+pub(crate) fn print_banner(world: &mut World, mut printed: Local<bool>) {
+    if *printed || !world.resource::<ConsoleOpen>().open {
+        return;
+    }
+    *printed = true;
+
+    let Some(banner) = world.get_resource::<ConsoleBanner>().cloned() else {
+        return;
+    };
+    let mut lines = banner
+        .banner
+        .iter()
+        .flat_map(|banner| banner.lines())
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+    if let Some(motd) = banner.motd {
+        lines.extend(motd(world));
+    }
+
+    world.send_event_batch(lines.into_iter().map(PrintConsoleLine::new));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_banner_printed_once() {
+        let mut app = App::new();
+        app.add_event::<PrintConsoleLine>()
+            .init_resource::<ConsoleOpen>()
+            .insert_resource(ConsoleBanner {
+                banner: Some("My Game\nv1".to_owned()),
+                motd: Some(Arc::new(|_| vec!["motd".to_owned()])),
+            })
+            .add_systems(Update, print_banner.run_if(resource_changed::<ConsoleOpen>));
+        let printed = |app: &App| {
+            app.world()
+                .resource::<Events<PrintConsoleLine>>()
+                .iter_current_update_events()
+                .map(|line| line.line.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        app.update();
+        assert!(printed(&app).is_empty());
+
+        app.world_mut().resource_mut::<ConsoleOpen>().open = true;
+        app.update();
+        assert_eq!(printed(&app), ["My Game", "v1", "motd"]);
+
+        app.world_mut().resource_mut::<ConsoleOpen>().open = false;
+        app.update();
+        app.world_mut().resource_mut::<ConsoleOpen>().open = true;
+        app.update();
+        assert!(printed(&app).is_empty());
+    }
+}
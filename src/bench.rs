@@ -0,0 +1,76 @@
+//! Internals exercised by the benchmarks in `benches/`, not part of the public API.
+
+use bevy::ecs::event::Events;
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+
+use crate::color::parse_ansi_styled_str;
+use crate::console::{dispatch_line, AppCommands};
+use crate::middleware::ConsoleMiddlewares;
+use crate::{
+    CommandSource, ConsoleCommandEntered, ConsoleConfiguration, ConsoleLocale, PrintConsoleLine,
+};
+
+/// Parses the ANSI styling of a line, returning the number of style changes
+pub fn parse_ansi(line: &str) -> usize {
+    parse_ansi_styled_str(line).len()
+}
+
+/// Lays out a line the way the console window does, returning the number of sections
+#[cfg(feature = "ui")]
+pub fn style_line(line: &PrintConsoleLine, config: &ConsoleConfiguration) -> usize {
+    let font = bevy_egui::egui::FontId::monospace(config.theme.font_size);
+    crate::console::style_ansi_text(line, config, &font)
+        .0
+        .sections
+        .len()
+}
+
+type DispatchParams = (
+    Res<'static, ConsoleConfiguration>,
+    Res<'static, AppCommands>,
+    Res<'static, ConsoleLocale>,
+    ResMut<'static, ConsoleMiddlewares>,
+    EventWriter<'static, ConsoleCommandEntered>,
+);
+
+/// Dispatches input lines outside of an app
+pub struct Dispatcher {
+    world: World,
+    params: SystemState<DispatchParams>,
+}
+
+impl Dispatcher {
+    /// Creates a dispatcher for the commands registered in the configuration
+    pub fn new(config: ConsoleConfiguration) -> Self {
+        let mut world = World::new();
+        world.insert_resource(config);
+        world.init_resource::<AppCommands>();
+        world.init_resource::<ConsoleLocale>();
+        world.init_resource::<ConsoleMiddlewares>();
+        world.init_resource::<Events<ConsoleCommandEntered>>();
+        let params = SystemState::new(&mut world);
+        Self { world, params }
+    }
+
+    /// Splits the line and dispatches its command like a line entered into the console
+    pub fn dispatch(&mut self, line: &str) -> Result<(), String> {
+        let (config, app_commands, locale, mut middlewares, mut command_entered) =
+            self.params.get_mut(&mut self.world);
+        let sent = dispatch_line(
+            line,
+            CommandSource::LocalUi,
+            &config,
+            &app_commands,
+            &locale,
+            &mut middlewares,
+            &mut command_entered,
+        );
+        // nothing reads the commands, drop them instead of letting them pile up
+        middlewares.1.clear();
+        self.world
+            .resource_mut::<Events<ConsoleCommandEntered>>()
+            .clear();
+        sent.map(drop)
+    }
+}
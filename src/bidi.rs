@@ -0,0 +1,133 @@
+use bevy_egui::egui::text::{LayoutJob, LayoutSection};
+use unicode_bidi::{BidiInfo, Level};
+
+/// Reorders the text of a layout job from logical to visual order, so lines mixing right-to-left
+/// scripts like Arabic or Hebrew with left-to-right command syntax read correctly, as egui lays
+/// out text strictly left to right.
+///
+/// Paragraphs without strongly directional characters are laid out right to left if
+/// `right_to_left` is set. Lines are reordered before they are wrapped, so the rows of a wrapped
+/// right-to-left line are read from the bottom up.
+///
+/// Returns the reordered job along with the index of the section of `job` each of its sections
+/// comes from, or `None` if the text doesn't need to be reordered.
+pub(crate) fn reorder_job(job: &LayoutJob, right_to_left: bool) -> Option<(LayoutJob, Vec<u32>)> {
+    let bidi = BidiInfo::new(&job.text, right_to_left.then(Level::rtl));
+    if !bidi.has_rtl() {
+        return None;
+    }
+
+    let mut reordered = LayoutJob {
+        text: String::with_capacity(job.text.len()),
+        sections: Vec::new(),
+        ..job.clone()
+    };
+    let mut origins = Vec::new();
+    let mut push = |index: usize, c: char| {
+        let Some(origin) = job
+            .sections
+            .iter()
+            .position(|section| section.byte_range.contains(&index))
+        else {
+            return;
+        };
+        let start = reordered.text.len();
+        reordered.text.push(c);
+        let end = reordered.text.len();
+        match reordered.sections.last_mut() {
+            Some(section) if origins.last() == Some(&(origin as u32)) => {
+                section.byte_range.end = end;
+            }
+            _ => {
+                reordered.sections.push(LayoutSection {
+                    leading_space: 0.0,
+                    byte_range: start..end,
+                    format: job.sections[origin].format.clone(),
+                });
+                origins.push(origin as u32);
+            }
+        }
+    };
+
+    for paragraph in &bidi.paragraphs {
+        let text = &job.text[paragraph.range.clone()];
+        // the paragraph separator stays at the end
+        let line_end = paragraph.range.start + text.trim_end_matches(['\r', '\n']).len();
+        let (levels, runs) = bidi.visual_runs(paragraph, paragraph.range.start..line_end);
+        for run in runs {
+            let chars = job.text[run.clone()]
+                .char_indices()
+                .map(|(index, c)| (run.start + index, c));
+            if levels[run.start].is_rtl() {
+                for (index, c) in chars.rev() {
+                    push(index, mirrored(c));
+                }
+            } else {
+                for (index, c) in chars {
+                    push(index, c);
+                }
+            }
+        }
+        for (index, c) in job.text[line_end..paragraph.range.end].char_indices() {
+            push(line_end + index, c);
+        }
+    }
+    Some((reordered, origins))
+}
+
+/// The mirrored glyph of paired punctuation, shown in right-to-left runs
+fn mirrored(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_egui::egui::{Color32, FontId, TextFormat};
+
+    use super::*;
+
+    fn job(sections: &[&str]) -> LayoutJob {
+        let mut job = LayoutJob::default();
+        for (index, text) in sections.iter().enumerate() {
+            let color = Color32::from_gray(index as u8);
+            job.append(text, 0.0, TextFormat::simple(FontId::default(), color));
+        }
+        job
+    }
+
+    fn sections(job: &LayoutJob) -> Vec<&str> {
+        job.sections
+            .iter()
+            .map(|section| &job.text[section.byte_range.clone()])
+            .collect()
+    }
+
+    #[test]
+    fn test_reorder_job() {
+        assert!(reorder_job(&job(&["say hello"]), false).is_none());
+
+        // "say" followed by the Hebrew word "shalom" in a second section
+        let (reordered, origins) = reorder_job(&job(&["say ", "שלום (1)"]), false).unwrap();
+        assert_eq!(reordered.text, "say (1) םולש");
+        assert_eq!(sections(&reordered), ["say ", "(1) םולש"]);
+        assert_eq!(origins, [0, 1]);
+
+        // a right-to-left base level puts the command syntax on the right
+        let (reordered, origins) = reorder_job(&job(&["say ", "שלום\n"]), true).unwrap();
+        assert_eq!(reordered.text, "םולש say\n");
+        assert_eq!(sections(&reordered), ["םולש", " say", "\n"]);
+        assert_eq!(origins, [1, 0, 1]);
+    }
+}
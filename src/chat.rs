@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::egui::{self, TextEdit};
+use bevy_egui::EguiContexts;
+
+use crate::console::{console_font, ConsoleState};
+use crate::{CommandSource, ConsoleConfiguration, ConsoleInputSubmitted, ConsoleOpen};
+
+/// Settings of the chat box, see [`ConsolePlugin::with_chat`](crate::ConsolePlugin::with_chat).
+///
+/// The chat box is a single line input without scrollback, shown at the bottom of the window
+/// while typing. Lines starting with [`command_prefix`](Self::command_prefix) are executed as
+/// commands, anything else is passed as a single argument to
+/// [`default_command`](Self::default_command). Either way the line is echoed and stored in the
+/// console history, like lines typed in the console.
+#[derive(Clone, Debug, Resource)]
+pub struct ChatBox {
+    /// Key opening the chat box, `Enter` by default
+    pub open_key: KeyCode,
+    /// Prefix of lines executed as commands, `/` by default
+    pub command_prefix: String,
+    /// Command receiving lines without the prefix, the builtin [`SayCommand`] by default.
+    ///
+    /// [`SayCommand`]: crate::builtin::SayCommand
+    ///
+    /// Without one, every line is executed as a command.
+    pub default_command: Option<String>,
+    /// Placeholder shown in the empty input
+    pub hint: String,
+}
+
+impl Default for ChatBox {
+    fn default() -> Self {
+        Self {
+            open_key: KeyCode::Enter,
+            command_prefix: "/".to_owned(),
+            default_command: Some("say".to_owned()),
+            hint: "Say something, or type /help".to_owned(),
+        }
+    }
+}
+
+impl ChatBox {
+    /// The command line run for a submitted line, if it isn't blank
+    fn command_line(&self, line: &str) -> Option<String> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        let command = match (
+            line.strip_prefix(&self.command_prefix),
+            &self.default_command,
+        ) {
+            (None, Some(default_command)) => {
+                shlex::try_join([default_command.as_str(), line]).ok()?
+            }
+            (command, _) => command.unwrap_or(line).trim().to_owned(),
+        };
+        (!command.is_empty()).then_some(command)
+    }
+}
+
+/// Whether the chat box is open, and the line being typed
+#[derive(Default, Resource)]
+pub(crate) struct ChatState {
+    open: bool,
+    input: String,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn chat_ui(
+    mut egui_context: EguiContexts,
+    chat: Res<ChatBox>,
+    config: Res<ConsoleConfiguration>,
+    console_state: Res<ConsoleState>,
+    console_open: Res<ConsoleOpen>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<ChatState>,
+    mut submitted: EventWriter<ConsoleInputSubmitted>,
+    windows: Query<(), With<Window>>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+) {
+    let Some(target) = config
+        .window
+        .filter(|window| windows.contains(*window))
+        .or_else(|| primary_window.get_single().ok())
+    else {
+        return;
+    };
+    let Some(ctx) = egui_context.try_ctx_for_entity_mut(target) else {
+        return;
+    };
+
+    // the console takes over the keyboard while open
+    if console_open.open {
+        state.open = false;
+        return;
+    }
+    if !state.open {
+        if keys.just_pressed(chat.open_key)
+            && console_state.restricted_by.is_empty()
+            && !ctx.wants_keyboard_input()
+        {
+            state.open = true;
+            state.input.clear();
+        }
+        return;
+    }
+
+    let font = console_font(ctx, &config, 1.0);
+    let mut line = None;
+    let app_style = ctx.style();
+    if !config.theme.inherit_app_style {
+        ctx.set_style(config.theme.egui_style(&font));
+    }
+    egui::Area::new(egui::Id::new("console_chat"))
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(8.0, -8.0))
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style())
+                .fill(config.theme.background_color.into())
+                .show(ui, |ui| {
+                    ui.style_mut().visuals.extreme_bg_color = config.theme.background_color.into();
+                    let response = ui.add(
+                        TextEdit::singleline(&mut state.input)
+                            .hint_text(chat.hint.as_str())
+                            .text_color(config.theme.foreground_color.into())
+                            .font(font)
+                            .frame(false)
+                            .desired_width(ui.ctx().screen_rect().width() / 3.0),
+                    );
+                    response.request_focus();
+                    if ui.input(|input| input.key_pressed(egui::Key::Escape)) {
+                        state.open = false;
+                    } else if ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                        line = Some(std::mem::take(&mut state.input));
+                        state.open = false;
+                    }
+                });
+        });
+    ctx.set_style(app_style);
+
+    if let Some(line) = line.and_then(|line| chat.command_line(&line)) {
+        submitted.send(ConsoleInputSubmitted::new(line, CommandSource::LocalUi));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NamedCommand;
+
+    #[test]
+    fn test_chat_command_line() {
+        let chat = ChatBox::default();
+        assert_eq!(
+            chat.command_line("hello there").as_deref(),
+            Some("say 'hello there'")
+        );
+        assert_eq!(
+            chat.command_line("/timescale 0.5").as_deref(),
+            Some("timescale 0.5")
+        );
+        assert_eq!(chat.command_line("  "), None);
+        assert_eq!(chat.command_line("/"), None);
+        // lines are said with the builtin command by default
+        assert_eq!(
+            chat.default_command.as_deref(),
+            Some(crate::builtin::SayCommand::name())
+        );
+
+        let chat = ChatBox {
+            default_command: None,
+            ..default()
+        };
+        assert_eq!(chat.command_line("fps").as_deref(), Some("fps"));
+        assert_eq!(chat.command_line("/fps").as_deref(), Some("fps"));
+    }
+}
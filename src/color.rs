@@ -1,57 +1,347 @@
+use std::borrow::Cow;
 use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
 use ansi_parser::AnsiParser;
+use bevy::color::{Color, ColorToPacked};
 
 #[cfg(feature = "ui")]
 use bevy_egui::egui::Color32;
 
 /// Remove dependence on egui's Color32
+///
+/// With the `serde` feature, colours are deserialized from either their channels,
+/// e.g. `(r: 255, g: 0, b: 0)` with `a` defaulting to opaque, or a hex string like `"#ff000080"`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "ColourRepr")
+)]
 pub struct Colour {
+    /// Red channel
     pub r: u8,
+    /// Green channel
     pub g: u8,
+    /// Blue channel
     pub b: u8,
+    /// Alpha channel, 255 is opaque
+    pub a: u8,
 }
 
 impl Colour {
-    pub fn from_rgb(r: u8, g: u8, b: u8) -> Self {
-        Self { r, g, b }
+    /// Creates an opaque colour from its red, green and blue channels.
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::from_rgba(r, g, b, u8::MAX)
+    }
+
+    /// Creates a colour from its red, green, blue and alpha channels.
+    pub const fn from_rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Parses a `#rrggbb` or `#rrggbbaa` hex string, the `#` is optional.
+    pub fn from_hex(hex: &str) -> Result<Self, ParseColourError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !matches!(digits.len(), 6 | 8) || !digits.is_ascii() {
+            return Err(ParseColourError(hex.to_owned()));
+        }
+        let channel = |index: usize| {
+            u8::from_str_radix(&digits[index * 2..index * 2 + 2], 16)
+                .map_err(|_| ParseColourError(hex.to_owned()))
+        };
+        let a = if digits.len() == 8 {
+            channel(3)?
+        } else {
+            u8::MAX
+        };
+        Ok(Self::from_rgba(channel(0)?, channel(1)?, channel(2)?, a))
+    }
+
+    /// Returns a copy of the colour with the given alpha channel.
+    pub fn with_alpha(mut self, a: u8) -> Self {
+        self.a = a;
+        self
+    }
+}
+
+impl FromStr for Colour {
+    type Err = ParseColourError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+/// Formats the colour as `#rrggbb`, or `#rrggbbaa` if it isn't opaque
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != u8::MAX {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Color> for Colour {
+    fn from(color: Color) -> Self {
+        let [r, g, b, a] = color.to_srgba().to_u8_array();
+        Self::from_rgba(r, g, b, a)
+    }
+}
+
+impl From<Colour> for Color {
+    fn from(colour: Colour) -> Self {
+        Color::srgba_u8(colour.r, colour.g, colour.b, colour.a)
     }
 }
 
 #[cfg(feature = "ui")]
-impl Into<Color32> for Colour {
-    fn into(self) -> Color32 {
-        Color32::from_rgb(self.r, self.g, self.b)
+impl From<Colour> for Color32 {
+    fn from(colour: Colour) -> Self {
+        Color32::from_rgba_unmultiplied(colour.r, colour.g, colour.b, colour.a)
+    }
+}
+
+#[cfg(feature = "tui")]
+impl From<Colour> for ratatui::style::Color {
+    fn from(colour: Colour) -> Self {
+        ratatui::style::Color::Rgb(colour.r, colour.g, colour.b)
+    }
+}
+
+/// A string which isn't a `#rrggbb` or `#rrggbbaa` hex colour
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseColourError(String);
+
+impl fmt::Display for ParseColourError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid colour `{}`, expected #rrggbb or #rrggbbaa",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseColourError {}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum ColourRepr {
+    Hex(String),
+    Channels {
+        r: u8,
+        g: u8,
+        b: u8,
+        #[serde(default = "opaque")]
+        a: u8,
+    },
+}
+
+#[cfg(feature = "serde")]
+fn opaque() -> u8 {
+    u8::MAX
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ColourRepr> for Colour {
+    type Error = ParseColourError;
+
+    fn try_from(repr: ColourRepr) -> Result<Self, Self::Error> {
+        match repr {
+            ColourRepr::Hex(hex) => Self::from_hex(&hex),
+            ColourRepr::Channels { r, g, b, a } => Ok(Self::from_rgba(r, g, b, a)),
+        }
     }
 }
 
+/// Start of an OSC 8 hyperlink sequence, `ESC ] 8 ; params ; URI ST`
+const OSC8_START: &str = "\x1b]8;";
+
 pub(crate) fn parse_ansi_styled_str(
     ansi_string: &str,
 ) -> Vec<(usize, HashSet<TextFormattingOverride>)> {
     let mut result: Vec<(usize, HashSet<TextFormattingOverride>)> = Vec::new();
     let mut offset = 0;
-    for element in ansi_string.ansi_parse() {
-        match element {
-            ansi_parser::Output::TextBlock(t) => {
-                offset += t.len();
+    let mut rest = ansi_string;
+    loop {
+        // ansi_parser doesn't know OSC sequences, so hyperlinks are split off beforehand
+        let hyperlink = rest.find(OSC8_START).and_then(|start| {
+            let params = &rest[start + OSC8_START.len()..];
+            let (end, terminator_len) = [("\x1b\\", 2), ("\x07", 1)]
+                .into_iter()
+                .filter_map(|(terminator, len)| Some((params.find(terminator)?, len)))
+                .min()?;
+            let uri = params[..end].split_once(';').map_or("", |(_, uri)| uri);
+            Some((start, uri, start + OSC8_START.len() + end + terminator_len))
+        });
+        let text = hyperlink.map_or(rest, |(start, ..)| &rest[..start]);
+
+        for element in text.ansi_parse() {
+            match element {
+                ansi_parser::Output::TextBlock(t) => {
+                    offset += t.len();
+                }
+                ansi_parser::Output::Escape(escape) => {
+                    if let ansi_parser::AnsiSequence::SetGraphicsMode(mode) = escape {
+                        push_overrides(&mut result, offset, parse_graphics_mode(mode.as_slice()));
+                    };
+                }
             }
-            ansi_parser::Output::Escape(escape) => {
-                if let ansi_parser::AnsiSequence::SetGraphicsMode(mode) = escape {
-                    let modes = parse_graphics_mode(mode.as_slice());
-                    if let Some((last_offset, last)) = result.last_mut() {
-                        if *last_offset == offset {
-                            last.extend(modes);
-                            continue;
-                        }
-                    }
-
-                    result.push((offset, modes));
-                };
+        }
+
+        let Some((_, uri, end)) = hyperlink else {
+            return result;
+        };
+        // an empty URI closes the link
+        let link = (!uri.is_empty()).then(|| uri.to_owned());
+        push_overrides(
+            &mut result,
+            offset,
+            HashSet::from([TextFormattingOverride::Hyperlink(link)]),
+        );
+        rest = &rest[end..];
+    }
+}
+
+/// Removes ANSI escape codes from the text, e.g. for outputs showing plain text.
+pub fn strip_ansi(text: &str) -> String {
+    strip_ansi_escapes::strip_str(text)
+}
+
+/// Handling of ANSI escape codes in the lines printed to an output, see [`AnsiPolicy`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnsiMode {
+    /// Keep them, the console window renders them as colors and styles and other outputs pass
+    /// them through
+    #[default]
+    Keep,
+    /// Remove them, leaving plain text
+    Strip,
+}
+
+impl AnsiMode {
+    /// Returns the text with its escape codes kept or removed.
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        match self {
+            Self::Keep => Cow::Borrowed(text),
+            Self::Strip => Cow::Owned(strip_ansi(text)),
+        }
+    }
+}
+
+/// Handling of ANSI escape codes per output, see [`ConsoleConfiguration::ansi`](crate::ConsoleConfiguration::ansi).
+///
+/// The terminal UI of the `tui` feature always shows plain text, colored by severity.
+/// Lines sent to remote console clients keep them with `RemoteConsole::ansi`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnsiPolicy {
+    /// The console window
+    pub window: AnsiMode,
+    /// The terminal lines are printed to with the `rustyline` feature
+    pub terminal: AnsiMode,
+    /// The [`ConsoleConfiguration::log_file`](crate::ConsoleConfiguration::log_file)
+    pub log_file: AnsiMode,
+    /// Output returned by the HTTP endpoint of the `http` feature
+    pub http: AnsiMode,
+}
+
+impl Default for AnsiPolicy {
+    fn default() -> Self {
+        Self {
+            window: AnsiMode::Keep,
+            terminal: AnsiMode::Keep,
+            log_file: AnsiMode::Strip,
+            http: AnsiMode::Strip,
+        }
+    }
+}
+
+/// Sequences erasing the whole screen, `CSI 2J` and `CSI 3J`
+const CLEAR_SCREEN: [&str; 2] = ["\x1b[2J", "\x1b[3J"];
+/// Sequences erasing the text of the row before the cursor, `CSI 1K` and `CSI 2K`
+const ERASE_ROW: [&str; 2] = ["\x1b[1K", "\x1b[2K"];
+/// Sequences erasing the row after a carriage return, `CR CSI K` and `CR CSI 0K`
+const RETURN_ERASE_ROW: [&str; 2] = ["\r\x1b[K", "\r\x1b[0K"];
+
+/// Applies the erase sequences and carriage returns of a printed line the way a terminal with its
+/// cursor at the end of the text would, e.g. for the progress output of tools mirrored into the
+/// console.
+///
+/// Returns whether the line clears the screen, and the text left of it: the text after the last
+/// clear-screen sequence, with every row starting after its last erase-line sequence or carriage
+/// return. Graphics modes set in the erased text still apply to the rest.
+pub(crate) fn apply_erase_sequences(line: &str) -> (bool, Cow<'_, str>) {
+    if !line.contains(['\x1b', '\r']) {
+        return (false, Cow::Borrowed(line));
+    }
+    let last_end = |text: &str, sequences: &[&str]| {
+        sequences
+            .iter()
+            .filter_map(|sequence| Some(text.rfind(sequence)? + sequence.len()))
+            .max()
+    };
+
+    let clear_end = last_end(line, &CLEAR_SCREEN);
+    let mut text = String::with_capacity(line.len());
+    if let Some(end) = clear_end {
+        text.extend(graphics_modes(&line[..end]));
+    }
+    let mut erased = clear_end.is_some();
+    for row in line[clear_end.unwrap_or(0)..].split_inclusive('\n') {
+        // text printed after a carriage return overwrites the row, except before a line break
+        let return_end = row
+            .trim_end_matches(['\r', '\n'])
+            .rfind('\r')
+            .map(|index| index + 1);
+        let erase_end = last_end(row, &ERASE_ROW)
+            .max(last_end(row, &RETURN_ERASE_ROW))
+            .max(return_end);
+        match erase_end {
+            Some(end) => {
+                text.extend(graphics_modes(&row[..end]));
+                text.push_str(&row[end..]);
+                erased = true;
             }
+            None => text.push_str(row),
         }
     }
-    result
+    match erased {
+        true => (clear_end.is_some(), Cow::Owned(text)),
+        false => (false, Cow::Borrowed(line)),
+    }
+}
+
+/// The select graphic rendition sequences in the text, e.g. `ESC [ 1 ; 31 m`
+fn graphics_modes(text: &str) -> impl Iterator<Item = &str> {
+    text.match_indices("\x1b[").filter_map(|(start, _)| {
+        let params = &text[start + 2..];
+        let end = params.find(|c: char| !c.is_ascii_digit() && c != ';')?;
+        params[end..]
+            .starts_with('m')
+            .then(|| &text[start..start + 2 + end + 1])
+    })
+}
+
+/// Adds the overrides starting at the offset, merging them with the previous ones at the same offset
+fn push_overrides(
+    result: &mut Vec<(usize, HashSet<TextFormattingOverride>)>,
+    offset: usize,
+    overrides: HashSet<TextFormattingOverride>,
+) {
+    if let Some((last_offset, last)) = result.last_mut() {
+        if *last_offset == offset {
+            last.extend(overrides);
+            return;
+        }
+    }
+    result.push((offset, overrides));
 }
 
 fn parse_graphics_mode(modes: &[u8]) -> HashSet<TextFormattingOverride> {
@@ -94,7 +384,7 @@ fn ansi_color_code_to_color32(color_code: u8) -> Colour {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum TextFormattingOverride {
     Reset,
     Bold,
@@ -104,12 +394,72 @@ pub(crate) enum TextFormattingOverride {
     Strikethrough,
     Foreground(Colour),
     Background(Colour),
+    /// Start of an OSC 8 hyperlink to the URI, or its end if `None`
+    Hyperlink(Option<String>),
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_hex_colour() {
+        assert_eq!(
+            Colour::from_hex("#de382b"),
+            Ok(Colour::from_rgb(222, 56, 43))
+        );
+        assert_eq!("de382b80".parse(), Ok(Colour::from_rgba(222, 56, 43, 128)));
+        assert!(Colour::from_hex("#de382").is_err());
+        assert!(Colour::from_hex("#gg382b").is_err());
+        assert!(Colour::from_hex("#de3€").is_err());
+        assert_eq!(Colour::from_rgb(222, 56, 43).to_string(), "#de382b");
+        assert_eq!(Colour::from_rgba(222, 56, 43, 128).to_string(), "#de382b80");
+    }
+
+    #[test]
+    fn test_bevy_color_conversion() {
+        let colour = Colour::from_rgba(222, 56, 43, 128);
+        assert_eq!(Colour::from(Color::from(colour)), colour);
+        assert_eq!(Colour::from(Color::WHITE), Colour::from_rgb(255, 255, 255));
+    }
+
+    #[test]
+    fn test_ansi_mode() {
+        let line = "\x1b[31mred\x1b[0m";
+        assert_eq!(AnsiMode::Keep.apply(line), line);
+        assert_eq!(AnsiMode::Strip.apply(line), "red");
+        assert_eq!(strip_ansi(line), "red");
+    }
+
+    #[test]
+    fn test_erase_sequences() {
+        assert_eq!(
+            apply_erase_sequences("plain"),
+            (false, Cow::Borrowed("plain"))
+        );
+        assert_eq!(
+            apply_erase_sequences("old\x1b[2J\x1b[Hnew"),
+            (true, "\x1b[Hnew".into())
+        );
+        assert_eq!(
+            apply_erase_sequences("10%\r\x1b[K\x1b[32m20%\r\x1b[K30%\ndone"),
+            (false, "\x1b[32m30%\ndone".into())
+        );
+        assert_eq!(
+            apply_erase_sequences("a\x1b[31mb\x1b[2Kc"),
+            (false, "\x1b[31mc".into())
+        );
+        assert_eq!(
+            apply_erase_sequences("\r10%\r20%\r\n"),
+            (false, "20%\r\n".into())
+        );
+        // erasing after the cursor at the end of the text erases nothing
+        assert_eq!(
+            apply_erase_sequences("ab\x1b[Kc"),
+            (false, "ab\x1b[Kc".into())
+        );
+    }
+
     #[test]
     fn test_bold_text() {
         let ansi_string = color_print::cstr!(r#"<bold>12345</bold>"#);
@@ -250,6 +600,50 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_hyperlink() {
+        let ansi_string = "see \x1b]8;;https://bevyengine.org\x1b\\bevy\x1b]8;;\x1b\\ docs";
+        let result = parse_ansi_styled_str(ansi_string);
+        assert_eq!(
+            result,
+            vec![
+                (
+                    4,
+                    HashSet::from([TextFormattingOverride::Hyperlink(Some(
+                        "https://bevyengine.org".to_owned()
+                    ))])
+                ),
+                (8, HashSet::from([TextFormattingOverride::Hyperlink(None)]))
+            ]
+        );
+        assert_eq!(strip_ansi_escapes::strip_str(ansi_string), "see bevy docs");
+    }
+
+    #[test]
+    fn test_styled_hyperlink() {
+        let ansi_string = "\x1b]8;id=1;file:///tmp\x07\x1b[31mtmp\x1b[0m\x1b]8;;\x07";
+        let result = parse_ansi_styled_str(ansi_string);
+        assert_eq!(
+            result,
+            vec![
+                (
+                    0,
+                    HashSet::from([
+                        TextFormattingOverride::Hyperlink(Some("file:///tmp".to_owned())),
+                        TextFormattingOverride::Foreground(Colour::from_rgb(222, 56, 43)),
+                    ])
+                ),
+                (
+                    3,
+                    HashSet::from([
+                        TextFormattingOverride::Reset,
+                        TextFormattingOverride::Hyperlink(None)
+                    ])
+                )
+            ]
+        );
+    }
+
     #[test]
     fn overlapping_non_symmetric_styles() {
         let ansi_string = color_print::cstr!(r#"<bold>12345<red>12345</red></bold>"#);
@@ -0,0 +1,124 @@
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use clap::{Parser, Subcommand};
+
+use crate as bevy_console;
+use crate::{reply, ConsoleCommand, ConsoleLocale};
+
+/// Controls the global volume and plays sounds
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "audio")]
+pub struct AudioCommand {
+    #[command(subcommand)]
+    subcommand: AudioSubcommand,
+}
+
+#[derive(Subcommand)]
+enum AudioSubcommand {
+    /// Prints or sets the global volume, applied to playing sounds as well
+    Volume {
+        /// New volume between 0 and 1
+        volume: Option<f32>,
+    },
+    /// Mutes all sounds, or unmutes them if already muted
+    Mute,
+    /// Plays an audio asset once
+    Play {
+        /// Asset path, e.g. `sounds/jump.ogg`
+        asset: String,
+    },
+}
+
+/// Volumes from before `audio mute`, restored when unmuting
+#[derive(Default, Resource)]
+pub(crate) struct AudioMute(Option<(f32, HashMap<Entity, f32>)>);
+
+pub(crate) fn audio_command(
+    mut audio: ConsoleCommand<AudioCommand>,
+    mut commands: Commands,
+    mut global_volume: Option<ResMut<GlobalVolume>>,
+    mut mute: ResMut<AudioMute>,
+    sinks: Query<(Entity, &AudioSink)>,
+    asset_server: Option<Res<AssetServer>>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(AudioCommand { subcommand })) = audio.take() {
+        let Some(global_volume) = global_volume.as_mut() else {
+            audio.reply_failed(locale.format(
+                "missing-resource",
+                &[("resource", "GlobalVolume"), ("plugin", "AudioPlugin")],
+            ));
+            continue;
+        };
+
+        match subcommand {
+            AudioSubcommand::Volume { volume: None } => {
+                reply!(audio, "volume: {}", global_volume.volume.get());
+                if mute.0.is_some() {
+                    reply!(audio, "(muted)");
+                }
+                audio.ok();
+            }
+            AudioSubcommand::Volume {
+                volume: Some(volume),
+            } => {
+                if !(0.0..=1.0).contains(&volume) {
+                    audio.reply_failed(locale.get("volume-out-of-range"));
+                    continue;
+                }
+                let previous = global_volume.volume.get();
+                if let Some((muted_volume, _)) = &mut mute.0 {
+                    // applied when unmuting
+                    *muted_volume = volume;
+                } else if previous > 0.0 {
+                    for (_, sink) in &sinks {
+                        sink.set_volume(sink.volume() * volume / previous);
+                    }
+                }
+                global_volume.volume = Volume::new(volume);
+                audio.ok();
+            }
+            AudioSubcommand::Mute => match mute.0.take() {
+                Some((volume, sink_volumes)) => {
+                    global_volume.volume = Volume::new(volume);
+                    for (entity, sink) in &sinks {
+                        if let Some(sink_volume) = sink_volumes.get(&entity) {
+                            sink.set_volume(*sink_volume);
+                        }
+                    }
+                    reply!(audio, "unmuted");
+                    audio.ok();
+                }
+                None => {
+                    let sink_volumes = sinks
+                        .iter()
+                        .map(|(entity, sink)| {
+                            let volume = sink.volume();
+                            sink.set_volume(0.0);
+                            (entity, volume)
+                        })
+                        .collect();
+                    mute.0 = Some((global_volume.volume.get(), sink_volumes));
+                    global_volume.volume = Volume::new(0.0);
+                    reply!(audio, "muted");
+                    audio.ok();
+                }
+            },
+            AudioSubcommand::Play { asset } => {
+                let Some(asset_server) = asset_server.as_deref() else {
+                    audio.reply_failed(locale.format(
+                        "missing-resource",
+                        &[("resource", "AssetServer"), ("plugin", "AssetPlugin")],
+                    ));
+                    continue;
+                };
+                commands.spawn(AudioBundle {
+                    source: asset_server.load(asset),
+                    settings: PlaybackSettings::DESPAWN,
+                });
+                audio.ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use clap::{Parser, Subcommand};
+
+use crate as bevy_console;
+use crate::{reply, ConsoleCommand, ConsoleConfiguration};
+
+/// Lists, enables and disables output channels
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "channel")]
+pub struct ChannelCommand {
+    #[command(subcommand)]
+    subcommand: ChannelSubcommand,
+}
+
+#[derive(Subcommand)]
+enum ChannelSubcommand {
+    /// Lists all known channels
+    List,
+    /// Shows the lines of a channel
+    Enable {
+        /// Channel name, e.g. `net`
+        name: String,
+    },
+    /// Hides the lines of a channel
+    Disable {
+        /// Channel name, e.g. `net`
+        name: String,
+    },
+}
+
+pub(crate) fn channel_command(
+    mut channel: ConsoleCommand<ChannelCommand>,
+    mut config: ResMut<ConsoleConfiguration>,
+) {
+    while let Some(Ok(ChannelCommand { subcommand })) = channel.take() {
+        match subcommand {
+            ChannelSubcommand::List => {
+                if config.channels.is_empty() {
+                    reply!(channel, "No channels");
+                }
+                for (name, info) in &config.channels {
+                    let status = if info.enabled { "enabled" } else { "disabled" };
+                    reply!(channel, "  {name} - {status}");
+                }
+                channel.ok();
+            }
+            ChannelSubcommand::Enable { name } => {
+                config.channels.entry(name).or_default().enabled = true;
+                channel.ok();
+            }
+            ChannelSubcommand::Disable { name } => {
+                config.channels.entry(name).or_default().enabled = false;
+                channel.ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CommandOutcome, CommandSource, CommandStatus, ConsoleCommandEntered, PrintConsoleLine,
+    };
+
+    #[test]
+    fn test_every_invocation_of_a_frame() {
+        let mut app = App::new();
+        app.add_event::<ConsoleCommandEntered>()
+            .add_event::<PrintConsoleLine>()
+            .add_event::<CommandOutcome>()
+            .init_resource::<ConsoleConfiguration>()
+            .add_systems(Update, channel_command);
+        let enable = |name: &str| ConsoleCommandEntered {
+            command_name: "channel".to_owned(),
+            args: vec!["enable".to_owned(), name.to_owned()],
+            source: CommandSource::LocalUi,
+        };
+        app.world_mut()
+            .send_event_batch([enable("net"), enable("ai")]);
+        app.update();
+
+        let config = app.world().resource::<ConsoleConfiguration>();
+        assert!(config.channels["net"].enabled);
+        assert!(config.channels["ai"].enabled);
+        let statuses = app
+            .world()
+            .resource::<Events<CommandOutcome>>()
+            .iter_current_update_events()
+            .map(|outcome| outcome.status)
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, [CommandStatus::Ok, CommandStatus::Ok]);
+    }
+}
@@ -8,13 +8,13 @@ use clap::Parser;
 /// Clears the console
 #[derive(Parser, ConsoleCommand)]
 #[command(name = "clear")]
-pub(crate) struct ClearCommand;
+pub struct ClearCommand;
 
 pub(crate) fn clear_command(
     mut clear: ConsoleCommand<ClearCommand>,
     mut state: ResMut<ConsoleState>,
 ) {
-    if let Some(Ok(_)) = clear.take() {
+    while let Some(Ok(_)) = clear.take() {
         state.scrollback.clear();
     }
 }
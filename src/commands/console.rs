@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy::window::PrimaryWindow;
+#[cfg(feature = "ui")]
+use bevy_egui::EguiContext;
+use clap::{Parser, Subcommand};
+
+use crate as bevy_console;
+use crate::console::ConsoleState;
+use crate::{reply, ConsoleCommand, ConsoleConfiguration};
+
+/// Inspects the console itself
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "console")]
+pub struct ConsoleStatsCommand {
+    #[command(subcommand)]
+    subcommand: ConsoleSubcommand,
+}
+
+#[derive(Subcommand)]
+enum ConsoleSubcommand {
+    /// Reports the memory held by the scrollback and history
+    Stats,
+}
+
+pub(crate) fn console_stats_command(
+    mut console: ConsoleCommand<ConsoleStatsCommand>,
+    state: Res<ConsoleState>,
+    config: Res<ConsoleConfiguration>,
+    #[cfg(feature = "ui")] mut egui: Query<&mut EguiContext, With<PrimaryWindow>>,
+) {
+    while let Some(Ok(ConsoleStatsCommand { subcommand })) = console.take() {
+        match subcommand {
+            ConsoleSubcommand::Stats => {
+                let budget = config
+                    .scrollback_budget
+                    .map_or("unlimited".to_owned(), format_bytes);
+                reply!(
+                    console,
+                    "scrollback: {} lines, {} (budget: {budget})",
+                    state.scrollback.len(),
+                    format_bytes(state.scrollback_bytes())
+                );
+
+                // the first history entry is the input being edited
+                let history = state.history.iter().skip(1);
+                reply!(
+                    console,
+                    "history:    {} of {} entries, {}",
+                    history.len(),
+                    config.history_size,
+                    format_bytes(history.map(String::len).sum())
+                );
+
+                #[cfg(feature = "ui")]
+                if let Ok(mut egui) = egui.get_single_mut() {
+                    let galleys = egui.get_mut().fonts(|fonts| fonts.num_galleys_in_cache());
+                    reply!(console, "layouts:    {galleys} cached egui galleys");
+                }
+
+                let mut channels = BTreeMap::<&str, usize>::new();
+                for line in &state.scrollback {
+                    *channels
+                        .entry(line.channel.as_deref().unwrap_or("(none)"))
+                        .or_default() += 1;
+                }
+                reply!(console, "channels:");
+                for (channel, lines) in channels {
+                    reply!(console, "  {channel}: {lines} lines");
+                }
+                console.ok();
+            }
+        }
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{bytes} B"),
+        1024..=1_048_575 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
@@ -0,0 +1,232 @@
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+};
+use std::collections::BTreeSet;
+use std::mem;
+
+use bevy::prelude::*;
+use clap::{Parser, Subcommand};
+
+use crate as bevy_console;
+use crate::console::ConsoleState;
+use crate::{reply, ConsoleCommand, ConsoleConfiguration, ConsoleLocale, PrintConsoleLine};
+
+/// Prints the current frame rate, frame time and entity count
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "fps")]
+pub struct FpsCommand;
+
+/// Inspects diagnostics registered in the DiagnosticsStore
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "diag")]
+pub struct DiagCommand {
+    #[command(subcommand)]
+    subcommand: DiagSubcommand,
+}
+
+#[derive(Subcommand)]
+enum DiagSubcommand {
+    /// Lists all registered diagnostics
+    List,
+    /// Shows the current, average and smoothed value of a diagnostic
+    Show {
+        /// Diagnostic path, e.g. `fps` or `frame_time`
+        name: String,
+    },
+    /// Pins the value of a diagnostic to the top of the console, updated live
+    Watch {
+        /// Diagnostic path, e.g. `fps` or `frame_time`
+        name: String,
+    },
+    /// Removes a watched diagnostic, or all of them if none is given
+    Unwatch {
+        /// Watched diagnostic path
+        name: Option<String>,
+    },
+}
+
+/// Diagnostics pinned with `diag watch`
+#[derive(Default, Resource)]
+pub(crate) struct DiagWatches(BTreeSet<String>);
+
+/// Key of the pinned line of a watched diagnostic
+fn pin_key(name: &str) -> String {
+    format!("diag {name}")
+}
+
+pub(crate) fn fps_command(
+    mut fps: ConsoleCommand<FpsCommand>,
+    store: Option<Res<DiagnosticsStore>>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(_)) = fps.take() {
+        let Some(store) = store.as_deref() else {
+            fps.reply_failed(locale.format(
+                "missing-resource",
+                &[
+                    ("resource", "DiagnosticsStore"),
+                    ("plugin", "FrameTimeDiagnosticsPlugin"),
+                ],
+            ));
+            continue;
+        };
+
+        let Some(frame_rate) = store.get(&FrameTimeDiagnosticsPlugin::FPS) else {
+            fps.reply_failed(locale.format(
+                "missing-plugin",
+                &[("plugin", "FrameTimeDiagnosticsPlugin")],
+            ));
+            continue;
+        };
+
+        reply!(fps, "fps:          {}", format_value(frame_rate));
+        if let Some(frame_time) = store.get(&FrameTimeDiagnosticsPlugin::FRAME_TIME) {
+            reply!(fps, "frame time:   {}", format_value(frame_time));
+        }
+        if let Some(entity_count) = store.get(&EntityCountDiagnosticsPlugin::ENTITY_COUNT) {
+            reply!(fps, "entity count: {}", format_value(entity_count));
+        }
+    }
+}
+
+pub(crate) fn diag_command(
+    mut diag: ConsoleCommand<DiagCommand>,
+    store: Option<Res<DiagnosticsStore>>,
+    mut watches: ResMut<DiagWatches>,
+    mut state: ResMut<ConsoleState>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(DiagCommand { subcommand })) = diag.take() {
+        let Some(store) = store.as_deref() else {
+            diag.reply_failed(locale.format(
+                "missing-resource",
+                &[
+                    ("resource", "DiagnosticsStore"),
+                    ("plugin", "DiagnosticsPlugin"),
+                ],
+            ));
+            continue;
+        };
+
+        match subcommand {
+            DiagSubcommand::List => {
+                let mut paths = store
+                    .iter()
+                    .map(|diagnostic| diagnostic.path().as_str())
+                    .collect::<Vec<_>>();
+                paths.sort_unstable();
+                for path in paths {
+                    reply!(diag, "  {path}");
+                }
+            }
+            DiagSubcommand::Show { name } => match store.iter().find(|d| d.path().as_str() == name)
+            {
+                Some(diagnostic) => reply!(diag, "{name}: {}", format_value(diagnostic)),
+                None => diag.reply_failed(locale.format("diagnostic-unknown", &[("name", &name)])),
+            },
+            DiagSubcommand::Watch { name } => {
+                if store.iter().all(|d| d.path().as_str() != name) {
+                    diag.reply_failed(locale.format("diagnostic-unknown", &[("name", &name)]));
+                    continue;
+                }
+                watches.0.insert(name);
+                diag.ok();
+            }
+            DiagSubcommand::Unwatch { name: Some(name) } => {
+                if watches.0.remove(&name) {
+                    state.pinned.remove(&pin_key(&name));
+                    diag.ok();
+                } else {
+                    reply!(diag, "'{}' is not being watched", name);
+                }
+            }
+            DiagSubcommand::Unwatch { name: None } => {
+                for name in mem::take(&mut watches.0) {
+                    state.pinned.remove(&pin_key(&name));
+                }
+                diag.ok();
+            }
+        }
+    }
+}
+
+/// Updates the pinned lines of the watched diagnostics every
+/// [`ConsoleConfiguration::watch_interval`] frames
+pub(crate) fn update_diag_watches(
+    watches: Res<DiagWatches>,
+    store: Option<Res<DiagnosticsStore>>,
+    config: Res<ConsoleConfiguration>,
+    mut state: ResMut<ConsoleState>,
+    mut frames: Local<u32>,
+) {
+    *frames += 1;
+    if *frames < config.watch_interval.max(1) {
+        return;
+    }
+    *frames = 0;
+
+    let Some(store) = store.filter(|_| !watches.0.is_empty()) else {
+        return;
+    };
+    for diagnostic in store.iter() {
+        let name = diagnostic.path().as_str();
+        if watches.0.contains(name) {
+            let key = pin_key(name);
+            let line = PrintConsoleLine::new(format!("{name}: {}", format_value(diagnostic)));
+            state.pinned.insert(key.clone(), line.with_pin(key));
+        }
+    }
+}
+
+fn format_value(diagnostic: &Diagnostic) -> String {
+    let suffix = &diagnostic.suffix;
+    match (
+        diagnostic.value(),
+        diagnostic.average(),
+        diagnostic.smoothed(),
+    ) {
+        (Some(value), Some(average), Some(smoothed)) => {
+            format!("{value:.2}{suffix} (avg {average:.2}{suffix}, smoothed {smoothed:.2}{suffix})")
+        }
+        (Some(value), _, _) => format!("{value:.2}{suffix}"),
+        _ => "no measurements yet".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::diagnostic::{DiagnosticMeasurement, DiagnosticPath};
+    use bevy::utils::Instant;
+
+    use super::*;
+
+    #[test]
+    fn test_update_diag_watches() {
+        let path = DiagnosticPath::const_new("players");
+        let mut store = DiagnosticsStore::default();
+        store.add(Diagnostic::new(path.clone()));
+        store
+            .get_mut(&path)
+            .unwrap()
+            .add_measurement(DiagnosticMeasurement {
+                time: Instant::now(),
+                value: 3.0,
+            });
+
+        let mut app = App::new();
+        app.insert_resource(store)
+            .insert_resource(DiagWatches(BTreeSet::from(["players".to_owned()])))
+            .insert_resource(ConsoleConfiguration {
+                watch_interval: 1,
+                ..default()
+            })
+            .init_resource::<ConsoleState>()
+            .add_systems(Update, update_diag_watches);
+        app.update();
+
+        let state = app.world().resource::<ConsoleState>();
+        assert!(state.pinned["diag players"]
+            .line
+            .starts_with("players: 3.00"));
+    }
+}
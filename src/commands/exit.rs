@@ -8,13 +8,13 @@ use crate::ConsoleCommand;
 /// Exits the app
 #[derive(Parser, ConsoleCommand)]
 #[command(name = "exit")]
-pub(crate) struct ExitCommand;
+pub struct ExitCommand;
 
 pub(crate) fn exit_command(
     mut exit: ConsoleCommand<ExitCommand>,
     mut exit_writer: EventWriter<AppExit>,
 ) {
-    if let Some(Ok(_)) = exit.take() {
+    while let Some(Ok(_)) = exit.take() {
         exit_writer.send(AppExit::Success);
         exit.ok();
     }
@@ -0,0 +1,195 @@
+use bevy::ecs::entity::Entities;
+use bevy::ecs::reflect::ReflectCommandExt;
+use bevy::prelude::*;
+use bevy::reflect::std_traits::ReflectDefault;
+use clap::Parser;
+
+use crate as bevy_console;
+use crate::{reply, ConsoleCommand, ConsoleLocale};
+
+/// Spawns a scene file (`.scn.ron`) or an entity with a reflected component
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "spawn")]
+pub struct SpawnCommand {
+    /// Scene asset path, or name of a component implementing `Default`
+    scene_or_type: String,
+}
+
+/// Despawns an entity and its children
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "despawn")]
+pub struct DespawnCommand {
+    /// Entity, e.g. `12v1` or `12`
+    #[arg(value_parser = parse_entity)]
+    entity: Entity,
+}
+
+/// Moves an entity to the given translation
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "tp")]
+pub struct TeleportCommand {
+    /// Entity, e.g. `12v1` or `12`
+    #[arg(value_parser = parse_entity)]
+    entity: Entity,
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+/// Makes an entity the child of another
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "parent")]
+pub struct ParentCommand {
+    /// Child entity
+    #[arg(value_parser = parse_entity)]
+    child: Entity,
+    /// New parent entity
+    #[arg(value_parser = parse_entity)]
+    parent: Entity,
+}
+
+/// Parses an entity from its `<index>v<generation>` display form, or just its index
+fn parse_entity(value: &str) -> Result<Entity, String> {
+    let (index, generation) = value.split_once('v').unwrap_or((value, "1"));
+    let index = index
+        .parse::<u32>()
+        .map_err(|_| format!("invalid entity index `{index}`"))?;
+    let generation = generation
+        .parse::<u32>()
+        .ok()
+        .filter(|generation| (1..=0x7fff_ffff).contains(generation))
+        .ok_or_else(|| format!("invalid entity generation `{generation}`"))?;
+    Ok(Entity::from_bits(
+        (u64::from(generation) << 32) | u64::from(index),
+    ))
+}
+
+pub(crate) fn spawn_command(
+    mut spawn: ConsoleCommand<SpawnCommand>,
+    mut commands: Commands,
+    registry: Res<AppTypeRegistry>,
+    asset_server: Option<Res<AssetServer>>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(SpawnCommand { scene_or_type })) = spawn.take() {
+        if scene_or_type.ends_with(".scn.ron") {
+            let Some(asset_server) = asset_server.as_deref() else {
+                spawn.reply_failed(locale.format(
+                    "missing-resource",
+                    &[("resource", "AssetServer"), ("plugin", "AssetPlugin")],
+                ));
+                continue;
+            };
+            let entity = commands
+                .spawn(DynamicSceneBundle {
+                    scene: asset_server.load(scene_or_type),
+                    ..default()
+                })
+                .id();
+            reply!(spawn, "Spawned scene {entity}");
+            spawn.ok();
+            continue;
+        }
+
+        let registry = registry.read();
+        let Some(registration) = registry
+            .get_with_short_type_path(&scene_or_type)
+            .or_else(|| registry.get_with_type_path(&scene_or_type))
+        else {
+            spawn.reply_failed(locale.format("type-unknown", &[("type", &scene_or_type)]));
+            continue;
+        };
+        if registration.data::<ReflectComponent>().is_none() {
+            spawn.reply_failed(locale.format("type-not-component", &[("type", &scene_or_type)]));
+            continue;
+        }
+        let Some(default) = registration.data::<ReflectDefault>() else {
+            spawn.reply_failed(locale.format("type-no-default", &[("type", &scene_or_type)]));
+            continue;
+        };
+
+        let entity = commands
+            .spawn_empty()
+            .insert_reflect(default.default())
+            .id();
+        reply!(spawn, "Spawned {entity}");
+        spawn.ok();
+    }
+}
+
+pub(crate) fn despawn_command(
+    mut despawn: ConsoleCommand<DespawnCommand>,
+    mut commands: Commands,
+    entities: &Entities,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(DespawnCommand { entity })) = despawn.take() {
+        if !entities.contains(entity) {
+            despawn
+                .reply_failed(locale.format("entity-unknown", &[("entity", &entity.to_string())]));
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+        despawn.ok();
+    }
+}
+
+pub(crate) fn teleport_command(
+    mut tp: ConsoleCommand<TeleportCommand>,
+    mut transforms: Query<&mut Transform>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(TeleportCommand { entity, x, y, z })) = tp.take() {
+        match transforms.get_mut(entity) {
+            Ok(mut transform) => {
+                transform.translation = Vec3::new(x, y, z);
+                tp.ok();
+            }
+            Err(_) => tp.reply_failed(
+                locale.format("entity-no-transform", &[("entity", &entity.to_string())]),
+            ),
+        }
+    }
+}
+
+pub(crate) fn parent_command(
+    mut parent: ConsoleCommand<ParentCommand>,
+    mut commands: Commands,
+    entities: &Entities,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(ParentCommand {
+        child,
+        parent: new_parent,
+    })) = parent.take()
+    {
+        if let Some(missing) = [child, new_parent]
+            .into_iter()
+            .find(|entity| !entities.contains(*entity))
+        {
+            parent
+                .reply_failed(locale.format("entity-unknown", &[("entity", &missing.to_string())]));
+            continue;
+        }
+        if child == new_parent {
+            parent.reply_failed(locale.get("entity-own-parent"));
+            continue;
+        }
+        commands.entity(child).set_parent(new_parent);
+        parent.ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entity() {
+        let entity = parse_entity("12v3").unwrap();
+        assert_eq!((entity.index(), entity.generation()), (12, 3));
+        assert_eq!(parse_entity("7"), Ok(Entity::from_raw(7)));
+        assert!(parse_entity("7v0").is_err());
+        assert!(parse_entity("abc").is_err());
+    }
+}
@@ -2,51 +2,108 @@ use bevy::prelude::*;
 use clap::Parser;
 
 use crate as bevy_console;
-use crate::{reply, ConsoleCommand, ConsoleConfiguration};
+use crate::console::command_group;
+use crate::{reply, ConsoleCommand, ConsoleConfiguration, ConsoleLocale};
 
 /// Prints available arguments and usage
 #[derive(Parser, ConsoleCommand)]
 #[command(name = "help")]
-pub(crate) struct HelpCommand {
-    /// Help for a given command
+pub struct HelpCommand {
+    /// Help for a given command or command group
     command: Option<String>,
 }
 
 pub(crate) fn help_command(
     mut help: ConsoleCommand<HelpCommand>,
     mut config: ResMut<ConsoleConfiguration>,
+    locale: Res<ConsoleLocale>,
 ) {
-    match help.take() {
-        Some(Ok(HelpCommand { command: Some(cmd) })) => match config.commands.get_mut(cmd.as_str())
-        {
-            Some(command_info) => {
-                help.reply(command_info.render_long_help().to_string());
+    while let Some(Ok(HelpCommand { command })) = help.take() {
+        match command {
+            Some(cmd) => {
+                let is_group = config
+                    .commands
+                    .keys()
+                    .any(|name| command_group(name) == Some(cmd.as_str()));
+                match config.commands.get_mut(cmd.as_str()) {
+                    Some(command_info) => {
+                        help.reply(command_info.render_long_help().ansi().to_string());
+                        if let Some(examples) = config.examples.get(cmd.as_str()) {
+                            help.reply(locale.get("help-examples"));
+                            for example in examples {
+                                help.reply(format!("  {example}"));
+                            }
+                        }
+                    }
+                    None if is_group => {
+                        reply_group(&mut help, &config, &cmd);
+                        help.reply("");
+                    }
+                    None => {
+                        help.reply(locale.format("help-unknown-command", &[("command", &cmd)]));
+                    }
+                }
             }
             None => {
-                reply!(help, "Command '{}' does not exist", cmd);
-            }
-        },
-        Some(Ok(HelpCommand { command: None })) => {
-            debug!("No command received in help");
-            reply!(help, "Available commands:");
-            let longest_command_name = config
-                .commands
-                .keys()
-                .map(|name| name.len())
-                .max()
-                .unwrap_or(0);
-            for (name, cmd) in &config.commands {
-                let mut line = format!("  {name}{}", " ".repeat(longest_command_name - name.len()));
-                line.push_str(&format!(
-                    " - {}",
-                    cmd.get_about()
-                        .map(|about| about.to_string())
-                        .unwrap_or_default()
-                ));
-                help.reply(line);
+                debug!("No command received in help");
+                help.reply(locale.get("help-available-commands"));
+                reply_commands(
+                    &mut help,
+                    &config,
+                    config
+                        .commands
+                        .keys()
+                        .copied()
+                        .filter(|name| command_group(name).is_none()),
+                );
+
+                let mut groups = config
+                    .commands
+                    .keys()
+                    .filter_map(|name| command_group(name))
+                    .collect::<Vec<_>>();
+                groups.dedup();
+                for group in groups {
+                    reply_group(&mut help, &config, group);
+                }
+                help.reply("");
             }
-            help.reply("");
         }
-        _ => {}
+    }
+}
+
+/// Prints the header of a command group followed by its commands
+fn reply_group(help: &mut ConsoleCommand<HelpCommand>, config: &ConsoleConfiguration, group: &str) {
+    match config.groups.get(group) {
+        Some(info) => reply!(help, "{group} - {}", info.about),
+        None => reply!(help, "{group}"),
+    }
+    reply_commands(
+        help,
+        config,
+        config
+            .commands
+            .keys()
+            .copied()
+            .filter(|name| command_group(name) == Some(group)),
+    );
+}
+
+fn reply_commands<'a>(
+    help: &mut ConsoleCommand<HelpCommand>,
+    config: &ConsoleConfiguration,
+    names: impl Iterator<Item = &'a str> + Clone,
+) {
+    let longest_command_name = names.clone().map(|name| name.len()).max().unwrap_or(0);
+    for name in names {
+        let cmd = &config.commands[name];
+        let mut line = format!("  {name}{}", " ".repeat(longest_command_name - name.len()));
+        line.push_str(&format!(
+            " - {}",
+            cmd.get_about()
+                .map(|about| about.to_string())
+                .unwrap_or_default()
+        ));
+        help.reply(line);
     }
 }
@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use clap::Parser;
+
+use crate as bevy_console;
+#[cfg(feature = "ui")]
+use crate::console::{ConsoleState, ManPage};
+use crate::markdown::markdown_lines;
+#[cfg(feature = "ui")]
+use crate::CommandSource;
+use crate::{ConsoleCommand, ConsoleConfiguration, ConsoleLocale, PrintConsoleLine};
+
+/// Shows the documentation of a command, its long description rendered as markdown followed by
+/// its arguments
+///
+/// The console window shows the page instead of the scrollback until Escape is pressed, other
+/// frontends print it.
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "man")]
+#[console_command(example = "man grep")]
+pub struct ManCommand {
+    /// Command to document
+    command: String,
+}
+
+pub(crate) fn man_command(
+    mut man: ConsoleCommand<ManCommand>,
+    config: Res<ConsoleConfiguration>,
+    locale: Res<ConsoleLocale>,
+    #[cfg(feature = "ui")] mut state: ResMut<ConsoleState>,
+) {
+    while let Some(Ok(ManCommand { command })) = man.take() {
+        let Some(info) = config.commands.get(command.as_str()) else {
+            man.reply_failed(locale.format("help-unknown-command", &[("command", &command)]));
+            continue;
+        };
+        let lines = man_page(info);
+
+        #[cfg(feature = "ui")]
+        if man.source() == Some(&CommandSource::LocalUi) {
+            state.man_page = Some(ManPage { command, lines });
+            man.ok();
+            continue;
+        }
+        for line in lines {
+            match line.styled {
+                Some(styled) => man.reply_styled(styled),
+                None => man.reply(line.line),
+            }
+        }
+        man.ok();
+    }
+}
+
+/// Lines of the manual page of a command: its `long_about` as markdown, then clap's help
+/// without the description
+pub(crate) fn man_page(command: &clap::Command) -> Vec<PrintConsoleLine> {
+    let about = command
+        .get_long_about()
+        .or(command.get_about())
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let mut lines = markdown_lines(&about)
+        .into_iter()
+        .map(PrintConsoleLine::from_styled)
+        .collect::<Vec<_>>();
+    lines.push(PrintConsoleLine::new(""));
+
+    let help = command
+        .clone()
+        .about(None::<&str>)
+        .long_about(None::<&str>)
+        .render_long_help()
+        .ansi()
+        .to_string();
+    lines.extend(
+        help.lines()
+            .map(|line| PrintConsoleLine::new(line.to_owned())),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    /// Spawns enemies
+    ///
+    /// Spawns **count** enemies at the `cursor`:
+    ///
+    /// - in front of the player
+    /// - never inside walls
+    #[derive(Parser)]
+    #[command(name = "spawn", verbatim_doc_comment)]
+    struct SpawnCommand {
+        /// Number of enemies
+        count: usize,
+    }
+
+    #[test]
+    fn test_man_page() {
+        let lines = man_page(&SpawnCommand::command().no_binary_name(true));
+        let texts = lines
+            .iter()
+            .map(|line| crate::strip_ansi(&line.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            texts[..6],
+            [
+                "Spawns enemies",
+                "",
+                "Spawns count enemies at the cursor:",
+                "",
+                "• in front of the player",
+                "• never inside walls",
+            ]
+        );
+        assert!(lines[2].styled.as_ref().unwrap().spans[1].bold);
+        assert!(texts.iter().any(|line| line.starts_with("Usage: ")));
+        assert!(texts.iter().any(|line| line.contains("Number of enemies")));
+        assert_eq!(
+            texts.iter().filter(|line| line.contains("Spawns")).count(),
+            2
+        );
+    }
+}
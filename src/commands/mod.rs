@@ -1,3 +1,32 @@
+#[cfg(feature = "audio_commands")]
+pub(crate) mod audio;
+pub(crate) mod channel;
 pub(crate) mod clear;
+pub(crate) mod console;
+pub(crate) mod diagnostics;
 pub(crate) mod exit;
+#[cfg(feature = "gameplay_commands")]
+pub(crate) mod gameplay;
 pub(crate) mod help;
+pub(crate) mod man;
+pub(crate) mod perf;
+pub(crate) mod pin;
+// macros and jobs are only run by the dispatch systems, which `release_disabled` leaves out
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+pub(crate) mod record;
+#[cfg(feature = "render_commands")]
+pub(crate) mod render;
+pub(crate) mod say;
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+pub(crate) mod schedule;
+pub(crate) mod scroll;
+pub(crate) mod search;
+#[cfg(feature = "replicon")]
+pub(crate) mod server;
+#[cfg(feature = "serde")]
+pub(crate) mod snapshot;
+pub(crate) mod state;
+pub(crate) mod time;
+pub(crate) mod var;
+pub(crate) mod version;
+pub(crate) mod watch;
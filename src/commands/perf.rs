@@ -0,0 +1,177 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use bevy::app::MainScheduleOrder;
+use bevy::diagnostic::{DiagnosticPath, DiagnosticsStore};
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::utils::Instant;
+use clap::Parser;
+
+use crate as bevy_console;
+use crate::{reply, ConsoleCommand, ConsoleLocale};
+
+/// Number of frames of schedule timings kept around
+const MAX_FRAMES: usize = 600;
+
+/// Paths of the `SystemInformationDiagnosticsPlugin` diagnostics, which is only
+/// available with bevy's `sysinfo_plugin` feature
+const CPU_USAGE: DiagnosticPath = DiagnosticPath::const_new("system/cpu_usage");
+const MEM_USAGE: DiagnosticPath = DiagnosticPath::const_new("system/mem_usage");
+
+/// Prints CPU and memory usage, and the most expensive schedules over the last frames.
+///
+/// CPU and memory usage require the `SystemInformationDiagnosticsPlugin`.
+/// Schedules added to the main schedule order after the console plugin are
+/// counted towards the schedule running before them.
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "perf")]
+pub struct PerfCommand {
+    /// Number of frames to average over
+    #[arg(default_value_t = 60, value_parser = clap::value_parser!(u16).range(1..=MAX_FRAMES as i64))]
+    frames: u16,
+}
+
+/// Marks the end of a main schedule, or the start of the frame for `None`
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct PerfMark(Option<InternedScheduleLabel>);
+
+/// Time spent in each main schedule over the last [`MAX_FRAMES`] frames
+#[derive(Default, Resource)]
+pub(crate) struct ScheduleTimings {
+    last_mark: Option<Instant>,
+    /// Schedule names and timings in run order
+    schedules: Vec<(String, VecDeque<Duration>)>,
+}
+
+impl ScheduleTimings {
+    fn record(&mut self, schedule: &str) {
+        let now = Instant::now();
+        let Some(last_mark) = self.last_mark.replace(now) else {
+            return;
+        };
+        let timings = match self.schedules.iter().position(|(name, _)| name == schedule) {
+            Some(index) => &mut self.schedules[index].1,
+            None => {
+                self.schedules.push((schedule.to_owned(), VecDeque::new()));
+                &mut self.schedules.last_mut().unwrap().1
+            }
+        };
+        if timings.len() == MAX_FRAMES {
+            timings.pop_front();
+        }
+        timings.push_back(now - last_mark);
+    }
+
+    /// Average and maximum time of each schedule over the last frames, most expensive first
+    fn summary(&self, frames: usize) -> Vec<(&str, Duration, Duration)> {
+        let mut summary = self
+            .schedules
+            .iter()
+            .filter(|(_, timings)| !timings.is_empty())
+            .map(|(name, timings)| {
+                let recent = timings.iter().rev().take(frames);
+                let count = recent.len() as u32;
+                let total = recent.clone().sum::<Duration>();
+                let max = recent.max().copied().unwrap_or_default();
+                (name.as_str(), total / count, max)
+            })
+            .collect::<Vec<_>>();
+        summary.sort_by_key(|(_, average, _)| std::cmp::Reverse(*average));
+        summary
+    }
+}
+
+/// Measures the main schedules by running a [`PerfMark`] schedule after each of them
+pub(crate) fn instrument_schedules(app: &mut App) {
+    let Some(mut order) = app.world_mut().get_resource_mut::<MainScheduleOrder>() else {
+        return;
+    };
+    let labels = std::mem::take(&mut order.labels);
+    order.labels.push(PerfMark(None).intern());
+    for label in &labels {
+        order.labels.push(*label);
+        order.labels.push(PerfMark(Some(*label)).intern());
+    }
+
+    app.init_resource::<ScheduleTimings>().add_systems(
+        PerfMark(None),
+        |mut timings: ResMut<ScheduleTimings>| {
+            timings.last_mark = Some(Instant::now());
+        },
+    );
+    for label in labels {
+        let name = format!("{label:?}");
+        app.add_systems(
+            PerfMark(Some(label)),
+            move |mut timings: ResMut<ScheduleTimings>| timings.record(&name),
+        );
+    }
+}
+
+pub(crate) fn perf_command(
+    mut perf: ConsoleCommand<PerfCommand>,
+    store: Option<Res<DiagnosticsStore>>,
+    timings: Res<ScheduleTimings>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(PerfCommand { frames })) = perf.take() {
+        let system_information = store.as_ref().and_then(|store| {
+            let cpu = store.get(&CPU_USAGE)?;
+            let mem = store.get(&MEM_USAGE)?;
+            Some((cpu.smoothed()?, mem.smoothed()?))
+        });
+        match system_information {
+            Some((cpu, mem)) => reply!(perf, "cpu: {cpu:.1}%  mem: {mem:.1}%"),
+            None => reply!(
+                perf,
+                "cpu/mem: unavailable, add SystemInformationDiagnosticsPlugin to your app"
+            ),
+        }
+
+        let summary = timings.summary(frames.into());
+        if summary.is_empty() {
+            perf.reply_failed(locale.get("perf-no-timings"));
+            continue;
+        }
+        let width = summary
+            .iter()
+            .map(|(name, ..)| name.len())
+            .max()
+            .unwrap_or_default();
+        reply!(perf, "{:width$}  {:>9}  {:>9}", "schedule", "avg", "max");
+        for (name, average, max) in summary {
+            reply!(
+                perf,
+                "{name:width$}  {:>7.3}ms  {:>7.3}ms",
+                average.as_secs_f64() * 1000.0,
+                max.as_secs_f64() * 1000.0
+            );
+        }
+        perf.ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_timings() {
+        let mut app = App::new();
+        instrument_schedules(&mut app);
+        app.add_systems(Update, || std::thread::sleep(Duration::from_millis(5)));
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let timings = app.world().resource::<ScheduleTimings>();
+        let summary = timings.summary(2);
+        let (name, average, max) = summary[0];
+        assert_eq!(name, "Update");
+        assert!(average >= Duration::from_millis(5));
+        assert!(max >= average);
+        assert!(summary.iter().any(|(name, ..)| *name == "First"));
+        assert_eq!(timings.schedules[0].1.len(), 3);
+    }
+}
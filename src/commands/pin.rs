@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use clap::Parser;
+
+use crate as bevy_console;
+use crate::console::ConsoleState;
+use crate::{ConsoleCommand, ConsoleLocale};
+
+/// Removes a line pinned to the top of the console, or all pinned lines if none is given
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "unpin")]
+pub struct UnpinCommand {
+    /// Command which pinned the line
+    key: Option<String>,
+}
+
+pub(crate) fn unpin_command(
+    mut unpin: ConsoleCommand<UnpinCommand>,
+    mut state: ResMut<ConsoleState>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(UnpinCommand { key })) = unpin.take() {
+        match key {
+            Some(key) => {
+                if state.pinned.remove(&key).is_some() {
+                    unpin.ok();
+                } else {
+                    unpin.reply_failed(locale.format("pin-unknown", &[("key", &key)]));
+                }
+            }
+            None => {
+                state.pinned.clear();
+                unpin.ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CommandOutcome, CommandSource, CommandStatus, ConsoleCommandEntered, ConsoleConfiguration,
+        PrintConsoleLine,
+    };
+
+    #[test]
+    fn test_unpin() {
+        let mut app = App::new();
+        app.add_event::<ConsoleCommandEntered>()
+            .add_event::<PrintConsoleLine>()
+            .add_event::<CommandOutcome>()
+            .init_resource::<ConsoleConfiguration>()
+            .init_resource::<ConsoleLocale>()
+            .init_resource::<ConsoleState>()
+            .add_systems(Update, unpin_command);
+        app.world_mut()
+            .resource_mut::<ConsoleState>()
+            .pinned
+            .insert(
+                "fps".to_owned(),
+                PrintConsoleLine::new("fps: 60".to_owned()),
+            );
+        let unpin = |key: &str| ConsoleCommandEntered {
+            command_name: "unpin".to_owned(),
+            args: vec![key.to_owned()],
+            source: CommandSource::LocalUi,
+        };
+        app.world_mut()
+            .send_event_batch([unpin("fps"), unpin("fps")]);
+        app.update();
+
+        assert!(app.world().resource::<ConsoleState>().pinned.is_empty());
+        let statuses = app
+            .world()
+            .resource::<Events<CommandOutcome>>()
+            .iter_current_update_events()
+            .map(|outcome| outcome.status)
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, [CommandStatus::Ok, CommandStatus::Failed]);
+    }
+}
@@ -0,0 +1,416 @@
+use std::collections::BTreeMap;
+use std::iter::once;
+use std::mem;
+use std::time::Duration;
+
+use bevy::ecs::event::EventId;
+use bevy::prelude::*;
+use clap::{Parser, Subcommand};
+
+use crate as bevy_console;
+use crate::console::{dispatch_line, AppCommands};
+use crate::middleware::ConsoleMiddlewares;
+use crate::{
+    reply, CommandSource, ConsoleCommand, ConsoleCommandEntered, ConsoleConfiguration,
+    ConsoleLocale, ConsoleSeverity, NamedCommand, PrintConsoleLine,
+};
+
+/// Records the entered commands into a macro played back with `playback`
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "record")]
+#[console_command(example = "record start setup", example = "record stop")]
+pub struct RecordCommand {
+    #[command(subcommand)]
+    subcommand: RecordSubcommand,
+}
+
+#[derive(Subcommand)]
+enum RecordSubcommand {
+    /// Starts recording the commands entered from now on
+    Start {
+        /// Name of the macro, replacing any macro of the same name
+        name: String,
+    },
+    /// Stops recording and saves the macro
+    Stop,
+}
+
+/// Executes the commands of a macro recorded with `record` again
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "playback")]
+#[console_command(
+    example = "playback setup",
+    example = "playback spam --loop 10 --delay 500"
+)]
+pub struct PlaybackCommand {
+    /// Name of the macro
+    name: String,
+    /// Number of times to play the macro
+    #[arg(long = "loop", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    loops: u32,
+    /// Milliseconds to wait between commands, all commands run in the same frame without a delay
+    #[arg(long, default_value_t = 0)]
+    delay: u64,
+}
+
+/// Macros recorded with the `record` command, by name.
+///
+/// Macros are lists of command lines, which can also be added by hand.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_console::ConsoleMacros;
+/// fn add_macros(mut macros: ResMut<ConsoleMacros>) {
+///     macros.insert("setup", vec!["timescale 0.5".to_owned(), "fps".to_owned()]);
+/// }
+/// ```
+#[derive(Debug, Default, Resource)]
+pub struct ConsoleMacros {
+    macros: BTreeMap<String, Vec<String>>,
+    /// Name of the macro being recorded, where `record start` was entered and the commands
+    /// entered there since
+    recording: Option<(String, CommandSource, Vec<String>)>,
+    playbacks: Vec<Playback>,
+    /// Commands sent by playbacks this frame, which aren't recorded again
+    replayed: Vec<usize>,
+}
+
+impl ConsoleMacros {
+    /// Returns the command lines of a macro.
+    pub fn get(&self, name: &str) -> Option<&[String]> {
+        self.macros.get(name).map(Vec::as_slice)
+    }
+
+    /// Adds a macro, replacing any macro of the same name.
+    pub fn insert(&mut self, name: impl Into<String>, lines: Vec<String>) {
+        self.macros.insert(name.into(), lines);
+    }
+
+    /// Removes a macro, returning its command lines.
+    pub fn remove(&mut self, name: &str) -> Option<Vec<String>> {
+        self.macros.remove(name)
+    }
+
+    /// Returns the names of the macros, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.macros.keys().map(String::as_str)
+    }
+
+    /// Returns the name of the macro being recorded, if any.
+    pub fn recording(&self) -> Option<&str> {
+        self.recording.as_ref().map(|(name, _, _)| name.as_str())
+    }
+
+    /// Stops the recording and the playbacks of a remote client or player who disconnected
+    pub(crate) fn stop_from(&mut self, source: &CommandSource) {
+        if matches!(&self.recording, Some((_, recorder, _)) if recorder == source) {
+            self.recording = None;
+        }
+        self.playbacks.retain(|playback| playback.source != *source);
+    }
+
+    /// Keeps a command sent this frame without being entered out of the macro being recorded
+    pub(crate) fn exclude_from_recording(&mut self, sent: Option<EventId<ConsoleCommandEntered>>) {
+        self.replayed.extend(sent.map(|id| id.id));
+    }
+}
+
+/// A macro being played back
+#[derive(Debug)]
+struct Playback {
+    /// Where `playback` was entered, the commands are executed as if entered there
+    source: CommandSource,
+    lines: Vec<String>,
+    next: usize,
+    loops_left: u32,
+    delay: Duration,
+    /// Time the next command is due at
+    due: Duration,
+}
+
+impl Playback {
+    fn next_line(&mut self) -> Option<&str> {
+        if self.next == self.lines.len() {
+            if self.loops_left <= 1 || self.lines.is_empty() {
+                return None;
+            }
+            self.loops_left -= 1;
+            self.next = 0;
+        }
+        self.next += 1;
+        Some(&self.lines[self.next - 1])
+    }
+}
+
+pub(crate) fn record_command(
+    mut record: ConsoleCommand<RecordCommand>,
+    mut macros: ResMut<ConsoleMacros>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(RecordCommand { subcommand })) = record.take() {
+        match subcommand {
+            RecordSubcommand::Start { name } => {
+                if let Some(recording) = macros.recording() {
+                    record.reply_failed(
+                        locale.format("record-already-recording", &[("name", recording)]),
+                    );
+                    continue;
+                }
+                let source = record.source().cloned().unwrap_or_default();
+                macros.recording = Some((name, source, Vec::new()));
+                record.ok();
+            }
+            RecordSubcommand::Stop => {
+                let Some((name, _, lines)) = macros.recording.take() else {
+                    record.reply_failed(locale.get("record-not-recording"));
+                    continue;
+                };
+                reply!(record, "Recorded {} commands as `{name}`", lines.len());
+                macros.insert(name, lines);
+                record.ok();
+            }
+        }
+    }
+}
+
+pub(crate) fn playback_command(
+    mut playback: ConsoleCommand<PlaybackCommand>,
+    mut macros: ResMut<ConsoleMacros>,
+    time: Res<Time<Real>>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(PlaybackCommand { name, loops, delay })) = playback.take() {
+        let Some(lines) = macros.get(&name) else {
+            playback.reply_failed(locale.format("macro-unknown", &[("name", &name)]));
+            continue;
+        };
+        let lines = lines.to_vec();
+        macros.playbacks.push(Playback {
+            source: playback.source().cloned().unwrap_or_default(),
+            lines,
+            next: 0,
+            loops_left: loops,
+            delay: Duration::from_millis(delay),
+            due: time.elapsed(),
+        });
+        playback.ok();
+    }
+}
+
+/// Dispatches the commands of the macros being played back which are due
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn play_macros(
+    mut macros: ResMut<ConsoleMacros>,
+    time: Res<Time<Real>>,
+    config: Res<ConsoleConfiguration>,
+    app_commands: Res<AppCommands>,
+    locale: Res<ConsoleLocale>,
+    mut middlewares: ResMut<ConsoleMiddlewares>,
+    mut command_entered: EventWriter<ConsoleCommandEntered>,
+    mut console_line: EventWriter<PrintConsoleLine>,
+) {
+    if macros.playbacks.is_empty() {
+        return;
+    }
+    let now = time.elapsed();
+    let ConsoleMacros {
+        playbacks,
+        replayed,
+        ..
+    } = &mut *macros;
+    playbacks.retain_mut(|playback| {
+        while playback.due <= now {
+            let source = playback.source.clone();
+            let Some(line) = playback.next_line() else {
+                return false;
+            };
+            match dispatch_line(
+                line,
+                source.clone(),
+                &config,
+                &app_commands,
+                &locale,
+                &mut middlewares,
+                &mut command_entered,
+            ) {
+                Ok(sent) => replayed.extend(sent.map(|id| id.id)),
+                Err(reason) => {
+                    console_line.send(
+                        PrintConsoleLine::new(reason)
+                            .with_severity(ConsoleSeverity::Error)
+                            .with_target(source),
+                    );
+                }
+            }
+            playback.due += playback.delay;
+        }
+        true
+    });
+}
+
+/// Adds the commands entered where the recording started to the macro being recorded, except
+/// those played back
+pub(crate) fn record_macros(
+    mut macros: ResMut<ConsoleMacros>,
+    mut entered: EventReader<ConsoleCommandEntered>,
+) {
+    let replayed = mem::take(&mut macros.replayed);
+    let Some((_, recorder, lines)) = &mut macros.recording else {
+        entered.clear();
+        return;
+    };
+    for (command, id) in entered.read_with_id() {
+        if command.source != *recorder
+            || replayed.contains(&id.id)
+            || command.command_name == RecordCommand::name()
+        {
+            continue;
+        }
+        let words = once(&command.command_name).chain(&command.args);
+        if let Ok(line) = shlex::try_join(words.map(String::as_str)) {
+            lines.push(line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_app;
+    use crate::ConsoleSet;
+
+    fn submit(app: &mut App, line: &str) {
+        submit_from(app, line, CommandSource::Terminal);
+    }
+
+    fn submit_from(app: &mut App, line: &str, source: CommandSource) {
+        let mut args = shlex::split(line).unwrap();
+        app.world_mut().send_event(ConsoleCommandEntered {
+            command_name: args.remove(0),
+            args,
+            source,
+        });
+    }
+
+    fn entered(app: &App, name: &str) -> Vec<CommandSource> {
+        let events = app.world().resource::<Events<ConsoleCommandEntered>>();
+        events
+            .iter_current_update_events()
+            .filter(|command| command.command_name == name)
+            .map(|command| command.source.clone())
+            .collect()
+    }
+
+    fn app() -> App {
+        let mut app = test_app();
+        app.add_plugins(bevy::time::TimePlugin).add_systems(
+            Update,
+            (
+                (play_macros, record_macros)
+                    .chain()
+                    .before(ConsoleSet::Commands),
+                (record_command, playback_command).in_set(ConsoleSet::Commands),
+            ),
+        );
+        app
+    }
+
+    #[test]
+    fn test_record_and_playback() {
+        let mut app = app();
+        submit(&mut app, "record start greet");
+        app.update();
+        submit(&mut app, "say 'hello there'");
+        app.update();
+        submit(&mut app, "record stop");
+        app.update();
+        let macros = app.world().resource::<ConsoleMacros>();
+        assert_eq!(macros.get("greet").unwrap(), ["say 'hello there'"]);
+        assert_eq!(macros.recording(), None);
+
+        // played back commands aren't recorded again
+        submit(&mut app, "record start twice");
+        app.update();
+        submit(&mut app, "playback greet --loop 2");
+        app.update();
+        app.update();
+        // once entered, twice played back from where `playback` was entered
+        assert_eq!(entered(&app, "say"), vec![CommandSource::Terminal; 3]);
+        submit(&mut app, "record stop");
+        app.update();
+        let macros = app.world().resource::<ConsoleMacros>();
+        assert_eq!(macros.get("twice").unwrap(), ["playback greet --loop 2"]);
+        assert!(macros.playbacks.is_empty());
+    }
+
+    #[test]
+    fn test_record_only_where_started() {
+        let mut app = app();
+        submit(&mut app, "record start mine");
+        app.update();
+        submit(&mut app, "say mine");
+        submit_from(&mut app, "say theirs", CommandSource::LocalUi);
+        app.update();
+        submit(&mut app, "record stop");
+        app.update();
+        let macros = app.world().resource::<ConsoleMacros>();
+        assert_eq!(macros.get("mine").unwrap(), ["say mine"]);
+    }
+
+    #[test]
+    fn test_playback_needs_a_loop() {
+        assert!(PlaybackCommand::try_parse_from(["playback", "greet", "--loop", "0"]).is_err());
+        assert!(PlaybackCommand::try_parse_from(["playback", "greet", "--loop", "1"]).is_ok());
+    }
+
+    #[cfg(feature = "remote")]
+    #[test]
+    fn test_playback_keeps_permission_of_source() {
+        use std::collections::BTreeSet;
+
+        use crate::RemotePermission;
+
+        let mut app = app();
+        let client = CommandSource::Remote(([127, 0, 0, 1], 7777).into());
+        app.world_mut().resource_mut::<ConsoleMiddlewares>().grant(
+            client.clone(),
+            RemotePermission::Commands(BTreeSet::from(["say".to_owned(), "playback".to_owned()])),
+        );
+        app.world_mut()
+            .resource_mut::<ConsoleMacros>()
+            .insert("admin", vec!["exit".to_owned(), "say hi".to_owned()]);
+        submit_from(&mut app, "playback admin", client.clone());
+        app.update();
+        app.update();
+        assert!(entered(&app, "exit").is_empty());
+        assert_eq!(entered(&app, "say"), std::slice::from_ref(&client));
+        let denied = app
+            .world()
+            .resource::<Events<PrintConsoleLine>>()
+            .iter_current_update_events()
+            .filter(|line| line.severity == ConsoleSeverity::Error)
+            .map(|line| (line.line.to_string(), line.target.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            denied,
+            [(
+                "Permission denied for 'exit'".to_owned(),
+                Some(client.clone())
+            )]
+        );
+
+        // playbacks of a client are stopped once it disconnects
+        submit_from(
+            &mut app,
+            "playback admin --loop 3 --delay 1000",
+            client.clone(),
+        );
+        app.update();
+        let mut macros = app.world_mut().resource_mut::<ConsoleMacros>();
+        assert_eq!(macros.playbacks.len(), 1);
+        macros.stop_from(&client);
+        assert!(macros.playbacks.is_empty());
+    }
+}
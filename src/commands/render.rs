@@ -0,0 +1,138 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::pbr::wireframe::WireframeConfig;
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::window::PrimaryWindow;
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate as bevy_console;
+use crate::{reply, ConsoleCommand, ConsoleLocale};
+
+/// Saves a screenshot of the primary window
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "screenshot")]
+pub struct ScreenshotCommand {
+    /// File to save to, defaults to `screenshot-<timestamp>.png`
+    path: Option<String>,
+}
+
+/// Toggles common rendering settings
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "render")]
+pub struct RenderCommand {
+    #[command(subcommand)]
+    subcommand: RenderSubcommand,
+}
+
+#[derive(Subcommand)]
+enum RenderSubcommand {
+    /// Shows wireframes of all meshes, requires the `WireframePlugin`
+    Wireframe {
+        #[arg(value_enum)]
+        toggle: Toggle,
+    },
+    /// Prints or sets the number of MSAA samples
+    Msaa {
+        /// 1 (off), 2, 4 or 8
+        samples: Option<u32>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Toggle {
+    On,
+    Off,
+}
+
+pub(crate) fn screenshot_command(
+    mut screenshot: ConsoleCommand<ScreenshotCommand>,
+    mut screenshot_manager: Option<ResMut<ScreenshotManager>>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(ScreenshotCommand { path })) = screenshot.take() {
+        let Some(screenshot_manager) = screenshot_manager.as_mut() else {
+            screenshot.reply_failed(locale.format(
+                "missing-resource",
+                &[
+                    ("resource", "ScreenshotManager"),
+                    ("plugin", "RenderPlugin"),
+                ],
+            ));
+            continue;
+        };
+        let Ok(window) = window.get_single() else {
+            screenshot.reply_failed(locale.get("no-primary-window"));
+            continue;
+        };
+
+        let path = path.unwrap_or_else(|| {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+            format!("screenshot-{timestamp}.png")
+        });
+        match screenshot_manager.save_screenshot_to_disk(window, &path) {
+            Ok(()) => {
+                reply!(screenshot, "Saving screenshot to {path}");
+                screenshot.ok();
+            }
+            Err(err) => screenshot.reply_failed(err.to_string()),
+        }
+    }
+}
+
+pub(crate) fn render_command(
+    mut render: ConsoleCommand<RenderCommand>,
+    mut wireframe: Option<ResMut<WireframeConfig>>,
+    mut msaa: Option<ResMut<Msaa>>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(RenderCommand { subcommand })) = render.take() {
+        let Some(msaa) = msaa.as_mut() else {
+            render.reply_failed(locale.format(
+                "missing-resource",
+                &[("resource", "Msaa"), ("plugin", "RenderPlugin")],
+            ));
+            continue;
+        };
+
+        match subcommand {
+            RenderSubcommand::Wireframe { toggle } => {
+                let Some(wireframe) = wireframe.as_mut() else {
+                    render.reply_failed(locale.format(
+                        "missing-resource",
+                        &[
+                            ("resource", "WireframeConfig"),
+                            ("plugin", "WireframePlugin"),
+                        ],
+                    ));
+                    continue;
+                };
+                wireframe.global = matches!(toggle, Toggle::On);
+                render.ok();
+            }
+            RenderSubcommand::Msaa { samples: None } => {
+                reply!(render, "msaa: {}", msaa.samples());
+                render.ok();
+            }
+            RenderSubcommand::Msaa {
+                samples: Some(samples),
+            } => {
+                **msaa = match samples {
+                    1 => Msaa::Off,
+                    2 => Msaa::Sample2,
+                    4 => Msaa::Sample4,
+                    8 => Msaa::Sample8,
+                    _ => {
+                        render.reply_failed(locale.get("msaa-invalid-samples"));
+                        continue;
+                    }
+                };
+                render.ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+use clap::Parser;
+
+use crate as bevy_console;
+use crate::{ConsoleCommand, PrintConsoleLine};
+
+/// Prints a message to everyone, lines typed into the chat box without a command are said
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "say")]
+#[console_command(example = "say hello there")]
+pub struct SayCommand {
+    /// Words of the message, joined with spaces
+    #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    message: Vec<String>,
+}
+
+pub(crate) fn say_command(
+    mut say: ConsoleCommand<SayCommand>,
+    mut console_line: EventWriter<PrintConsoleLine>,
+) {
+    while let Some(Ok(SayCommand { message })) = say.take() {
+        console_line.send(PrintConsoleLine::new(message.join(" ")));
+        say.ok();
+    }
+}
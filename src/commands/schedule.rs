@@ -0,0 +1,424 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use clap::Parser;
+
+use crate as bevy_console;
+use crate::commands::record::ConsoleMacros;
+use crate::console::{dispatch_line, AppCommands};
+use crate::middleware::ConsoleMiddlewares;
+use crate::{
+    reply, CommandSource, ConsoleCommand, ConsoleCommandEntered, ConsoleConfiguration,
+    ConsoleLocale, ConsoleSeverity, PrintConsoleLine,
+};
+
+/// Runs a command once after a delay
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "after")]
+#[console_command(example = "after 5s spawn wave")]
+pub struct AfterCommand {
+    /// Delay before running the command, e.g. `5s` or `1m 30s`
+    delay: humantime::Duration,
+    /// Command to run and its arguments
+    #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+}
+
+/// Runs a command repeatedly until the job is cancelled
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "every")]
+#[console_command(example = "every 10s spawn wave")]
+pub struct EveryCommand {
+    /// Time between runs of the command, e.g. `10s`
+    #[arg(value_parser = parse_interval)]
+    interval: humantime::Duration,
+    /// Command to run and its arguments
+    #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+}
+
+/// Parses the interval of `every`, which would run the command every frame if it was zero
+fn parse_interval(value: &str) -> Result<humantime::Duration, String> {
+    let interval = value
+        .parse::<humantime::Duration>()
+        .map_err(|err| err.to_string())?;
+    if interval.is_zero() {
+        return Err("the interval must be longer than zero".to_owned());
+    }
+    Ok(interval)
+}
+
+/// Lists the commands scheduled with `after` and `every`
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "jobs")]
+pub struct JobsCommand;
+
+/// Cancels a command scheduled with `after` or `every`
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "cancel")]
+#[console_command(example = "cancel 1")]
+pub struct CancelCommand {
+    /// Id of the job, as listed by `jobs`
+    id: u32,
+}
+
+/// Commands scheduled to run later, with the `after` and `every` commands or by hand.
+///
+/// Delays count virtual time, so jobs wait while the game is paused.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use bevy::prelude::*;
+/// # use bevy_console::ConsoleScheduler;
+/// fn start_waves(mut scheduler: ResMut<ConsoleScheduler>) {
+///     scheduler.every(Duration::from_secs(10), "spawn wave");
+/// }
+/// ```
+#[derive(Debug, Default, Resource)]
+pub struct ConsoleScheduler {
+    jobs: Vec<Job>,
+    next_id: u32,
+}
+
+#[derive(Debug)]
+struct Job {
+    id: u32,
+    line: String,
+    /// Where the job was scheduled, the command runs as if entered there
+    source: CommandSource,
+    /// Time left until the command runs
+    remaining: Duration,
+    /// Time between runs of repeated jobs
+    interval: Option<Duration>,
+}
+
+impl ConsoleScheduler {
+    /// Runs the command line once after the delay, as if entered into the console window.
+    ///
+    /// Returns the id of the job.
+    pub fn after(&mut self, delay: Duration, line: impl Into<String>) -> u32 {
+        self.push(line.into(), CommandSource::LocalUi, delay, None)
+    }
+
+    /// Runs the command line repeatedly, as if entered into the console window.
+    ///
+    /// Returns the id of the job.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero, which would run the command every frame.
+    pub fn every(&mut self, interval: Duration, line: impl Into<String>) -> u32 {
+        assert!(!interval.is_zero(), "the interval must be longer than zero");
+        self.push(
+            line.into(),
+            CommandSource::LocalUi,
+            interval,
+            Some(interval),
+        )
+    }
+
+    /// Cancels a job, returns whether it was scheduled.
+    pub fn cancel(&mut self, id: u32) -> bool {
+        let len = self.jobs.len();
+        self.jobs.retain(|job| job.id != id);
+        self.jobs.len() < len
+    }
+
+    /// Cancels the jobs scheduled from a remote client or player who disconnected
+    pub(crate) fn cancel_from(&mut self, source: &CommandSource) {
+        self.jobs.retain(|job| job.source != *source);
+    }
+
+    fn push(
+        &mut self,
+        line: String,
+        source: CommandSource,
+        remaining: Duration,
+        interval: Option<Duration>,
+    ) -> u32 {
+        self.next_id += 1;
+        self.jobs.push(Job {
+            id: self.next_id,
+            line,
+            source,
+            remaining,
+            interval,
+        });
+        self.next_id
+    }
+}
+
+/// Joins the words of a command into a line, checking the command exists and the source of the
+/// job may run it. The permission is checked again when the job runs.
+fn command_line(
+    command: &[String],
+    source: &CommandSource,
+    config: &ConsoleConfiguration,
+    locale: &ConsoleLocale,
+    middlewares: &ConsoleMiddlewares,
+) -> Result<String, String> {
+    if !middlewares.allows(source, &command[0]) {
+        return Err(locale.format("permission-denied", &[("command", &command[0])]));
+    }
+    if !config.commands.contains_key(command[0].as_str()) {
+        return Err(locale.get("invalid-command").to_owned());
+    }
+    shlex::try_join(command.iter().map(String::as_str)).map_err(|err| err.to_string())
+}
+
+pub(crate) fn after_command(
+    mut after: ConsoleCommand<AfterCommand>,
+    mut scheduler: ResMut<ConsoleScheduler>,
+    config: Res<ConsoleConfiguration>,
+    locale: Res<ConsoleLocale>,
+    middlewares: Res<ConsoleMiddlewares>,
+) {
+    while let Some(Ok(AfterCommand { delay, command })) = after.take() {
+        let source = after.source().cloned().unwrap_or_default();
+        match command_line(&command, &source, &config, &locale, &middlewares) {
+            Ok(line) => {
+                let id = scheduler.push(line, source, *delay, None);
+                reply!(after, "job {id}");
+                after.ok();
+            }
+            Err(reason) => after.reply_failed(reason),
+        }
+    }
+}
+
+pub(crate) fn every_command(
+    mut every: ConsoleCommand<EveryCommand>,
+    mut scheduler: ResMut<ConsoleScheduler>,
+    config: Res<ConsoleConfiguration>,
+    locale: Res<ConsoleLocale>,
+    middlewares: Res<ConsoleMiddlewares>,
+) {
+    while let Some(Ok(EveryCommand { interval, command })) = every.take() {
+        let source = every.source().cloned().unwrap_or_default();
+        match command_line(&command, &source, &config, &locale, &middlewares) {
+            Ok(line) => {
+                let id = scheduler.push(line, source, *interval, Some(*interval));
+                reply!(every, "job {id}");
+                every.ok();
+            }
+            Err(reason) => every.reply_failed(reason),
+        }
+    }
+}
+
+pub(crate) fn jobs_command(
+    mut jobs: ConsoleCommand<JobsCommand>,
+    scheduler: Res<ConsoleScheduler>,
+) {
+    while let Some(Ok(JobsCommand)) = jobs.take() {
+        if scheduler.jobs.is_empty() {
+            reply!(jobs, "no jobs scheduled");
+        }
+        for job in &scheduler.jobs {
+            // whole milliseconds are precise enough to read
+            let remaining = Duration::from_millis(job.remaining.as_millis() as u64);
+            let remaining = humantime::format_duration(remaining);
+            match job.interval {
+                Some(interval) => reply!(
+                    jobs,
+                    "{:>3}  every {}  {}  (next in {remaining})",
+                    job.id,
+                    humantime::format_duration(interval),
+                    job.line
+                ),
+                None => reply!(jobs, "{:>3}  after {remaining}  {}", job.id, job.line),
+            }
+        }
+        jobs.ok();
+    }
+}
+
+pub(crate) fn cancel_command(
+    mut cancel: ConsoleCommand<CancelCommand>,
+    mut scheduler: ResMut<ConsoleScheduler>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(CancelCommand { id })) = cancel.take() {
+        if scheduler.cancel(id) {
+            cancel.ok();
+        } else {
+            cancel.reply_failed(locale.format("job-unknown", &[("id", &id.to_string())]));
+        }
+    }
+}
+
+/// Dispatches the commands of the jobs which are due. Repeated jobs whose command can't be
+/// dispatched are cancelled.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_jobs(
+    mut scheduler: ResMut<ConsoleScheduler>,
+    mut macros: ResMut<ConsoleMacros>,
+    time: Res<Time>,
+    config: Res<ConsoleConfiguration>,
+    app_commands: Res<AppCommands>,
+    locale: Res<ConsoleLocale>,
+    mut middlewares: ResMut<ConsoleMiddlewares>,
+    mut command_entered: EventWriter<ConsoleCommandEntered>,
+    mut console_line: EventWriter<PrintConsoleLine>,
+) {
+    if scheduler.jobs.is_empty() {
+        return;
+    }
+    let delta = time.delta();
+    scheduler.jobs.retain_mut(|job| {
+        job.remaining = job.remaining.saturating_sub(delta);
+        if !job.remaining.is_zero() {
+            return true;
+        }
+        match dispatch_line(
+            &job.line,
+            job.source.clone(),
+            &config,
+            &app_commands,
+            &locale,
+            &mut middlewares,
+            &mut command_entered,
+        ) {
+            // scheduled commands aren't entered, so they aren't recorded into macros
+            Ok(sent) => macros.exclude_from_recording(sent),
+            Err(reason) => {
+                let reason = match job.interval {
+                    Some(_) => locale.format(
+                        "job-cancelled",
+                        &[("id", &job.id.to_string()), ("reason", &reason)],
+                    ),
+                    None => reason,
+                };
+                console_line.send(
+                    PrintConsoleLine::new(reason)
+                        .with_severity(ConsoleSeverity::Error)
+                        .with_target(job.source.clone()),
+                );
+                return false;
+            }
+        }
+        match job.interval {
+            Some(interval) => {
+                job.remaining = interval;
+                true
+            }
+            None => false,
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::testing::test_app;
+
+    fn entered(app: &App) -> usize {
+        app.world()
+            .resource::<Events<ConsoleCommandEntered>>()
+            .iter_current_update_events()
+            .filter(|command| command.command_name == "say")
+            .count()
+    }
+
+    #[test]
+    fn test_run_jobs() {
+        let mut app = test_app();
+        app.add_plugins(bevy::time::TimePlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                200,
+            )))
+            .add_systems(Update, run_jobs);
+
+        let mut scheduler = app.world_mut().resource_mut::<ConsoleScheduler>();
+        scheduler.after(Duration::from_millis(400), "say once");
+        let every = scheduler.every(Duration::from_millis(400), "say again");
+        scheduler.every(Duration::from_millis(200), "shout");
+
+        // the first update has no delta
+        app.update();
+        app.update();
+        assert_eq!(entered(&app), 0);
+        app.update();
+        assert_eq!(entered(&app), 2);
+        // the events of the previous updates were dropped, only the repeated job ran again
+        app.update();
+        app.update();
+        assert_eq!(entered(&app), 1);
+
+        // the unknown command was cancelled
+        let mut scheduler = app.world_mut().resource_mut::<ConsoleScheduler>();
+        assert_eq!(scheduler.jobs.len(), 1);
+        assert!(scheduler.cancel(every));
+        assert!(!scheduler.cancel(every));
+    }
+
+    #[test]
+    fn test_every_rejects_zero_interval() {
+        assert!(EveryCommand::try_parse_from(["every", "0s", "say", "hi"]).is_err());
+        assert!(EveryCommand::try_parse_from(["every", "10s", "say", "hi"]).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "the interval must be longer than zero")]
+    fn test_scheduler_every_panics_on_zero_interval() {
+        ConsoleScheduler::default().every(Duration::ZERO, "say hi");
+    }
+
+    #[cfg(feature = "remote")]
+    #[test]
+    fn test_jobs_keep_permission_of_source() {
+        use std::collections::BTreeSet;
+
+        use crate::RemotePermission;
+
+        let mut app = test_app();
+        app.add_plugins(bevy::time::TimePlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                200,
+            )))
+            .add_systems(Update, run_jobs);
+
+        let client = CommandSource::Remote(([127, 0, 0, 1], 7777).into());
+        let gone = CommandSource::Remote(([127, 0, 0, 1], 7778).into());
+        app.world_mut().resource_mut::<ConsoleMiddlewares>().grant(
+            client.clone(),
+            RemotePermission::Commands(BTreeSet::from(["say".to_owned()])),
+        );
+        let mut scheduler = app.world_mut().resource_mut::<ConsoleScheduler>();
+        scheduler.push("exit".to_owned(), client.clone(), Duration::ZERO, None);
+        scheduler.push("say hi".to_owned(), client.clone(), Duration::ZERO, None);
+        scheduler.push("say hi".to_owned(), gone.clone(), Duration::ZERO, None);
+        app.update();
+
+        let entered = app
+            .world()
+            .resource::<Events<ConsoleCommandEntered>>()
+            .iter_current_update_events()
+            .map(|command| (command.command_name.as_str(), command.source.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(entered, [("say", client.clone())]);
+        let denied = app
+            .world()
+            .resource::<Events<PrintConsoleLine>>()
+            .iter_current_update_events()
+            .filter_map(|line| Some((line.line.to_string(), line.target.clone()?)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            denied,
+            [
+                ("Permission denied for 'exit'".to_owned(), client.clone()),
+                ("Permission denied for 'say'".to_owned(), gone)
+            ]
+        );
+
+        // jobs of a client are cancelled once it disconnects
+        let mut scheduler = app.world_mut().resource_mut::<ConsoleScheduler>();
+        scheduler.push("say later".to_owned(), client.clone(), Duration::ZERO, None);
+        scheduler.cancel_from(&client);
+        assert!(scheduler.jobs.is_empty());
+    }
+}
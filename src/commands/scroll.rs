@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use clap::{Parser, ValueEnum};
+
+use crate as bevy_console;
+use crate::{ConsoleCommand, ConsoleScroll};
+
+/// Scrolls the console window to the oldest or the most recent line
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "scroll")]
+#[console_command(example = "scroll top")]
+pub struct ScrollCommand {
+    /// Where to scroll to
+    to: ScrollTo,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ScrollTo {
+    /// The oldest line
+    Top,
+    /// The most recent line
+    Bottom,
+}
+
+pub(crate) fn scroll_command(
+    mut scroll: ConsoleCommand<ScrollCommand>,
+    mut events: EventWriter<ConsoleScroll>,
+) {
+    while let Some(Ok(ScrollCommand { to })) = scroll.take() {
+        events.send(match to {
+            ScrollTo::Top => ConsoleScroll::to_top(),
+            ScrollTo::Bottom => ConsoleScroll::to_bottom(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ScrollTarget;
+    use crate::{CommandOutcome, CommandSource, ConsoleCommandEntered, PrintConsoleLine};
+
+    #[test]
+    fn test_scroll() {
+        let mut app = App::new();
+        app.add_event::<ConsoleCommandEntered>()
+            .add_event::<PrintConsoleLine>()
+            .add_event::<CommandOutcome>()
+            .add_event::<ConsoleScroll>()
+            .add_systems(Update, scroll_command);
+        app.world_mut().send_event(ConsoleCommandEntered {
+            command_name: "scroll".to_owned(),
+            args: vec!["top".to_owned()],
+            source: CommandSource::LocalUi,
+        });
+        app.update();
+
+        let scrolls = app
+            .world()
+            .resource::<Events<ConsoleScroll>>()
+            .iter_current_update_events()
+            .map(|scroll| scroll.0)
+            .collect::<Vec<_>>();
+        assert_eq!(scrolls, [ScrollTarget::Top]);
+    }
+}
@@ -0,0 +1,213 @@
+use bevy::prelude::*;
+use clap::Parser;
+use regex::{Regex, RegexBuilder};
+
+use crate as bevy_console;
+use crate::console::ConsoleState;
+use crate::{reply, strip_ansi, ConsoleCommand, ConsoleConfiguration, ConsoleTab};
+
+/// Prints the scrollback lines matching a regular expression, with their line numbers
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "grep")]
+#[console_command(example = "grep error", example = "grep -i -c net \"timed? ?out\"")]
+pub struct GrepCommand {
+    /// Regular expression, e.g. `warn|error`
+    pattern: String,
+    /// Match regardless of case
+    #[arg(short, long)]
+    ignore_case: bool,
+    /// Only search lines printed to the given channel
+    #[arg(short, long)]
+    channel: Option<String>,
+}
+
+/// Follows the lines of a channel in a new tab, optionally only those matching a regular
+/// expression
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "tail")]
+#[console_command(example = "tail net", example = "tail * \"panicked|error\"")]
+pub struct TailCommand {
+    /// Channel to follow, `*` for every line
+    channel: String,
+    /// Regular expression the followed lines match
+    pattern: Option<String>,
+    /// Match regardless of case
+    #[arg(short, long)]
+    ignore_case: bool,
+}
+
+fn build_regex(pattern: &str, ignore_case: bool) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+}
+
+pub(crate) fn grep_command(mut grep: ConsoleCommand<GrepCommand>, state: Res<ConsoleState>) {
+    while let Some(Ok(GrepCommand {
+        pattern,
+        ignore_case,
+        channel,
+    })) = grep.take()
+    {
+        let regex = match build_regex(&pattern, ignore_case) {
+            Ok(regex) => regex,
+            Err(err) => {
+                grep.reply_failed(err.to_string());
+                continue;
+            }
+        };
+
+        let width = state.scrollback.len().to_string().len();
+        let mut matches = 0;
+        // echoed commands are left out, the grep itself would always match
+        for (index, line) in state.scrollback.iter().enumerate() {
+            if line.is_command || channel.is_some() && line.channel != channel {
+                continue;
+            }
+            let text = strip_ansi(&line.line);
+            if regex.is_match(&text) {
+                reply!(grep, "{:>width$}: {text}", index + 1);
+                matches += 1;
+            }
+        }
+        if matches == 0 {
+            reply!(grep, "No matches");
+        }
+        grep.ok();
+    }
+}
+
+pub(crate) fn tail_command(
+    mut tail: ConsoleCommand<TailCommand>,
+    mut config: ResMut<ConsoleConfiguration>,
+    mut state: ResMut<ConsoleState>,
+) {
+    while let Some(Ok(TailCommand {
+        channel,
+        pattern,
+        ignore_case,
+    })) = tail.take()
+    {
+        let regex = match pattern
+            .as_deref()
+            .map(|pattern| build_regex(pattern, ignore_case))
+        {
+            Some(Err(err)) => {
+                tail.reply_failed(err.to_string());
+                continue;
+            }
+            Some(Ok(regex)) => Some(regex),
+            None => None,
+        };
+
+        let name = match &pattern {
+            Some(pattern) => format!("tail {channel} {pattern}"),
+            None => format!("tail {channel}"),
+        };
+        let index = match config.tabs.iter().position(|tab| tab.name == name) {
+            Some(index) => index,
+            None => {
+                // keep a way back to the whole scrollback
+                if config.tabs.is_empty() {
+                    config.tabs.push(ConsoleTab::all());
+                }
+                let followed = (channel != "*").then_some(channel);
+                config.tabs.push(ConsoleTab::new(name, move |line| {
+                    !line.is_command
+                        && (followed.is_none() || line.channel == followed)
+                        && regex
+                            .as_ref()
+                            .map_or(true, |regex| regex.is_match(&strip_ansi(&line.line)))
+                }));
+                config.tabs.len() - 1
+            }
+        };
+        state.active_tab = index;
+        tail.ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CommandOutcome, CommandSource, ConsoleCommandEntered, PrintConsoleLine};
+
+    fn search_app() -> App {
+        let mut app = App::new();
+        app.add_event::<ConsoleCommandEntered>()
+            .add_event::<PrintConsoleLine>()
+            .add_event::<CommandOutcome>()
+            .init_resource::<ConsoleConfiguration>()
+            .init_resource::<ConsoleState>()
+            .add_systems(Update, (grep_command, tail_command));
+        app.world_mut().resource_mut::<ConsoleState>().scrollback = vec![
+            PrintConsoleLine::new("\x1b[31mconnection timed out\x1b[0m"),
+            PrintConsoleLine::channel("net", "Connected"),
+            PrintConsoleLine::new("grep connect"),
+            PrintConsoleLine::channel("net", "ping 20ms"),
+        ];
+        app.world_mut().resource_mut::<ConsoleState>().scrollback[2].is_command = true;
+        app
+    }
+
+    fn enter(app: &mut App, command: &str, args: &[&str]) {
+        app.world_mut().send_event(ConsoleCommandEntered {
+            command_name: command.to_owned(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            source: CommandSource::LocalUi,
+        });
+        app.update();
+    }
+
+    fn replies(app: &App) -> Vec<String> {
+        app.world()
+            .resource::<Events<PrintConsoleLine>>()
+            .iter_current_update_events()
+            .map(|line| line.line.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_grep() {
+        let mut app = search_app();
+        enter(&mut app, "grep", &["-i", "connect"]);
+        assert_eq!(
+            replies(&app),
+            ["1: connection timed out", "2: Connected", "[ok]"]
+        );
+
+        enter(&mut app, "grep", &["--channel", "net", "connect"]);
+        assert_eq!(replies(&app), ["No matches", "[ok]"]);
+
+        enter(&mut app, "grep", &["("]);
+        assert_eq!(replies(&app)[1], "[failed]");
+    }
+
+    #[test]
+    fn test_tail() {
+        let mut app = search_app();
+        enter(&mut app, "tail", &["net", "ping"]);
+        let config = app.world().resource::<ConsoleConfiguration>();
+        let names = config
+            .tabs
+            .iter()
+            .map(|tab| tab.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["All", "tail net ping"]);
+        let state = app.world().resource::<ConsoleState>();
+        assert_eq!(state.active_tab, 1);
+        let followed = state
+            .scrollback
+            .iter()
+            .filter(|line| config.tabs[1].matches(line))
+            .map(|line| line.line.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(followed, ["ping 20ms"]);
+
+        // following it again switches back to the same tab
+        app.world_mut().resource_mut::<ConsoleState>().active_tab = 0;
+        enter(&mut app, "tail", &["net", "ping"]);
+        assert_eq!(app.world().resource::<ConsoleConfiguration>().tabs.len(), 2);
+        assert_eq!(app.world().resource::<ConsoleState>().active_tab, 1);
+    }
+}
@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use clap::Parser;
+
+use crate as bevy_console;
+use crate::{ConsoleCommand, ConsoleLocale, NetworkedConsoleCommand};
+
+/// Runs a command on the server of a networked game, its replies are printed once they arrive
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "server")]
+#[console_command(example = "server kick bob")]
+pub struct ServerCommand {
+    /// Command to run on the server and its arguments
+    #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    command: Vec<String>,
+}
+
+pub(crate) fn server_command(
+    mut server: ConsoleCommand<ServerCommand>,
+    client: Option<Res<RepliconClient>>,
+    locale: Res<ConsoleLocale>,
+    mut commands: EventWriter<NetworkedConsoleCommand>,
+) {
+    while let Some(Ok(ServerCommand { command })) = server.take() {
+        if !client.as_ref().is_some_and(|client| client.is_connected()) {
+            server.reply_failed(locale.get("server-not-connected"));
+            continue;
+        }
+        match shlex::try_join(command.iter().map(String::as_str)) {
+            Ok(line) => {
+                commands.send(NetworkedConsoleCommand { line });
+                server.ok();
+            }
+            Err(err) => server.reply_failed(err.to_string()),
+        }
+    }
+}
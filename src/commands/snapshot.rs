@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use clap::{Parser, Subcommand};
+
+use crate as bevy_console;
+use crate::console::ConsoleState;
+use crate::{reply, ConsoleCommand, ConsoleSnapshot};
+
+/// Saves the console session to a file or restores it
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "snapshot")]
+#[console_command(
+    example = "snapshot save session.json",
+    example = "snapshot load session.json"
+)]
+pub struct SnapshotCommand {
+    #[command(subcommand)]
+    subcommand: SnapshotSubcommand,
+}
+
+#[derive(Subcommand)]
+enum SnapshotSubcommand {
+    /// Saves the scrollback, history, watches and pinned lines as JSON
+    Save {
+        /// File to save to
+        path: PathBuf,
+    },
+    /// Replaces the session with one saved before
+    Load {
+        /// File to load from
+        path: PathBuf,
+    },
+}
+
+pub(crate) fn snapshot_command(
+    mut snapshot: ConsoleCommand<SnapshotCommand>,
+    mut state: ResMut<ConsoleState>,
+) {
+    while let Some(Ok(SnapshotCommand { subcommand })) = snapshot.take() {
+        match subcommand {
+            SnapshotSubcommand::Save { path } => match ConsoleSnapshot::of(&state).save(&path) {
+                Ok(()) => {
+                    reply!(
+                        snapshot,
+                        "Saved {} lines to {}",
+                        state.scrollback.len(),
+                        path.display()
+                    );
+                    snapshot.ok();
+                }
+                Err(err) => snapshot.reply_failed(format!("{}: {err}", path.display())),
+            },
+            SnapshotSubcommand::Load { path } => match ConsoleSnapshot::load(&path) {
+                // the replies are printed after the restored scrollback
+                Ok(restored) => {
+                    restored.restore_into(&mut state);
+                    reply!(snapshot, "Restored {}", path.display());
+                    snapshot.ok();
+                }
+                Err(err) => snapshot.reply_failed(format!("{}: {err}", path.display())),
+            },
+        }
+    }
+}
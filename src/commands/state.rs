@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use bevy::reflect::{
+    DynamicEnum, DynamicTuple, DynamicVariant, ReflectRef, TypeInfo, TypeRegistration, TypeRegistry,
+};
+use clap::{Parser, Subcommand};
+
+use crate as bevy_console;
+use crate::ConsoleCommand;
+
+/// Lists and changes Bevy states.
+///
+/// States have to derive `Reflect`, and `State<S>` and `NextState<S>` have to be registered
+/// with `app.register_type::<State<S>>().register_type::<NextState<S>>()`.
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "state")]
+pub struct StateCommand {
+    #[command(subcommand)]
+    subcommand: StateSubcommand,
+}
+
+#[derive(Subcommand)]
+enum StateSubcommand {
+    /// Lists all registered states and their current value
+    List,
+    /// Prints the current value of a state
+    Get {
+        /// State type, e.g. `GameState`
+        state: String,
+    },
+    /// Transitions a state to the given variant
+    Set {
+        /// State type, e.g. `GameState`
+        state: String,
+        /// Unit variant to transition to, e.g. `InGame`
+        variant: String,
+    },
+}
+
+pub(crate) fn state_command(mut state: ConsoleCommand<StateCommand>, mut commands: Commands) {
+    while let Some(Ok(StateCommand { subcommand })) = state.take() {
+        state.reply_with_world(&mut commands, move |world| {
+            let registry = world
+                .get_resource::<AppTypeRegistry>()
+                .ok_or("no type registry")?
+                .clone();
+            let registry = registry.read();
+            match subcommand {
+                StateSubcommand::List => {
+                    let mut lines = registry
+                        .iter()
+                        .filter_map(|registration| {
+                            let name = generic_argument(registration, "State")?;
+                            let value = current_state(world, registration).unwrap_or_default();
+                            Some(format!("  {name} = {value}"))
+                        })
+                        .collect::<Vec<_>>();
+                    lines.sort();
+                    if lines.is_empty() {
+                        lines.push("No reflected states registered".to_owned());
+                    }
+                    Ok(lines)
+                }
+                StateSubcommand::Get { state } => {
+                    let registration = find_generic(&registry, "State", &state)?;
+                    current_state(world, registration)
+                        .map(|value| vec![format!("{state} = {value}")])
+                        .ok_or_else(|| format!("state `{state}` does not exist"))
+                }
+                StateSubcommand::Set { state, variant } => {
+                    set_state(world, &registry, &state, &variant)?;
+                    Ok(vec![format!("{state} -> {variant}")])
+                }
+            }
+        });
+    }
+}
+
+/// Returns `S` if the registration is of the `Outer<S>` type
+fn generic_argument<'a>(registration: &'a TypeRegistration, outer: &str) -> Option<&'a str> {
+    registration
+        .type_info()
+        .type_path_table()
+        .short_path()
+        .strip_prefix(outer)?
+        .strip_prefix('<')?
+        .strip_suffix('>')
+}
+
+/// Finds the registration of `Outer<S>` by the short or full path of `S`
+fn find_generic<'a>(
+    registry: &'a TypeRegistry,
+    outer: &str,
+    state: &str,
+) -> Result<&'a TypeRegistration, String> {
+    registry
+        .iter()
+        .find(|registration| {
+            generic_argument(registration, outer) == Some(state)
+                || registration
+                    .type_info()
+                    .type_path_table()
+                    .path()
+                    .ends_with(&format!("::{outer}<{state}>"))
+        })
+        .ok_or_else(|| format!("`{outer}<{state}>` is not registered"))
+}
+
+/// Formats the current value of a reflected `State<S>`
+fn current_state(world: &World, registration: &TypeRegistration) -> Option<String> {
+    let state = registration.data::<ReflectResource>()?.reflect(world)?;
+    let ReflectRef::TupleStruct(state) = state.reflect_ref() else {
+        return None;
+    };
+    let value = state.field(0)?;
+    Some(match value.reflect_ref() {
+        ReflectRef::Enum(value) => value.variant_name().to_owned(),
+        _ => format!("{value:?}"),
+    })
+}
+
+/// Sets `NextState<S>` to `Pending(S::variant)`
+fn set_state(
+    world: &mut World,
+    registry: &TypeRegistry,
+    state: &str,
+    variant: &str,
+) -> Result<(), String> {
+    let next_state = find_generic(registry, "NextState", state)?;
+    let reflect_next_state = next_state
+        .data::<ReflectResource>()
+        .ok_or_else(|| format!("`NextState<{state}>` is not a reflected resource"))?;
+    if reflect_next_state.reflect(world).is_none() {
+        return Err(format!("state `{state}` does not exist"));
+    }
+
+    // check the variant against the state enum
+    let state_info = registry
+        .get_with_short_type_path(state)
+        .or_else(|| registry.get_with_type_path(state))
+        .map(TypeRegistration::type_info);
+    if let Some(TypeInfo::Enum(info)) = state_info {
+        if info.variant(variant).is_none() {
+            let variants = info.variant_names().join(", ");
+            return Err(format!(
+                "`{variant}` is not a variant of `{state}`, expected one of: {variants}"
+            ));
+        }
+    }
+
+    let mut pending = DynamicTuple::default();
+    pending.insert(DynamicEnum::new(variant, DynamicVariant::Unit));
+    let next = DynamicEnum::new("Pending", DynamicVariant::Tuple(pending));
+    reflect_next_state.apply(world, &next);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+
+    #[derive(States, Reflect, Default, Debug, Clone, PartialEq, Eq, Hash)]
+    enum GameState {
+        #[default]
+        Menu,
+        InGame,
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin)
+            .init_state::<GameState>()
+            .register_type::<GameState>()
+            .register_type::<State<GameState>>()
+            .register_type::<NextState<GameState>>();
+        app
+    }
+
+    #[test]
+    fn test_get_and_set_state() {
+        let mut app = app();
+        app.update();
+        let world = app.world_mut();
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        let state = find_generic(&registry, "State", "GameState").unwrap();
+        assert_eq!(current_state(world, state), Some("Menu".to_owned()));
+
+        assert!(set_state(world, &registry, "GameState", "Paused").is_err());
+        set_state(world, &registry, "GameState", "InGame").unwrap();
+        drop(registry);
+        app.update();
+        assert_eq!(
+            *app.world().resource::<State<GameState>>().get(),
+            GameState::InGame
+        );
+    }
+}
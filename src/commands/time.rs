@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use clap::Parser;
+
+use crate as bevy_console;
+use crate::{reply, ConsoleCommand, ConsoleLocale};
+
+/// Prints or sets the speed of virtual time
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "timescale")]
+pub struct TimescaleCommand {
+    /// New relative speed, e.g. `0.5` for half speed
+    scale: Option<f64>,
+}
+
+/// Pauses virtual time
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "pause")]
+pub struct PauseCommand;
+
+/// Resumes virtual time
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "resume")]
+pub struct ResumeCommand;
+
+/// Advances virtual time by a number of frames while paused
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "step")]
+pub struct StepCommand {
+    /// Number of frames to advance
+    #[arg(default_value_t = 1)]
+    frames: u32,
+}
+
+/// Frames left to advance before virtual time is paused again
+#[derive(Default, Resource)]
+pub(crate) struct PendingSteps(u32);
+
+pub(crate) fn timescale_command(
+    mut timescale: ConsoleCommand<TimescaleCommand>,
+    mut time: Option<ResMut<Time<Virtual>>>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(TimescaleCommand { scale })) = timescale.take() {
+        let Some(time) = time.as_mut() else {
+            timescale.reply_failed(no_time(&locale));
+            continue;
+        };
+        match scale {
+            Some(scale) => {
+                if !scale.is_finite() || scale < 0.0 {
+                    timescale.reply_failed(locale.get("timescale-invalid"));
+                    continue;
+                }
+                time.set_relative_speed_f64(scale);
+                timescale.ok();
+            }
+            None => {
+                reply!(timescale, "timescale: {}", time.relative_speed_f64());
+                timescale.ok();
+            }
+        }
+    }
+}
+
+pub(crate) fn pause_command(
+    mut pause: ConsoleCommand<PauseCommand>,
+    mut time: Option<ResMut<Time<Virtual>>>,
+    mut steps: ResMut<PendingSteps>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(_)) = pause.take() {
+        let Some(time) = time.as_mut() else {
+            pause.reply_failed(no_time(&locale));
+            continue;
+        };
+        time.pause();
+        steps.0 = 0;
+        pause.ok();
+    }
+}
+
+pub(crate) fn resume_command(
+    mut resume: ConsoleCommand<ResumeCommand>,
+    mut time: Option<ResMut<Time<Virtual>>>,
+    mut steps: ResMut<PendingSteps>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(_)) = resume.take() {
+        let Some(time) = time.as_mut() else {
+            resume.reply_failed(no_time(&locale));
+            continue;
+        };
+        time.unpause();
+        steps.0 = 0;
+        resume.ok();
+    }
+}
+
+pub(crate) fn step_command(
+    mut step: ConsoleCommand<StepCommand>,
+    mut time: Option<ResMut<Time<Virtual>>>,
+    mut steps: ResMut<PendingSteps>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(StepCommand { frames })) = step.take() {
+        let Some(time) = time.as_mut() else {
+            step.reply_failed(no_time(&locale));
+            continue;
+        };
+        if !time.is_paused() && steps.0 == 0 {
+            step.reply_failed(locale.get("step-not-paused"));
+            continue;
+        }
+        if frames == 0 {
+            step.ok();
+            continue;
+        }
+        steps.0 += frames;
+        time.unpause();
+        step.ok();
+    }
+}
+
+fn no_time(locale: &ConsoleLocale) -> String {
+    locale.format(
+        "missing-resource",
+        &[("resource", "Time<Virtual>"), ("plugin", "TimePlugin")],
+    )
+}
+
+/// Pauses virtual time again once all requested frames advanced,
+/// runs right after time is updated at the start of each frame
+pub(crate) fn gate_steps(time: Option<ResMut<Time<Virtual>>>, mut steps: ResMut<PendingSteps>) {
+    let Some(mut time) = time.filter(|_| steps.0 > 0) else {
+        return;
+    };
+    steps.0 -= 1;
+    if steps.0 == 0 {
+        time.pause();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::{TimePlugin, TimeSystem};
+
+    use super::*;
+
+    #[test]
+    fn test_steps_pause_after_frames() {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .init_resource::<PendingSteps>()
+            .add_systems(First, gate_steps.after(TimeSystem));
+        app.world_mut().resource_mut::<PendingSteps>().0 = 2;
+
+        app.update();
+        assert!(!app.world().resource::<Time<Virtual>>().is_paused());
+        app.update();
+        assert!(app.world().resource::<Time<Virtual>>().is_paused());
+        assert_eq!(app.world().resource::<PendingSteps>().0, 0);
+    }
+}
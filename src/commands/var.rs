@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate as bevy_console;
+use crate::{reply, ConsoleCommand, ConsoleConfiguration, ConsoleLocale};
+
+/// Prints or sets a console variable, see `if`
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "set")]
+#[console_command(example = "set mode hard")]
+pub struct SetCommand {
+    /// Name of the variable
+    name: String,
+    /// New value of the variable
+    value: Option<String>,
+}
+
+/// Runs a command only if a console variable has the given value, e.g. in config scripts.
+///
+/// The `platform` variable holds the operating system the app runs on, e.g. `linux` or `windows`.
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "if")]
+#[console_command(example = "if platform == windows then say hello windows")]
+pub struct IfCommand {
+    /// Name of the variable, unset variables match no value
+    pub(crate) name: String,
+    pub(crate) comparison: Comparison,
+    /// Value compared with the variable
+    pub(crate) value: String,
+    #[command(subcommand)]
+    pub(crate) branch: IfBranch,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum Comparison {
+    /// The variable has the value
+    #[value(name = "==")]
+    Equal,
+    /// The variable doesn't have the value
+    #[value(name = "!=")]
+    NotEqual,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum IfBranch {
+    /// Command run if the comparison holds
+    Then {
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+}
+
+impl IfCommand {
+    /// Returns the command to run, `None` if the comparison doesn't hold
+    pub(crate) fn evaluate(self, config: &ConsoleConfiguration) -> Option<Vec<String>> {
+        let matches = config.vars.get(&self.name) == Some(&self.value);
+        let IfBranch::Then { command } = self.branch;
+        match self.comparison {
+            Comparison::Equal => matches,
+            Comparison::NotEqual => !matches,
+        }
+        .then_some(command)
+    }
+}
+
+pub(crate) fn set_command(
+    mut set: ConsoleCommand<SetCommand>,
+    mut config: ResMut<ConsoleConfiguration>,
+    locale: Res<ConsoleLocale>,
+) {
+    while let Some(Ok(SetCommand { name, value })) = set.take() {
+        match value {
+            Some(value) => {
+                config.vars.insert(name, value);
+                set.ok();
+            }
+            None => match config.vars.get(&name) {
+                Some(value) => {
+                    reply!(set, "{name} = {value}");
+                    set.ok();
+                }
+                None => set.reply_failed(locale.format("var-unset", &[("name", &name)])),
+            },
+        }
+    }
+}
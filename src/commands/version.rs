@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use clap::Parser;
+
+use crate as bevy_console;
+use crate::{reply, ConsoleCommand};
+
+/// Version of bevy this crate is built against
+const BEVY_VERSION: &str = "0.14";
+
+/// Information about the running application, printed by the `version` and `about` commands.
+///
+/// Use [`console_app_info!`](crate::console_app_info) to fill it from the cargo environment of your crate.
+#[derive(Clone, Debug, Resource)]
+pub struct ConsoleAppInfo {
+    /// Application name
+    pub name: String,
+    /// Application version
+    pub version: String,
+    /// Git commit the application was built from
+    pub git_hash: Option<String>,
+    /// Build profile, e.g. `debug` or `release`
+    pub build_profile: String,
+    /// Version of bevy
+    pub bevy_version: String,
+}
+
+impl ConsoleAppInfo {
+    /// Creates app info with the given name and version.
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            git_hash: None,
+            build_profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+            .to_owned(),
+            bevy_version: BEVY_VERSION.to_owned(),
+        }
+    }
+
+    /// Sets the git commit hash.
+    pub fn with_git_hash(mut self, git_hash: Option<impl Into<String>>) -> Self {
+        self.git_hash = git_hash.map(Into::into);
+        self
+    }
+
+    /// Sets the build profile.
+    pub fn with_build_profile(mut self, build_profile: impl Into<String>) -> Self {
+        self.build_profile = build_profile.into();
+        self
+    }
+
+    /// One line summary, e.g. `my_game 1.0.0 (3f2c1ab, release)`
+    pub fn summary(&self) -> String {
+        match &self.git_hash {
+            Some(git_hash) => format!(
+                "{} {} ({git_hash}, {})",
+                self.name, self.version, self.build_profile
+            ),
+            None => format!("{} {} ({})", self.name, self.version, self.build_profile),
+        }
+    }
+}
+
+/// Prints the application version
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "version")]
+pub struct VersionCommand;
+
+/// Prints information about the application and its build
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "about")]
+pub struct AboutCommand;
+
+pub(crate) fn version_command(
+    mut version: ConsoleCommand<VersionCommand>,
+    info: Option<Res<ConsoleAppInfo>>,
+) {
+    while let Some(Ok(_)) = version.take() {
+        match &info {
+            Some(info) => version.reply(info.summary()),
+            None => reply!(version, "bevy_console {}", env!("CARGO_PKG_VERSION")),
+        }
+    }
+}
+
+pub(crate) fn about_command(
+    mut about: ConsoleCommand<AboutCommand>,
+    info: Option<Res<ConsoleAppInfo>>,
+) {
+    while let Some(Ok(_)) = about.take() {
+        if let Some(info) = &info {
+            reply!(about, "name:          {}", info.name);
+            reply!(about, "version:       {}", info.version);
+            if let Some(git_hash) = &info.git_hash {
+                reply!(about, "git hash:      {git_hash}");
+            }
+            reply!(about, "build profile: {}", info.build_profile);
+            reply!(about, "bevy:          {}", info.bevy_version);
+        } else {
+            reply!(about, "bevy:          {BEVY_VERSION}");
+        }
+        reply!(about, "bevy_console:  {}", env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use bevy::reflect::GetPath;
+use clap::Parser;
+
+use crate as bevy_console;
+use crate::console::ConsoleState;
+use crate::{reply, ConsoleCommand, ConsoleConfiguration, ConsoleOpen};
+
+/// Pins the value of a reflected resource field to the top of the console
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "watch")]
+pub struct WatchCommand {
+    /// Resource and field path to watch, e.g. `Time<Real>.elapsed`
+    expr: String,
+}
+
+/// Removes a watch, or all watches if none is given
+#[derive(Parser, ConsoleCommand)]
+#[command(name = "unwatch")]
+pub struct UnwatchCommand {
+    /// Watched expression to remove
+    expr: Option<String>,
+}
+
+pub(crate) fn watch_command(
+    mut watch: ConsoleCommand<WatchCommand>,
+    mut state: ResMut<ConsoleState>,
+) {
+    while let Some(Ok(WatchCommand { expr })) = watch.take() {
+        state.watches.insert(expr, "...".to_owned());
+        watch.ok();
+    }
+}
+
+pub(crate) fn unwatch_command(
+    mut unwatch: ConsoleCommand<UnwatchCommand>,
+    mut state: ResMut<ConsoleState>,
+) {
+    while let Some(Ok(UnwatchCommand { expr })) = unwatch.take() {
+        match expr {
+            Some(expr) => {
+                if state.watches.remove(&expr).is_some() {
+                    unwatch.ok();
+                } else {
+                    reply!(unwatch, "'{}' is not being watched", expr);
+                }
+            }
+            None => {
+                state.watches.clear();
+                unwatch.ok();
+            }
+        }
+    }
+}
+
+/// Whether there are watches shown in the open console
+pub(crate) fn watches_shown(console_open: Res<ConsoleOpen>, state: Res<ConsoleState>) -> bool {
+    console_open.open && !state.watches.is_empty()
+}
+
+/// Re-evaluates all watched expressions every [`ConsoleConfiguration::watch_interval`] frames
+/// while they are shown
+pub(crate) fn update_watches(world: &mut World, mut frames: Local<u32>) {
+    let interval = world
+        .resource::<ConsoleConfiguration>()
+        .watch_interval
+        .max(1);
+    *frames += 1;
+    if *frames < interval {
+        return;
+    }
+    *frames = 0;
+
+    world.resource_scope(|world, mut state: Mut<ConsoleState>| {
+        for (expr, value) in state.watches.iter_mut() {
+            *value = evaluate_watch(world, expr).unwrap_or_else(|err| format!("<{err}>"));
+        }
+    });
+}
+
+/// Splits `Resource.path` at the start of the reflection path and reads the value from the world
+fn evaluate_watch(world: &World, expr: &str) -> Result<String, String> {
+    let (resource_name, path) = expr
+        .find(['.', '[', '#'])
+        .map(|index| expr.split_at(index))
+        .unwrap_or((expr, ""));
+
+    let registry = world
+        .get_resource::<AppTypeRegistry>()
+        .ok_or("no type registry")?
+        .read();
+    let registration = registry
+        .get_with_short_type_path(resource_name)
+        .or_else(|| registry.get_with_type_path(resource_name))
+        .ok_or_else(|| format!("unknown type `{resource_name}`"))?;
+    let reflect_resource = registration
+        .data::<ReflectResource>()
+        .ok_or_else(|| format!("`{resource_name}` is not a reflected resource"))?;
+    let resource = reflect_resource
+        .reflect(world)
+        .ok_or_else(|| format!("resource `{resource_name}` does not exist"))?;
+
+    if path.is_empty() {
+        return Ok(format!("{resource:?}"));
+    }
+    resource
+        .reflect_path(path)
+        .map(|value| format!("{value:?}"))
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Reflect, Default)]
+    #[reflect(Resource)]
+    struct Score {
+        points: u32,
+        names: Vec<String>,
+    }
+
+    fn world_with_score() -> World {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        registry.write().register::<Score>();
+        world.insert_resource(registry);
+        world.insert_resource(Score {
+            points: 42,
+            names: vec!["ferris".to_owned()],
+        });
+        world
+    }
+
+    #[test]
+    fn test_evaluate_field() {
+        let world = world_with_score();
+        assert_eq!(evaluate_watch(&world, "Score.points"), Ok("42".to_owned()));
+        assert_eq!(
+            evaluate_watch(&world, "Score.names[0]"),
+            Ok("\"ferris\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_evaluate_unknown_resource() {
+        let world = world_with_score();
+        assert_eq!(
+            evaluate_watch(&world, "Health.value"),
+            Err("unknown type `Health`".to_owned())
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use clap::ValueHint;
+use shlex::Shlex;
+
+/// The command reached by the words before the one typed at the end of the input
+struct Walk<'a> {
+    /// The innermost subcommand typed
+    command: &'a clap::Command,
+    /// Number of positional arguments typed for `command`
+    positionals: usize,
+    /// The option whose value is being typed
+    pending_option: Option<&'a clap::Arg>,
+    /// Byte offset of the word being typed
+    start: usize,
+}
+
+/// Walks the words before the one typed at the end of the input, descending into subcommands
+fn walk<'a>(commands: &'a BTreeMap<&'static str, clap::Command>, input: &str) -> Option<Walk<'a>> {
+    let start = input
+        .rfind(char::is_whitespace)
+        .map_or(0, |index| index + 1);
+    let mut words = Shlex::new(&input[..start]).collect::<Vec<_>>();
+    if words.is_empty() {
+        return None;
+    }
+    let name = words.remove(0);
+    let mut command = commands.get(name.as_str())?;
+
+    let mut positionals = 0;
+    let mut pending_option = None;
+    for word in &words {
+        if pending_option.take().is_some() {
+            continue;
+        }
+        let option = if let Some(long) = word.strip_prefix("--") {
+            (!long.contains('='))
+                .then(|| command.get_arguments().find(|a| a.get_long() == Some(long)))
+                .flatten()
+        } else if let Some(short) = word.strip_prefix('-').filter(|short| short.len() == 1) {
+            let short = short.chars().next();
+            command.get_arguments().find(|a| a.get_short() == short)
+        } else if positionals == 0 && command.has_subcommands() {
+            match command.find_subcommand(word) {
+                Some(subcommand) => command = subcommand,
+                None => positionals += 1,
+            }
+            continue;
+        } else {
+            positionals += 1;
+            continue;
+        };
+        pending_option = option.filter(|option| option.get_action().takes_values());
+    }
+
+    Some(Walk {
+        command,
+        positionals,
+        pending_option,
+        start,
+    })
+}
+
+/// Finds the argument whose value is being typed at the end of the input,
+/// along with the byte offset the value starts at.
+pub(crate) fn completing_arg<'a>(
+    commands: &'a BTreeMap<&'static str, clap::Command>,
+    input: &str,
+) -> Option<(&'a clap::Arg, usize)> {
+    let Walk {
+        command,
+        positionals,
+        pending_option,
+        start,
+    } = walk(commands, input)?;
+
+    if let Some(option) = pending_option {
+        return Some((option, start));
+    }
+    if input[start..].starts_with('-') {
+        return None;
+    }
+    let mut remaining = positionals;
+    command
+        .get_positionals()
+        .find(|arg| {
+            let multiple = arg
+                .get_num_args()
+                .is_some_and(|range| range.max_values() > 1);
+            if remaining > 0 && !multiple {
+                remaining -= 1;
+                return false;
+            }
+            true
+        })
+        .map(|arg| (arg, start))
+}
+
+/// Byte offset of the completed value in the input, with the values it can be completed to
+pub(crate) type Completions = (usize, Vec<String>);
+
+/// Completes the subcommand or flag typed at the end of the input, e.g. the subcommands of
+/// `asset ` or the flags of `asset load --`, walking the subcommands already typed.
+///
+/// Flags are only offered once a `-` is typed.
+fn subcommand_completions(
+    commands: &BTreeMap<&'static str, clap::Command>,
+    input: &str,
+) -> Option<Completions> {
+    let walk = walk(commands, input)?;
+    if walk.pending_option.is_some() {
+        return None;
+    }
+    let typed = &input[walk.start..];
+    let candidates = if typed.starts_with('-') {
+        walk.command
+            .get_arguments()
+            .filter(|arg| !arg.is_hide_set())
+            .filter_map(|arg| arg.get_long())
+            .map(|long| format!("--{long}"))
+            .collect::<Vec<_>>()
+    } else if walk.positionals == 0 {
+        walk.command
+            .get_subcommands()
+            .filter(|subcommand| !subcommand.is_hide_set())
+            .map(|subcommand| subcommand.get_name().to_owned())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if candidates.is_empty() {
+        return None;
+    }
+    if candidates.iter().any(|candidate| candidate == typed) {
+        return Some((walk.start, candidates));
+    }
+    let completions = candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(typed))
+        .collect();
+    Some((walk.start, completions))
+}
+
+/// Completes the argument value typed at the end of the input, with the possible values of the
+/// argument or file system paths relative to `root` if it has a path [`ValueHint`]. Otherwise
+/// the subcommands or flags of the command typed so far are completed.
+///
+/// Returns the byte offset the value starts at and the completed values. Once the value matches
+/// a possible value, all of them are returned so they can be cycled through.
+pub(crate) fn argument_completions(
+    commands: &BTreeMap<&'static str, clap::Command>,
+    input: &str,
+    root: &Path,
+) -> Option<Completions> {
+    let Some((arg, start)) = completing_arg(commands, input) else {
+        return subcommand_completions(commands, input);
+    };
+    let typed = &input[start..];
+    let values = possible_values(arg);
+    if !values.is_empty() {
+        let completions = if values.iter().any(|value| value == typed) {
+            values
+        } else {
+            values
+                .into_iter()
+                .filter(|value| value.starts_with(typed))
+                .collect()
+        };
+        return Some((start, completions));
+    }
+    path_completions(arg, typed, root)
+        .map(|completions| (start, completions))
+        .or_else(|| subcommand_completions(commands, input))
+}
+
+/// Visible possible values of the argument, e.g. the variants of a `ValueEnum`
+pub(crate) fn possible_values(arg: &clap::Arg) -> Vec<String> {
+    arg.get_possible_values()
+        .into_iter()
+        .filter(|value| !value.is_hide_set())
+        .map(|value| value.get_name().to_owned())
+        .collect()
+}
+
+/// Completes the typed path if the argument has a path [`ValueHint`],
+/// directories end with `/`
+fn path_completions(arg: &clap::Arg, typed: &str, root: &Path) -> Option<Vec<String>> {
+    let dirs_only = match arg.get_value_hint() {
+        ValueHint::DirPath => true,
+        ValueHint::AnyPath | ValueHint::FilePath | ValueHint::ExecutablePath => false,
+        _ => return None,
+    };
+
+    let (dir, prefix) = match typed.rfind('/') {
+        Some(index) => typed.split_at(index + 1),
+        None => ("", typed),
+    };
+    let mut completions = fs::read_dir(root.join(dir))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            // hidden entries are only completed once their dot is typed
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            if is_dir {
+                Some(format!("{dir}{name}/"))
+            } else {
+                (!dirs_only).then(|| format!("{dir}{name}"))
+            }
+        })
+        .collect::<Vec<_>>();
+    completions.sort();
+    Some(completions)
+}
+
+/// Longest prefix shared by all completions
+pub(crate) fn common_prefix(completions: &[String]) -> &str {
+    let Some((first, rest)) = completions.split_first() else {
+        return "";
+    };
+    let mut len = first.len();
+    for completion in rest {
+        len = first
+            .char_indices()
+            .zip(completion.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((index, a), _)| index + a.len_utf8())
+            .min(len);
+    }
+    &first[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_commands;
+    use std::path::PathBuf;
+
+    fn commands() -> BTreeMap<&'static str, clap::Command> {
+        let mut commands = test_commands();
+        commands.extend([
+            (
+                "load",
+                clap::Command::new("load")
+                    .arg(clap::Arg::new("scene").value_hint(ValueHint::FilePath))
+                    .arg(
+                        clap::Arg::new("out")
+                            .long("out")
+                            .value_hint(ValueHint::DirPath),
+                    ),
+            ),
+            (
+                "difficulty",
+                clap::Command::new("difficulty")
+                    .arg(clap::Arg::new("level").value_parser(["easy", "normal", "hard"])),
+            ),
+            (
+                "asset",
+                clap::Command::new("asset")
+                    .subcommand(
+                        clap::Command::new("load")
+                            .arg(clap::Arg::new("path"))
+                            .arg(
+                                clap::Arg::new("force")
+                                    .long("force")
+                                    .action(clap::ArgAction::SetTrue),
+                            )
+                            .arg(clap::Arg::new("label").long("label")),
+                    )
+                    .subcommand(clap::Command::new("list"))
+                    .subcommand(clap::Command::new("debug").hide(true)),
+            ),
+        ]);
+        commands
+    }
+
+    fn root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("bevy_console_paths_{}", std::process::id()));
+        fs::create_dir_all(root.join("scenes")).unwrap();
+        fs::create_dir_all(root.join(".hidden")).unwrap();
+        fs::write(root.join("scenes/level1.scn.ron"), "").unwrap();
+        fs::write(root.join("scenes/level2.scn.ron"), "").unwrap();
+        fs::write(root.join("settings.ron"), "").unwrap();
+        root
+    }
+
+    #[test]
+    fn test_path_completions() {
+        let (commands, root) = (commands(), root());
+        assert_eq!(
+            argument_completions(&commands, "load s", &root),
+            Some((5, vec!["scenes/".to_owned(), "settings.ron".to_owned()]))
+        );
+        assert_eq!(
+            argument_completions(&commands, "load scenes/l", &root),
+            Some((
+                5,
+                vec![
+                    "scenes/level1.scn.ron".to_owned(),
+                    "scenes/level2.scn.ron".to_owned()
+                ]
+            ))
+        );
+        assert_eq!(
+            argument_completions(&commands, "load --out ", &root),
+            Some((11, vec!["scenes/".to_owned()]))
+        );
+        assert_eq!(
+            argument_completions(&commands, "load .", &root),
+            Some((5, vec![".hidden/".to_owned()]))
+        );
+        assert_eq!(argument_completions(&commands, "say s", &root), None);
+        assert_eq!(argument_completions(&commands, "load", &root), None);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_value_completions() {
+        let (commands, root) = (commands(), PathBuf::new());
+        let values = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        assert_eq!(
+            argument_completions(&commands, "difficulty ", &root),
+            Some((11, values(&["easy", "normal", "hard"])))
+        );
+        assert_eq!(
+            argument_completions(&commands, "difficulty h", &root),
+            Some((11, values(&["hard"])))
+        );
+        // a complete value cycles through all of them
+        assert_eq!(
+            argument_completions(&commands, "difficulty hard", &root),
+            Some((11, values(&["easy", "normal", "hard"])))
+        );
+        assert_eq!(
+            argument_completions(&commands, "difficulty x", &root),
+            Some((11, Vec::new()))
+        );
+    }
+
+    #[test]
+    fn test_subcommand_completions() {
+        let (commands, root) = (commands(), PathBuf::new());
+        let values = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        assert_eq!(
+            argument_completions(&commands, "asset ", &root),
+            Some((6, values(&["load", "list"])))
+        );
+        assert_eq!(
+            argument_completions(&commands, "asset lo", &root),
+            Some((6, values(&["load"])))
+        );
+        // a complete subcommand cycles through all of them
+        assert_eq!(
+            argument_completions(&commands, "asset list", &root),
+            Some((6, values(&["load", "list"])))
+        );
+        assert_eq!(
+            argument_completions(&commands, "asset load --", &root),
+            Some((11, values(&["--force", "--label"])))
+        );
+        assert_eq!(
+            argument_completions(&commands, "asset load --force --l", &root),
+            Some((19, values(&["--label"])))
+        );
+        assert_eq!(argument_completions(&commands, "asset load ", &root), None);
+        assert_eq!(
+            argument_completions(&commands, "asset load --label ", &root),
+            None
+        );
+    }
+
+    #[test]
+    fn test_common_prefix() {
+        let completions = [
+            "scenes/level1.scn.ron".to_owned(),
+            "scenes/level2.scn.ron".to_owned(),
+        ];
+        assert_eq!(common_prefix(&completions), "scenes/level");
+        assert_eq!(common_prefix(&completions[..1]), "scenes/level1.scn.ron");
+        assert_eq!(common_prefix(&[]), "");
+    }
+}
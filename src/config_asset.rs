@@ -0,0 +1,260 @@
+use std::fmt;
+
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{ConsoleConfiguration, ConsoleTheme};
+
+/// Console settings loaded from a `.console.ron` or `.console.toml` asset, applied to the
+/// [`ConsoleConfiguration`] whenever the asset is loaded or modified.
+///
+/// Every field is optional, fields left out keep their current value.
+/// Enable bevy's `file_watcher` feature to reload the configuration while the game is running.
+///
+/// ```ron
+/// (
+///     keys: Some([F1]),
+///     width: Some(1000.0),
+///     theme: Some((
+///         background_color: (r: 20, g: 20, b: 30),
+///     )),
+/// )
+/// ```
+///
+/// or in TOML:
+///
+/// ```toml
+/// keys = ["F1"]
+/// width = 1000.0
+///
+/// [theme]
+/// background_color = { r = 20, g = 20, b = 30 }
+/// ```
+#[derive(Asset, Clone, Debug, Default, Deserialize, TypePath)]
+#[serde(default)]
+pub struct ConsoleConfigAsset {
+    /// See [`ConsoleConfiguration::keys`]
+    pub keys: Option<Vec<KeyCode>>,
+    /// See [`ConsoleConfiguration::left_pos`]
+    pub left_pos: Option<f32>,
+    /// See [`ConsoleConfiguration::top_pos`]
+    pub top_pos: Option<f32>,
+    /// See [`ConsoleConfiguration::height`]
+    pub height: Option<f32>,
+    /// See [`ConsoleConfiguration::width`]
+    pub width: Option<f32>,
+    /// See [`ConsoleConfiguration::history_size`]
+    pub history_size: Option<usize>,
+    /// See [`ConsoleConfiguration::symbol`]
+    pub symbol: Option<String>,
+    /// See [`ConsoleConfiguration::collapsible`]
+    pub collapsible: Option<bool>,
+    /// See [`ConsoleConfiguration::title_name`]
+    pub title_name: Option<String>,
+    /// See [`ConsoleConfiguration::resizable`]
+    pub resizable: Option<bool>,
+    /// See [`ConsoleConfiguration::moveable`]
+    pub moveable: Option<bool>,
+    /// See [`ConsoleConfiguration::show_title_bar`]
+    pub show_title_bar: Option<bool>,
+    /// See [`ConsoleConfiguration::num_suggestions`]
+    pub num_suggestions: Option<usize>,
+    /// See [`ConsoleConfiguration::theme`]
+    pub theme: Option<ConsoleTheme>,
+}
+
+impl ConsoleConfigAsset {
+    /// Overwrites the fields of the configuration which are set in this asset.
+    pub fn apply(&self, config: &mut ConsoleConfiguration) {
+        fn set<T: Clone>(target: &mut T, value: &Option<T>) {
+            if let Some(value) = value {
+                *target = value.clone();
+            }
+        }
+
+        set(&mut config.keys, &self.keys);
+        set(&mut config.left_pos, &self.left_pos);
+        set(&mut config.top_pos, &self.top_pos);
+        set(&mut config.height, &self.height);
+        set(&mut config.width, &self.width);
+        set(&mut config.history_size, &self.history_size);
+        set(&mut config.symbol, &self.symbol);
+        set(&mut config.collapsible, &self.collapsible);
+        set(&mut config.title_name, &self.title_name);
+        set(&mut config.resizable, &self.resizable);
+        set(&mut config.moveable, &self.moveable);
+        set(&mut config.show_title_bar, &self.show_title_bar);
+        set(&mut config.num_suggestions, &self.num_suggestions);
+        set(&mut config.theme, &self.theme);
+    }
+}
+
+/// Error while loading a [`ConsoleConfigAsset`]
+#[derive(Debug)]
+pub enum ConsoleConfigAssetError {
+    /// The asset could not be read
+    Io(std::io::Error),
+    /// The asset is not valid RON
+    Ron(ron::error::SpannedError),
+    /// The asset is not valid TOML
+    Toml(toml::de::Error),
+}
+
+impl fmt::Display for ConsoleConfigAssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read console configuration: {err}"),
+            Self::Ron(err) => write!(f, "invalid console configuration: {err}"),
+            Self::Toml(err) => write!(f, "invalid console configuration: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ConsoleConfigAssetError {}
+
+#[derive(Default)]
+pub(crate) struct ConsoleConfigAssetLoader;
+
+impl AssetLoader for ConsoleConfigAssetLoader {
+    type Asset = ConsoleConfigAsset;
+    type Settings = ();
+    type Error = ConsoleConfigAssetError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(ConsoleConfigAssetError::Io)?;
+        if load_context
+            .path()
+            .extension()
+            .is_some_and(|ext| ext == "toml")
+        {
+            let text = String::from_utf8_lossy(&bytes);
+            toml::from_str(&text).map_err(ConsoleConfigAssetError::Toml)
+        } else {
+            ron::de::from_bytes(&bytes).map_err(ConsoleConfigAssetError::Ron)
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["console.ron", "console.toml"]
+    }
+}
+
+/// Handle of the configuration asset set with [`ConsolePlugin::with_config_asset`](crate::ConsolePlugin::with_config_asset)
+#[derive(Resource)]
+pub(crate) struct ConsoleConfigHandle(pub(crate) Handle<ConsoleConfigAsset>);
+
+/// Creates a startup system loading the configuration asset at the given path
+pub(crate) fn load_config_asset(path: String) -> impl FnMut(Commands, Res<AssetServer>) {
+    move |mut commands, asset_server| {
+        commands.insert_resource(ConsoleConfigHandle(asset_server.load(path.clone())));
+    }
+}
+
+/// Applies the configuration asset every time it is (re)loaded
+pub(crate) fn apply_config_asset(
+    mut events: EventReader<AssetEvent<ConsoleConfigAsset>>,
+    handle: Option<Res<ConsoleConfigHandle>>,
+    assets: Res<Assets<ConsoleConfigAsset>>,
+    mut config: ResMut<ConsoleConfiguration>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0) {
+            if let Some(asset) = assets.get(&handle.0) {
+                debug!("Applying console configuration asset");
+                asset.apply(&mut config);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_only_set_fields() {
+        let asset: ConsoleConfigAsset = ron::from_str(
+            "(keys: Some([F1]), width: Some(1000.0), theme: Some((foreground_color: (r: 1, g: 2, b: 3))))",
+        )
+        .unwrap();
+        let mut config = ConsoleConfiguration::default();
+        asset.apply(&mut config);
+
+        assert_eq!(config.keys, vec![KeyCode::F1]);
+        assert_eq!(config.width, 1000.0);
+        assert_eq!(config.height, ConsoleConfiguration::default().height);
+        assert_eq!(
+            config.theme.foreground_color,
+            crate::Colour::from_rgb(1, 2, 3)
+        );
+        assert_eq!(
+            config.theme.background_color,
+            ConsoleTheme::default().background_color
+        );
+    }
+
+    #[test]
+    fn test_hex_colours() {
+        let asset: ConsoleConfigAsset = ron::from_str(
+            r##"(theme: Some((background_color: "#10203080", foreground_color: (r: 1, g: 2, b: 3, a: 4))))"##,
+        )
+        .unwrap();
+        let mut config = ConsoleConfiguration::default();
+        asset.apply(&mut config);
+
+        assert_eq!(
+            config.theme.background_color,
+            crate::Colour::from_rgba(16, 32, 48, 128)
+        );
+        assert_eq!(
+            config.theme.foreground_color,
+            crate::Colour::from_rgba(1, 2, 3, 4)
+        );
+        assert!(ron::from_str::<ConsoleConfigAsset>(
+            r##"(theme: Some((background_color: "#102030f")))"##
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_toml() {
+        let asset: ConsoleConfigAsset = toml::from_str(
+            r##"
+            keys = ["F1", "Backquote"]
+            symbol = "$ "
+
+            [theme]
+            background_color = "#10203080"
+            foreground_color = { r = 1, g = 2, b = 3 }
+            "##,
+        )
+        .unwrap();
+        let mut config = ConsoleConfiguration::default();
+        asset.apply(&mut config);
+
+        assert_eq!(config.keys, vec![KeyCode::F1, KeyCode::Backquote]);
+        assert_eq!(config.symbol, "$ ");
+        assert_eq!(config.width, ConsoleConfiguration::default().width);
+        assert_eq!(
+            config.theme.background_color,
+            crate::Colour::from_rgba(16, 32, 48, 128)
+        );
+        assert_eq!(
+            config.theme.foreground_color,
+            crate::Colour::from_rgb(1, 2, 3)
+        );
+    }
+}
@@ -1,40 +1,100 @@
 use bevy::ecs::{
     component::Tick,
+    event::EventId,
     system::{Resource, SystemMeta, SystemParam},
     world::unsafe_world_cell::UnsafeWorldCell,
 };
+#[cfg(feature = "ui")]
+use bevy::window::{Ime, PrimaryWindow};
 use bevy::{input::keyboard::KeyboardInput, prelude::*};
 
 #[cfg(feature = "ui")]
-use bevy_egui::egui::{self, Align, ScrollArea, TextEdit};
+use bevy_egui::egui::text::LayoutJob;
 #[cfg(feature = "ui")]
-use bevy_egui::egui::{text::LayoutJob, text_selection::CCursorRange};
+use bevy_egui::egui::{self, Align, ScrollArea, TextEdit};
 #[cfg(feature = "ui")]
 use bevy_egui::egui::{Context, Id};
+// bevy_egui has no clipboard on Android
+#[cfg(all(
+    feature = "ui",
+    not(any(target_arch = "wasm32", target_os = "android"))
+))]
+use bevy_egui::EguiClipboard;
 #[cfg(feature = "ui")]
 use bevy_egui::{
-    egui::{epaint::text::cursor::CCursor, Color32, FontId, TextFormat},
+    egui::{Color32, FontId, TextFormat},
     EguiContexts,
 };
 
-use clap::{CommandFactory, FromArgMatches};
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{CommandFactory, FromArgMatches, Parser};
 use shlex::Shlex;
+use std::borrow::Cow;
+#[cfg(feature = "ui")]
+use std::cmp::Reverse;
 use std::marker::PhantomData;
-use std::mem;
+use std::net::SocketAddr;
+use std::ops::Range;
+#[cfg(feature = "ui")]
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::{
-    collections::{BTreeMap, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     iter::once,
 };
 
+#[cfg(feature = "auto_register")]
+use crate::auto_register::RegisteredCommands;
+#[cfg(feature = "ui")]
+use crate::bidi::reorder_job;
+use crate::commands::var::IfCommand;
+#[cfg(feature = "ui")]
+use crate::completion::Completions;
+use crate::completion::{completing_arg, possible_values};
+#[cfg(feature = "ui")]
+use crate::dock::{console_target, show_docked};
+use crate::gesture::TouchToggle;
+#[cfg(feature = "ui")]
+use crate::highlight::apply_highlights;
+#[cfg(feature = "ui")]
+use crate::input_keys::{
+    complete_on_tab, compose_ime, edit_keys, paste_lines, set_cursor_pos, shortcut_keys,
+};
+#[cfg(feature = "ui")]
+use crate::palette::{fuzzy_match, palette_ui, PaletteResponse};
+#[cfg(feature = "ui")]
+use crate::scrollback::scrollback_ui;
+#[cfg(feature = "ui")]
+use crate::surface::ConsoleSurface;
 use crate::{
-    color::{parse_ansi_styled_str, Colour, TextFormattingOverride},
-    ConsoleSet,
+    accessibility::ConsoleAccessibility,
+    color::{
+        apply_erase_sequences, parse_ansi_styled_str, strip_ansi, AnsiMode, AnsiPolicy, Colour,
+        TextFormattingOverride,
+    },
+    highlight::HighlightRule,
+    input_edit::{InputUndo, KillRing},
+    locale::ConsoleLocale,
+    middleware::{CommandOutcome, CommandStatus, ConsoleMiddleware, ConsoleMiddlewares},
+    palette::CommandPalette,
+    player::PlayerId,
+    styled::StyledLine,
+    text::ConsoleText,
+    throttle::Throttle,
+    ConsoleFrontend, ConsoleSet,
 };
 
 type ConsoleCommandEnteredReaderSystemParam = EventReader<'static, 'static, ConsoleCommandEntered>;
 
 type PrintConsoleLineWriterSystemParam = EventWriter<'static, PrintConsoleLine>;
 
+type CommandOutcomeWriterSystemParam = EventWriter<'static, CommandOutcome>;
+
+type StatusMarkersSystemParam = Option<Res<'static, ActiveStatusMarkers>>;
+
+type ConsoleLocaleSystemParam = Option<Res<'static, ConsoleLocale>>;
+
 /// A super-trait for command like structures
 pub trait Command: NamedCommand + CommandFactory + FromArgMatches + Sized + Resource {}
 impl<T: NamedCommand + CommandFactory + FromArgMatches + Sized + Resource> Command for T {}
@@ -43,6 +103,11 @@ impl<T: NamedCommand + CommandFactory + FromArgMatches + Sized + Resource> Comma
 pub trait NamedCommand {
     /// Return the unique command identifier (same as the command "executable")
     fn name() -> &'static str;
+
+    /// Example invocations, set with `#[console_command(example = "...")]` when deriving
+    fn examples() -> &'static [&'static str] {
+        &[]
+    }
 }
 
 /// Executed parsed console command.
@@ -66,48 +131,123 @@ pub trait NamedCommand {
 /// }
 ///
 /// fn log_command(mut log: ConsoleCommand<LogCommand>) {
-///     if let Some(Ok(LogCommand { msg, num })) = log.take() {
+///     while let Some(Ok(LogCommand { msg, num })) = log.take() {
 ///         log.ok();
 ///     }
 /// }
 /// ```
 pub struct ConsoleCommand<'w, T> {
-    command: Option<Result<T, clap::Error>>,
+    pending: VecDeque<(ConsoleCommandEntered, Result<T, clap::Error>)>,
+    entered: Option<ConsoleCommandEntered>,
     console_line: EventWriter<'w, PrintConsoleLine>,
+    outcomes: EventWriter<'w, CommandOutcome>,
+    markers: Option<Res<'w, ActiveStatusMarkers>>,
 }
 
 impl<'w, T> ConsoleCommand<'w, T> {
-    /// Returns Some(T) if the command was executed and arguments were valid.
+    /// Returns the next invocation of the command this frame, in the order they were entered.
+    ///
+    /// A command can be entered several times in one frame, e.g. by a script or a bind, so call
+    /// this in a `while let` loop to handle every invocation. Replies are sent to the source of
+    /// the invocation last returned.
     ///
-    /// This method should only be called once.
-    /// Consecutive calls will return None regardless if the command occurred.
+    /// Invocations with `--help` or `--version` are answered with clap's output and not returned.
     pub fn take(&mut self) -> Option<Result<T, clap::Error>> {
-        mem::take(&mut self.command)
+        let (entered, command) = self.pending.pop_front()?;
+        self.entered = Some(entered);
+        Some(command)
     }
 
-    /// Print `[ok]` in the console.
+    /// Returns where the command was entered, if it was.
+    pub fn source(&self) -> Option<&CommandSource> {
+        self.entered.as_ref().map(|entered| &entered.source)
+    }
+
+    /// Print `[ok]` in the console, see [`ConsoleConfiguration::status_markers`].
     pub fn ok(&mut self) {
-        self.console_line.send(PrintConsoleLine::new("[ok]".into()));
+        if let Some(marker) = self.markers().ok {
+            self.send(PrintConsoleLine::new(marker));
+        }
+        self.report(CommandStatus::Ok);
     }
 
-    /// Print `[failed]` in the console.
+    /// Print `[failed]` in the console, see [`ConsoleConfiguration::status_markers`].
     pub fn failed(&mut self) {
-        self.console_line
-            .send(PrintConsoleLine::new("[failed]".into()));
+        if let Some(marker) = self.markers().failed {
+            self.send(PrintConsoleLine::new(marker).with_severity(ConsoleSeverity::Error));
+        }
+        self.report(CommandStatus::Failed);
+    }
+
+    fn markers(&self) -> StatusMarkers {
+        self.markers
+            .as_ref()
+            .map(|markers| markers.0.clone())
+            .unwrap_or_default()
+    }
+
+    /// Sends a line to the source the command was entered from
+    fn send(&mut self, line: PrintConsoleLine) {
+        let target = self.source().cloned();
+        self.console_line.send(PrintConsoleLine { target, ..line });
+    }
+
+    fn report(&mut self, status: CommandStatus) {
+        if let Some(command) = self.entered.clone() {
+            self.outcomes.send(CommandOutcome { command, status });
+        }
+    }
+
+    /// Print a reply in the console with the given severity.
+    pub fn reply_with_severity(&mut self, msg: impl Into<String>, severity: ConsoleSeverity) {
+        self.send(PrintConsoleLine::new(msg.into()).with_severity(severity));
     }
 
     /// Print a reply in the console.
     ///
     /// See [`reply!`](crate::reply) for usage with the [`format!`] syntax.
     pub fn reply(&mut self, msg: impl Into<String>) {
-        self.console_line.send(PrintConsoleLine::new(msg.into()));
+        self.send(PrintConsoleLine::new(msg.into()));
+    }
+
+    /// Print a reply in the console made of styled spans, see [`StyledLine`].
+    pub fn reply_styled(&mut self, line: StyledLine) {
+        self.send(PrintConsoleLine::from_styled(line));
+    }
+
+    /// Print a reply made of a value, pretty-printed for people and sent as JSON to machine
+    /// consumers like the remote console and the HTTP endpoint.
+    #[cfg(feature = "serde")]
+    pub fn reply_value(&mut self, value: &impl serde::Serialize) {
+        match serde_json::to_string_pretty(value).and_then(|pretty| {
+            serde_json::to_string(value).map(|json| PrintConsoleLine::new(pretty).with_json(json))
+        }) {
+            Ok(line) => self.send(line),
+            Err(err) => self.reply_with_severity(
+                format!("Failed to serialize reply: {err}"),
+                ConsoleSeverity::Error,
+            ),
+        }
+    }
+
+    /// Pins a line to the header of the console window, e.g. the server address or the current
+    /// seed, replacing the line previously pinned by this command.
+    ///
+    /// Pinned lines stay visible above the scrollback until removed with `unpin <command>`.
+    pub fn reply_pinned(&mut self, msg: impl Into<String>) {
+        let key = self
+            .entered
+            .as_ref()
+            .map(|entered| entered.command_name.clone())
+            .unwrap_or_default();
+        self.send(PrintConsoleLine::new(msg.into()).with_pin(key));
     }
 
     /// Print a reply in the console followed by `[ok]`.
     ///
     /// See [`reply_ok!`](crate::reply_ok) for usage with the [`format!`] syntax.
     pub fn reply_ok(&mut self, msg: impl Into<String>) {
-        self.console_line.send(PrintConsoleLine::new(msg.into()));
+        self.send(PrintConsoleLine::new(msg.into()));
         self.ok();
     }
 
@@ -115,15 +255,165 @@ impl<'w, T> ConsoleCommand<'w, T> {
     ///
     /// See [`reply_failed!`](crate::reply_failed) for usage with the [`format!`] syntax.
     pub fn reply_failed(&mut self, msg: impl Into<String>) {
-        self.console_line.send(PrintConsoleLine::new(msg.into()));
+        self.reply_with_severity(msg, ConsoleSeverity::Error);
         self.failed();
     }
+
+    /// Runs `f` with exclusive access to the world once `commands` are applied.
+    ///
+    /// The returned lines are printed followed by `[ok]`, an error is printed followed by `[failed]`.
+    pub fn reply_with_world(
+        &mut self,
+        commands: &mut Commands,
+        f: impl FnOnce(&mut World) -> Result<Vec<String>, String> + Send + 'static,
+    ) {
+        let entered = self.entered.clone();
+        commands.add(move |world: &mut World| {
+            let result = f(world);
+            send_world_reply(world, entered, result);
+        });
+    }
+
+    /// Feeds the next line entered into the console to `f` instead of the command parser,
+    /// e.g. to ask for a password or a free-form message.
+    ///
+    /// The line isn't stored in history. Lines returned by `f` are printed followed by `[ok]`,
+    /// an error is printed followed by `[failed]`. Pressing escape cancels the prompt.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_console::{ConsoleCommand, InputPrompt};
+    /// # use clap::Parser;
+    /// /// Logs in to the server
+    /// #[derive(Parser, ConsoleCommand)]
+    /// #[command(name = "login")]
+    /// struct LoginCommand {
+    ///     user: String,
+    /// }
+    ///
+    /// fn login_command(mut login: ConsoleCommand<LoginCommand>, mut commands: Commands) {
+    ///     while let Some(Ok(LoginCommand { user })) = login.take() {
+    ///         login.prompt(&mut commands, InputPrompt::secret("password:"), move |_world, password| {
+    ///             Ok(vec![format!("logged in as {user}")])
+    ///         });
+    ///     }
+    /// }
+    /// ```
+    pub fn prompt(
+        &mut self,
+        commands: &mut Commands,
+        prompt: InputPrompt,
+        f: impl FnOnce(&mut World, String) -> Result<Vec<String>, String> + Send + Sync + 'static,
+    ) {
+        let pending = PendingPrompt {
+            prompt,
+            entered: self.entered.clone(),
+            on_input: Box::new(f),
+        };
+        commands.add(move |world: &mut World| {
+            let source = pending
+                .entered
+                .as_ref()
+                .map(|entered| entered.source.clone())
+                .unwrap_or_default();
+            let Some(mut state) = world.get_resource_mut::<ConsoleState>() else {
+                return;
+            };
+            if source.is_local() {
+                state.input_prompt = Some(pending);
+                return;
+            }
+            // remote clients and players don't see the console window, so they're sent the prompt
+            let message = pending.prompt.message.clone();
+            state.remote_prompts.insert(source.clone(), pending);
+            world.send_event(PrintConsoleLine::new(message).with_target(source));
+        });
+    }
+}
+
+/// Prints the result of a deferred command followed by `[ok]` or `[failed]`, and reports its outcome
+fn send_world_reply(
+    world: &mut World,
+    entered: Option<ConsoleCommandEntered>,
+    result: Result<Vec<String>, String>,
+) {
+    let target = entered.as_ref().map(|entered| entered.source.clone());
+    let markers = world
+        .get_resource::<ConsoleConfiguration>()
+        .map(|config| config.status_markers.clone())
+        .unwrap_or_default();
+    let (lines, status) = match result {
+        Ok(lines) => (
+            lines
+                .into_iter()
+                .chain(markers.ok)
+                .map(PrintConsoleLine::new)
+                .collect::<Vec<_>>(),
+            CommandStatus::Ok,
+        ),
+        Err(err) => (
+            once(err)
+                .chain(markers.failed)
+                .map(|line| PrintConsoleLine::new(line).with_severity(ConsoleSeverity::Error))
+                .collect(),
+            CommandStatus::Failed,
+        ),
+    };
+    world.send_event_batch(lines.into_iter().map(|line| PrintConsoleLine {
+        target: target.clone(),
+        ..line
+    }));
+    if let Some(command) = entered {
+        world.send_event(CommandOutcome { command, status });
+    }
+}
+
+/// A follow-up input line requested by a command with [`ConsoleCommand::prompt`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputPrompt {
+    /// Shown above the input while the prompt is pending, e.g. `password:`
+    pub message: String,
+    /// Hide the entered characters, and don't echo the line into the scrollback
+    pub secret: bool,
+}
+
+impl InputPrompt {
+    /// Creates a prompt echoing the entered line.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            secret: false,
+        }
+    }
+
+    /// Creates a prompt hiding the entered line, e.g. for passwords.
+    pub fn secret(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            secret: true,
+        }
+    }
+}
+
+type PromptCallback =
+    Box<dyn FnOnce(&mut World, String) -> Result<Vec<String>, String> + Send + Sync>;
+
+/// A prompt waiting for the next input line
+pub(crate) struct PendingPrompt {
+    pub(crate) prompt: InputPrompt,
+    entered: Option<ConsoleCommandEntered>,
+    on_input: PromptCallback,
 }
 
 pub struct ConsoleCommandState<T> {
     #[allow(clippy::type_complexity)]
     event_reader: <ConsoleCommandEnteredReaderSystemParam as SystemParam>::State,
     console_line: <PrintConsoleLineWriterSystemParam as SystemParam>::State,
+    outcomes: <CommandOutcomeWriterSystemParam as SystemParam>::State,
+    markers: <StatusMarkersSystemParam as SystemParam>::State,
+    locale: <ConsoleLocaleSystemParam as SystemParam>::State,
     marker: PhantomData<T>,
 }
 
@@ -134,9 +424,15 @@ unsafe impl<T: Command> SystemParam for ConsoleCommand<'_, T> {
     fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
         let event_reader = ConsoleCommandEnteredReaderSystemParam::init_state(world, system_meta);
         let console_line = PrintConsoleLineWriterSystemParam::init_state(world, system_meta);
+        let outcomes = CommandOutcomeWriterSystemParam::init_state(world, system_meta);
+        let markers = StatusMarkersSystemParam::init_state(world, system_meta);
+        let locale = ConsoleLocaleSystemParam::init_state(world, system_meta);
         ConsoleCommandState {
             event_reader,
             console_line,
+            outcomes,
+            markers,
+            locale,
             marker: PhantomData,
         }
     }
@@ -161,10 +457,28 @@ unsafe impl<T: Command> SystemParam for ConsoleCommand<'_, T> {
             change_tick,
         );
 
-        let command = event_reader.read().find_map(|command| {
-            if T::name() == command.command_name {
+        let mut outcomes = CommandOutcomeWriterSystemParam::get_param(
+            &mut state.outcomes,
+            system_meta,
+            world,
+            change_tick,
+        );
+        let markers = StatusMarkersSystemParam::get_param(
+            &mut state.markers,
+            system_meta,
+            world,
+            change_tick,
+        );
+        let locale =
+            ConsoleLocaleSystemParam::get_param(&mut state.locale, system_meta, world, change_tick);
+        let default_locale = ConsoleLocale::default();
+        let locale = locale.as_deref().unwrap_or(&default_locale);
+
+        let pending = event_reader
+            .read()
+            .filter(|command| T::name() == command.command_name)
+            .filter_map(|command| {
                 let clap_command = T::command().no_binary_name(true);
-                // .color(clap::ColorChoice::Always);
                 let arg_matches = clap_command.try_get_matches_from(command.args.iter());
 
                 debug!(
@@ -172,45 +486,279 @@ unsafe impl<T: Command> SystemParam for ConsoleCommand<'_, T> {
                     command.command_name
                 );
 
-                match arg_matches {
-                    Ok(matches) => {
-                        return Some(T::from_arg_matches(&matches));
-                    }
-                    Err(err) => {
-                        console_line.send(PrintConsoleLine::new(err.to_string()));
-                        return Some(Err(err));
-                    }
-                }
-            }
-            None
-        });
+                let err = match arg_matches {
+                    Ok(matches) => return Some((command.clone(), T::from_arg_matches(&matches))),
+                    Err(err) => err,
+                };
+                console_line.send_batch(
+                    clap_error_lines(&err, &command.command_name, locale)
+                        .into_iter()
+                        .map(|line| line.with_target(command.source.clone())),
+                );
+                // `--help` and `--version` are answered here instead of by the handler
+                let status = match err.kind() {
+                    ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => CommandStatus::Ok,
+                    _ => CommandStatus::InvalidArguments,
+                };
+                outcomes.send(CommandOutcome {
+                    command: command.clone(),
+                    status,
+                });
+                (status == CommandStatus::InvalidArguments).then(|| (command.clone(), Err(err)))
+            })
+            .collect();
 
         ConsoleCommand {
-            command,
+            pending,
+            entered: None,
             console_line,
+            outcomes,
+            markers,
         }
     }
 }
 /// Parsed raw console command into `command` and `args`.
-#[derive(Clone, Debug, Event)]
+#[derive(Clone, Debug, Eq, Event, PartialEq)]
 pub struct ConsoleCommandEntered {
     /// the command definition
     pub command_name: String,
     /// Raw parsed arguments
     pub args: Vec<String>,
+    /// Where the command was entered
+    pub source: CommandSource,
+}
+
+/// A line of input submitted by a frontend, e.g. the console window or the terminal.
+///
+/// Lines are split into a [`ConsoleCommandEntered`] after [`ConsoleSet::ConsoleUI`], once the
+/// [`ConsoleFrontend`]s were read, so any other input source can send this event to run a
+/// command line.
+#[derive(Clone, Debug, Eq, Event, PartialEq)]
+pub struct ConsoleInputSubmitted {
+    /// The raw line, as typed
+    pub line: String,
+    /// Where the line was entered
+    pub source: CommandSource,
+}
+
+impl ConsoleInputSubmitted {
+    /// Creates a line submitted by `source`
+    pub fn new(line: impl Into<String>, source: CommandSource) -> Self {
+        Self {
+            line: line.into(),
+            source,
+        }
+    }
+}
+
+/// Where a [`ConsoleCommandEntered`] came from, so handlers and middleware can decide how far to trust it.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum CommandSource {
+    /// The console window
+    #[default]
+    LocalUi,
+    /// The terminal the app was started from
+    Terminal,
+    /// A remote console client
+    Remote(SocketAddr),
+    /// A script file
+    Script(PathBuf),
+    /// A key bound to the command
+    Bind(KeyCode),
+    /// A player of a networked game, see [`PlayerConsoles`](crate::PlayerConsoles)
+    Player(PlayerId),
+}
+
+impl CommandSource {
+    /// Returns whether the command was entered on this machine, rather than by a remote client
+    /// or a player.
+    pub fn is_local(&self) -> bool {
+        !matches!(self, CommandSource::Remote(_) | CommandSource::Player(_))
+    }
+
+    /// Returns the player who entered the command, if a player did.
+    pub fn player(&self) -> Option<PlayerId> {
+        match self {
+            CommandSource::Player(player) => Some(*player),
+            _ => None,
+        }
+    }
+}
+
+/// Severity of a console line, used to pick its color and prefix.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConsoleSeverity {
+    /// Verbose diagnostic output
+    Debug,
+    /// Regular output
+    #[default]
+    Info,
+    /// Something unexpected which didn't prevent the command from running
+    Warn,
+    /// A command or system failed
+    Error,
+}
+
+impl ConsoleSeverity {
+    /// Returns the upper case name of the severity, e.g. `WARN`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ConsoleSeverity::Debug => "DEBUG",
+            ConsoleSeverity::Info => "INFO",
+            ConsoleSeverity::Warn => "WARN",
+            ConsoleSeverity::Error => "ERROR",
+        }
+    }
 }
 
 /// Events to print to the console.
 #[derive(Clone, Debug, Eq, Event, PartialEq)]
 pub struct PrintConsoleLine {
-    /// Console line
-    pub line: String,
+    /// Console line.
+    ///
+    /// A line starting with a carriage return replaces the last line printed to the same channel
+    /// if nothing was printed since, so progress output of tools updates a single line.
+    pub line: ConsoleText,
+    /// Severity of the line
+    pub severity: ConsoleSeverity,
+    /// Whether the line echoes a command entered into the console
+    pub is_command: bool,
+    /// Channel the line was printed to, see [`ConsoleChannel`]
+    pub channel: Option<String>,
+    /// Source of the command the line replies to, printed everywhere if `None`.
+    ///
+    /// Replies to other sources than [`CommandSource::LocalUi`] are only shown in the console
+    /// window with [`ConsoleConfiguration::mirror_replies`].
+    pub target: Option<CommandSource>,
+    /// Command entered when the line is clicked in the console window, e.g. `help spawn`
+    pub action: Option<String>,
+    /// Pins the line to the header of the console window under the given key instead of
+    /// appending it to the scrollback, replacing the line previously pinned under that key.
+    ///
+    /// See [`ConsoleCommand::reply_pinned`] and the `unpin` command.
+    pub pin: Option<String>,
+    /// Spans the console window shows instead of parsing the ANSI escape codes of
+    /// [`line`](Self::line), see [`PrintConsoleLine::from_styled`]
+    pub styled: Option<StyledLine>,
+    /// The line as JSON, sent instead of [`line`](Self::line) to machine consumers like the
+    /// remote console and the HTTP endpoint, see [`ConsoleCommand::reply_value`]
+    pub json: Option<String>,
 }
 
 impl PrintConsoleLine {
+    /// Whether this line replaces the given previously printed line, see [`Self::line`]
+    pub(crate) fn updates(&self, previous: &PrintConsoleLine) -> bool {
+        self.line.starts_with('\r')
+            && !previous.is_command
+            && previous.channel == self.channel
+            && previous.target == self.target
+    }
+
+    /// Clones the line with its erase sequences applied, returns whether it clears the screen.
+    ///
+    /// The text is only copied if it contains erase sequences.
+    pub(crate) fn erased(&self) -> (bool, PrintConsoleLine) {
+        let (clear_screen, text) = apply_erase_sequences(&self.line);
+        let text = match text {
+            Cow::Owned(text) => Some(text),
+            Cow::Borrowed(_) => None,
+        };
+        let mut erased = self.clone();
+        if let Some(text) = text {
+            erased.line = text.into();
+        }
+        (clear_screen, erased)
+    }
+
     /// Creates a new console line to print.
-    pub const fn new(line: String) -> Self {
-        Self { line }
+    ///
+    /// Static and shared text, e.g. `&'static str` or `Arc<str>`, is cloned into the
+    /// scrollback without allocating, see [`ConsoleText`].
+    pub fn new(line: impl Into<ConsoleText>) -> Self {
+        Self {
+            line: line.into(),
+            severity: ConsoleSeverity::Info,
+            is_command: false,
+            channel: None,
+            target: None,
+            action: None,
+            pin: None,
+            styled: None,
+            json: None,
+        }
+    }
+
+    /// Creates a new console line from styled spans, also formatted as ANSI text for outputs
+    /// other than the console window.
+    pub fn from_styled(line: StyledLine) -> Self {
+        Self {
+            styled: Some(line.clone()),
+            ..Self::new(line.to_ansi())
+        }
+    }
+
+    /// Creates a new console line printed to the given channel, e.g. `net`.
+    pub fn channel(channel: impl Into<String>, line: impl Into<ConsoleText>) -> Self {
+        Self {
+            channel: Some(channel.into()),
+            ..Self::new(line)
+        }
+    }
+
+    /// Sets the severity of the line.
+    pub const fn with_severity(mut self, severity: ConsoleSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Sends the line only to the given source, see [`PrintConsoleLine::target`].
+    pub fn with_target(mut self, target: CommandSource) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Makes the line clickable, entering the given command, see [`PrintConsoleLine::action`].
+    pub fn with_action(mut self, command: impl Into<String>) -> Self {
+        self.action = Some(command.into());
+        self
+    }
+
+    /// Sets the JSON sent to machine consumers, see [`PrintConsoleLine::json`].
+    pub fn with_json(mut self, json: impl Into<String>) -> Self {
+        self.json = Some(json.into());
+        self
+    }
+
+    /// Pins the line under the given key, see [`PrintConsoleLine::pin`].
+    pub fn with_pin(mut self, key: impl Into<String>) -> Self {
+        self.pin = Some(key.into());
+        self
+    }
+
+    /// Number of bytes of text held by the line and its channel name
+    pub(crate) fn text_bytes(&self) -> usize {
+        self.line.len() + self.channel.as_ref().map_or(0, String::len)
+    }
+}
+
+/// Styling applied to every line of a given [`ConsoleSeverity`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeverityStyle {
+    /// Text color, falls back to [`ConsoleTheme::foreground_color`] if `None`
+    pub color: Option<Colour>,
+    /// Text prepended to the line, e.g. `[ERROR] `
+    pub prefix: Option<String>,
+}
+
+impl SeverityStyle {
+    /// Creates a style with the given color and prefix.
+    pub fn new(color: Colour, prefix: impl Into<String>) -> Self {
+        Self {
+            color: Some(color),
+            prefix: Some(prefix.into()),
+        }
     }
 }
 
@@ -231,7 +779,12 @@ pub struct ConsoleConfiguration {
     pub commands: BTreeMap<&'static str, clap::Command>,
     /// Number of commands to store in history
     pub history_size: usize,
-    /// Line prefix symbol
+    /// Which entered commands are stored in history
+    pub history_policy: HistoryPolicy,
+    /// Number of history entries listed before and after the current one while browsing history
+    /// with Up and Down, no preview is shown if `None`
+    pub history_preview: Option<usize>,
+    /// Line prefix symbol, a template which may contain [`ConsolePrompt`](crate::ConsolePrompt) placeholders
     pub symbol: String,
     /// allows window to be collpased
     pub collapsible: bool,
@@ -243,126 +796,1450 @@ pub struct ConsoleConfiguration {
     pub moveable: bool,
     /// show the title bar or not
     pub show_title_bar: bool,
-    /// Background color of console window  
-    pub background_color: Colour,
-    /// Foreground (text) color
-    pub foreground_color: Colour,
+    /// Colors and styling of the console window
+    pub theme: ConsoleTheme,
     /// Number of suggested commands to show
     pub num_suggestions: usize,
+    /// Number of frames between refreshes of watched values
+    pub watch_interval: u32,
+    /// Console variables by name, set with the `set` command and tested with `if`.
+    ///
+    /// `platform` starts out as the operating system the app runs on, e.g. `linux`.
+    pub vars: BTreeMap<String, String>,
+    /// Registered command groups
+    pub groups: BTreeMap<&'static str, CommandGroup>,
+    /// How text containing multiple lines is handled when pasted into the input
+    pub paste_mode: PasteMode,
+    /// Number of pasted lines above which execution has to be confirmed
+    pub paste_confirm_threshold: usize,
+    /// Names of registered commands which currently can't be executed
+    pub disabled_commands: BTreeSet<String>,
+    /// File every entered command and printed line is appended to
+    pub log_file: Option<PathBuf>,
+    /// Whether the outputs of printed lines keep or remove their ANSI escape codes
+    pub ansi: AnsiPolicy,
+    /// Display of scrollback lines longer than the console is wide
+    pub wrap_mode: WrapMode,
+    /// Mark rows of a wrapped line which continue on the next row
+    pub continuation_indicator: bool,
+    /// Tabs showing filtered views of the scrollback, no tab bar is shown if empty
+    pub tabs: Vec<ConsoleTab>,
+    /// Output channels by name, channels printed to are added automatically
+    pub channels: BTreeMap<String, ConsoleChannel>,
+    /// Limit of similar lines printed per second, unlimited if `None`, see [`Throttle`]
+    pub throttle: Option<Throttle>,
+    /// Rules highlighting text of scrollback lines in the console window, see [`HighlightRule`]
+    pub highlights: Vec<HighlightRule>,
+    /// Example invocations per command name, shown by `help` and while typing the command
+    pub examples: BTreeMap<&'static str, Vec<String>>,
+    /// Don't submit input which fails to parse, showing the error below the input instead
+    pub block_invalid_input: bool,
+    /// Maximum number of bytes of text held in the scrollback,
+    /// the oldest lines are removed once it is exceeded
+    pub scrollback_budget: Option<usize>,
+    /// Also show replies to commands entered elsewhere than the console window, e.g. the terminal
+    pub mirror_replies: bool,
+    /// What happens when the terminal reader reaches the end of its input, e.g. on Ctrl+D
+    pub eof_action: EofAction,
+    /// Whether the console is a floating window or docked to an edge
+    pub dock: ConsoleDock,
+    /// Window the console is shown in, the primary window if `None` or once the window is closed
+    pub window: Option<Entity>,
+    /// Directory file system paths are completed relative to, for arguments with a path
+    /// [`clap::ValueHint`], e.g. `#[arg(value_hint = ValueHint::FilePath)]`
+    pub completion_root: PathBuf,
+    /// Key bindings of the input, also applied to the terminal editor when it starts
+    pub edit_mode: EditMode,
+    /// Show a status bar below the input, its segments are set up with [`ConsoleStatusBar`](crate::ConsoleStatusBar)
+    pub status_bar: bool,
+    /// Lines printed after the reply of a command to mark its success or failure
+    pub status_markers: StatusMarkers,
+    /// Mirror the console window for right-to-left languages like Arabic or Hebrew, aligning its
+    /// contents to the right and laying out lines without strongly directional text right to left.
+    ///
+    /// Printed lines and the input mixing both directions are always reordered for display,
+    /// the cursor of the input still moves through it in the order it is typed.
+    pub right_to_left: bool,
+    /// Adapt the console window to touchscreens: dragging the scrollback flings it instead of
+    /// selecting text.
+    ///
+    /// Opening the console also shows the soft keyboard on Android, and closing it hides it again.
+    /// winit doesn't show it on iOS, apps request it themselves when [`ConsoleOpen`] changes.
+    ///
+    /// Enabled by default on Android and iOS, see also [`ConsoleTheme::touch`].
+    pub touch: bool,
+    /// Paste the clipboard into the input when it is middle clicked, like terminals on Linux
+    pub middle_click_paste: bool,
+    /// Directory the scrollback is saved to from the context menu of its lines
+    pub save_dir: PathBuf,
+    /// Touch gesture toggling the console, for touchscreens without the toggle keys
+    pub touch_toggle: Option<TouchToggle>,
+    /// When the input takes the keyboard focus from other egui widgets
+    pub focus_policy: InputFocusPolicy,
+    /// Height of the suggestions and other popups of the input before they scroll
+    pub popup_max_height: f32,
+    /// Whether the toggle keys open the console while another egui text input has focus
+    pub open_policy: OpenPolicy,
 }
 
-impl Default for ConsoleConfiguration {
+/// Lines printed by [`ConsoleCommand::ok`] and [`ConsoleCommand::failed`], see
+/// [`ConsoleConfiguration::status_markers`].
+///
+/// The markers may contain ANSI escape codes to color them, e.g. `"\x1b[32m✔\x1b[0m"`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatusMarkers {
+    /// Printed after a successful command, nothing is printed if `None`
+    pub ok: Option<String>,
+    /// Printed with [`ConsoleSeverity::Error`] after a failed command, nothing is printed if `None`
+    pub failed: Option<String>,
+}
+
+impl Default for StatusMarkers {
     fn default() -> Self {
         Self {
-            keys: vec![KeyCode::Backquote],
-            left_pos: 200.0,
-            top_pos: 100.0,
-            height: 400.0,
-            width: 800.0,
-            commands: BTreeMap::new(),
-            history_size: 20,
-            symbol: "$ ".to_owned(),
-            collapsible: false,
-            title_name: "Console".to_string(),
-            resizable: true,
-            moveable: true,
-            show_title_bar: true,
-            background_color: Colour::from_rgb(102, 102, 102),
-            foreground_color: Colour::from_rgb(220, 220, 220),
-            num_suggestions: 4,
+            ok: Some("[ok]".to_owned()),
+            failed: Some("[failed]".to_owned()),
         }
     }
 }
 
-/// Add a console commands to Bevy app.
-pub trait AddConsoleCommand {
-    /// Add a console command with a given system.
-    ///
-    /// This registers the console command so it will print with the built-in `help` console command.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// # use bevy::prelude::*;
-    /// # use bevy_console::{AddConsoleCommand, ConsoleCommand};
-    /// # use clap::Parser;
-    /// App::new()
-    ///     .add_console_command::<LogCommand, _>(log_command);
-    /// #
-    /// # /// Prints given arguments to the console.
-    /// # #[derive(Parser, ConsoleCommand)]
-    /// # #[command(name = "log")]
-    /// # struct LogCommand;
-    /// #
-    /// # fn log_command(mut log: ConsoleCommand<LogCommand>) {}
-    /// ```
-    fn add_console_command<T: Command, Params>(
-        &mut self,
-        system: impl IntoSystemConfigs<Params>,
-    ) -> &mut Self;
+impl StatusMarkers {
+    /// Markers printing nothing, the outcome of commands is still reported.
+    pub fn none() -> Self {
+        Self {
+            ok: None,
+            failed: None,
+        }
+    }
 }
 
-impl AddConsoleCommand for App {
-    fn add_console_command<T: Command, Params>(
-        &mut self,
-        system: impl IntoSystemConfigs<Params>,
-    ) -> &mut Self {
-        let sys = move |mut config: ResMut<ConsoleConfiguration>| {
-            let command = T::command().no_binary_name(true);
-            // .color(clap::ColorChoice::Always);
-            let name = T::name();
-            if config.commands.contains_key(name) {
-                warn!(
-                    "console command '{}' already registered and was overwritten",
-                    name
-                );
-            }
-            config.commands.insert(name, command);
-        };
+/// [`ConsoleConfiguration::status_markers`] read by [`ConsoleCommand`], which can't access the
+/// configuration as commands may modify it
+#[derive(Default, Resource)]
+pub(crate) struct ActiveStatusMarkers(StatusMarkers);
 
-        self.add_systems(Startup, sys)
-            .add_systems(Update, system.in_set(ConsoleSet::Commands))
+/// Keeps [`ActiveStatusMarkers`] in sync with the configuration
+pub(crate) fn sync_status_markers(
+    config: Res<ConsoleConfiguration>,
+    mut markers: ResMut<ActiveStatusMarkers>,
+) {
+    if config.is_changed() && markers.0 != config.status_markers {
+        markers.0.clone_from(&config.status_markers);
     }
 }
 
-/// Console open state
-#[derive(Default, Resource)]
-pub struct ConsoleOpen {
-    /// Console open
-    pub open: bool,
+/// Settings of a named output channel lines can be printed to with [`PrintConsoleLine::channel`].
+///
+/// Lines of a channel are prefixed with its name, and hidden while the channel is disabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsoleChannel {
+    /// Color of the channel prefix, falls back to [`ConsoleTheme::foreground_color`] if `None`
+    pub color: Option<Colour>,
+    /// Whether lines of the channel are shown
+    pub enabled: bool,
+    /// Limit of similar lines printed to the channel per second, overriding
+    /// [`ConsoleConfiguration::throttle`]
+    pub throttle: Option<Throttle>,
 }
 
-#[derive(Resource)]
-pub(crate) struct ConsoleState {
-    pub(crate) buf: String,
-    pub(crate) scrollback: Vec<String>,
-    pub(crate) history: VecDeque<String>,
-    pub(crate) history_index: usize,
+impl Default for ConsoleChannel {
+    fn default() -> Self {
+        Self {
+            color: None,
+            enabled: true,
+            throttle: None,
+        }
+    }
 }
 
-impl Default for ConsoleState {
-    fn default() -> Self {
-        ConsoleState {
-            buf: String::default(),
-            scrollback: Vec::new(),
-            history: VecDeque::from([String::new()]),
-            history_index: 0,
+impl ConsoleChannel {
+    /// Creates an enabled channel with the given prefix color.
+    pub fn new(color: Colour) -> Self {
+        Self {
+            color: Some(color),
+            enabled: true,
+            throttle: None,
         }
     }
 }
 
-#[cfg(feature = "ui")]
-fn default_style(config: &ConsoleConfiguration) -> TextFormat {
-    TextFormat::simple(FontId::monospace(14f32), config.foreground_color.into())
+/// Handling of multi-line text pasted into the console input
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PasteMode {
+    /// Join all lines into the input, like any other single line text field
+    Join,
+    /// Execute each non-empty line as a separate command, in order
+    #[default]
+    ExecuteLines,
 }
 
+/// Reaction to the end of the terminal input, see [`ConsoleConfiguration::eof_action`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EofAction {
+    /// Keep running, only sending a `ConsoleEof` event
+    #[default]
+    Ignore,
+    /// Close the console window
+    CloseConsole,
+    /// Exit the app with [`AppExit::Success`]
+    Exit,
+}
+
+/// When the console input takes the keyboard focus, see [`ConsoleConfiguration::focus_policy`].
+///
+/// The input never takes the focus from another widget, e.g. a text field of an inspector,
+/// and releases it when the console closes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputFocusPolicy {
+    /// Focus the input when the console opens, and again whenever no widget has focus
+    #[default]
+    Keep,
+    /// Only focus the input when the console opens
+    OnOpen,
+    /// Never focus the input, it is focused by clicking it
+    Manual,
+}
+
+/// Whether the toggle keys open the console while another egui text input has focus, see
+/// [`ConsoleConfiguration::open_policy`]. They always close it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum OpenPolicy {
+    /// Leave the key to the focused text input
+    #[default]
+    NeverWhileTyping,
+    /// Open the console and take the focus from the text input
+    AlwaysOpen,
+    /// Open the console while any of these keys is held, e.g.
+    /// `vec![KeyCode::ControlLeft, KeyCode::ControlRight]` for `` Ctrl+` ``
+    RequireModifier(Vec<KeyCode>),
+}
+
+impl OpenPolicy {
+    /// Whether pressing a toggle key opens the console
+    pub(crate) fn allows_open(&self, typing: bool, keys: &ButtonInput<KeyCode>) -> bool {
+        match self {
+            _ if !typing => true,
+            Self::NeverWhileTyping => false,
+            Self::AlwaysOpen => true,
+            Self::RequireModifier(modifiers) => keys.any_pressed(modifiers.iter().copied()),
+        }
+    }
+}
+
+/// Key bindings of the console input and the terminal editor, see [`ConsoleConfiguration::edit_mode`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EditMode {
+    /// Readline style bindings, e.g. `Ctrl+A` to move to the start of the line
+    #[default]
+    Emacs,
+    /// Modal editing, `Escape` switches from insert to normal mode.
+    ///
+    /// The console window supports a minimal set of normal mode keys: `i a I A h l 0 ^ $ w b e
+    /// x D C S p P u`.
+    Vi,
+}
+
+/// Rules deciding which entered commands are stored in history
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HistoryPolicy {
+    /// Don't store a command identical to the previous history entry
+    pub ignore_duplicates: bool,
+    /// Don't store commands starting with a space, like bash's `ignorespace`
+    pub ignore_space: bool,
+    /// Don't store commands longer than this many characters
+    pub max_entry_len: Option<usize>,
+}
+
+impl HistoryPolicy {
+    /// Returns whether the entered line should be stored, given the most recent history entry.
+    pub fn should_store(&self, line: &str, previous: Option<&str>) -> bool {
+        if line.trim().is_empty() {
+            return false;
+        }
+        if self.ignore_space && line.starts_with(' ') {
+            return false;
+        }
+        if self.ignore_duplicates && previous == Some(line) {
+            return false;
+        }
+        self.max_entry_len
+            .map_or(true, |max_len| line.chars().count() <= max_len)
+    }
+}
+
+/// Placement of the console in its window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConsoleDock {
+    /// A movable window at [`ConsoleConfiguration::left_pos`] and [`ConsoleConfiguration::top_pos`]
+    #[default]
+    Floating,
+    /// A panel along the left edge, [`ConsoleConfiguration::width`] wide
+    Left,
+    /// A panel along the right edge, [`ConsoleConfiguration::width`] wide
+    Right,
+    /// A panel along the top edge, [`ConsoleConfiguration::height`] high
+    Top,
+    /// A panel along the bottom edge, [`ConsoleConfiguration::height`] high
+    Bottom,
+    /// The whole window, e.g. one dedicated to the console
+    Fill,
+}
+
+/// Display of scrollback lines longer than the console is wide
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WrapMode {
+    /// Wrap long lines onto multiple rows
+    #[default]
+    Wrap,
+    /// Cut long lines off with an ellipsis, the full line is shown on hover
+    Truncate,
+    /// Keep lines on a single row and scroll the scrollback horizontally
+    HorizontalScroll,
+}
+
+/// A tab of the console window showing the scrollback lines matching its filter.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_console::{ConsoleConfiguration, ConsoleSeverity, ConsoleTab};
+/// let mut config = ConsoleConfiguration::default();
+/// config.tabs = vec![
+///     ConsoleTab::all(),
+///     ConsoleTab::errors(),
+///     ConsoleTab::commands(),
+///     ConsoleTab::new("Warnings", |line| line.severity == ConsoleSeverity::Warn),
+/// ];
+/// ```
+#[derive(Clone)]
+pub struct ConsoleTab {
+    /// Label of the tab
+    pub name: String,
+    /// Lines shown in the tab
+    pub filter: TabFilter,
+}
+
+/// Lines shown in a [`ConsoleTab`]
+#[derive(Clone)]
+pub enum TabFilter {
+    /// Every line
+    All,
+    /// Lines with at least the given severity
+    MinSeverity(ConsoleSeverity),
+    /// Echoed commands entered into the console
+    Commands,
+    /// Lines printed to the given channel
+    Channel(String),
+    /// Lines for which the function returns `true`
+    Custom(Arc<dyn Fn(&PrintConsoleLine) -> bool + Send + Sync>),
+}
+
+impl ConsoleTab {
+    /// Creates a tab showing the lines for which `filter` returns `true`.
+    pub fn new(
+        name: impl Into<String>,
+        filter: impl Fn(&PrintConsoleLine) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            filter: TabFilter::Custom(Arc::new(filter)),
+        }
+    }
+
+    /// Creates a tab showing every line.
+    pub fn all() -> Self {
+        Self {
+            name: "All".to_owned(),
+            filter: TabFilter::All,
+        }
+    }
+
+    /// Creates a tab showing only errors.
+    pub fn errors() -> Self {
+        Self {
+            name: "Errors".to_owned(),
+            filter: TabFilter::MinSeverity(ConsoleSeverity::Error),
+        }
+    }
+
+    /// Creates a tab showing only the entered commands.
+    pub fn commands() -> Self {
+        Self {
+            name: "Commands".to_owned(),
+            filter: TabFilter::Commands,
+        }
+    }
+
+    /// Returns whether the line is shown in this tab.
+    pub fn matches(&self, line: &PrintConsoleLine) -> bool {
+        match &self.filter {
+            TabFilter::All => true,
+            TabFilter::MinSeverity(severity) => line.severity >= *severity,
+            TabFilter::Commands => line.is_command,
+            TabFilter::Channel(channel) => line.channel.as_ref() == Some(channel),
+            TabFilter::Custom(filter) => filter(line),
+        }
+    }
+}
+
+impl ConsoleConfiguration {
+    /// Returns whether lines of the given channel are shown, unknown channels are enabled.
+    pub fn is_channel_enabled(&self, channel: &str) -> bool {
+        self.channels
+            .get(channel)
+            .map_or(true, |channel| channel.enabled)
+    }
+
+    /// Returns the throttle of lines printed to the given channel, see [`Throttle`].
+    pub fn throttle_for(&self, channel: Option<&str>) -> Option<Throttle> {
+        channel
+            .and_then(|channel| self.channels.get(channel))
+            .and_then(|channel| channel.throttle)
+            .or(self.throttle)
+    }
+
+    /// Enables or disables a command at runtime.
+    ///
+    /// Disabled commands are greyed out in suggestions and are rejected when entered.
+    pub fn set_command_enabled(&mut self, name: impl Into<String>, enabled: bool) {
+        let name = name.into();
+        if enabled {
+            self.disabled_commands.remove(&name);
+        } else {
+            self.disabled_commands.insert(name);
+        }
+    }
+
+    /// Returns whether a command may currently be executed.
+    pub fn is_command_enabled(&self, name: &str) -> bool {
+        !self.disabled_commands.contains(name)
+    }
+}
+
+/// Colors and styling of the console window
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ConsoleTheme {
+    /// Background color of console window, translucent if its alpha is below 255
+    pub background_color: Colour,
+    /// Foreground (text) color
+    pub foreground_color: Colour,
+    /// Color and prefix of lines per severity, severities without an entry use the default style
+    pub severity_styles: BTreeMap<ConsoleSeverity, SeverityStyle>,
+    /// Name of the egui font family used for console text, monospace if `None` or not registered
+    pub font_family: Option<String>,
+    /// Font size of console text before zooming
+    pub font_size: f32,
+    /// Vertical space between scrollback lines
+    pub line_spacing: f32,
+    /// Color drawn over the whole screen behind the open console, e.g. translucent black to dim the game.
+    ///
+    /// There's no option to blur the game instead: egui only paints shapes over the rendered
+    /// frame and can't sample it, so blurring would need a post processing pass of the app's
+    /// camera.
+    pub backdrop_color: Option<Colour>,
+    /// Lay out the console with the app's egui style instead of its own, see
+    /// [`ConsoleTheme::egui_style`]
+    pub inherit_app_style: bool,
+}
+
+impl ConsoleTheme {
+    /// Returns the style of lines with the given severity.
+    pub fn severity_style(&self, severity: ConsoleSeverity) -> SeverityStyle {
+        self.severity_styles
+            .get(&severity)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The default theme with larger text and line spacing, for tapping lines on touchscreens.
+    pub fn touch() -> Self {
+        Self {
+            font_size: 22.0,
+            line_spacing: 10.0,
+            ..default()
+        }
+    }
+
+    /// Returns the egui style the console is laid out with, derived from egui's default style
+    /// rather than the app's so customized app styles don't leak into the console.
+    ///
+    /// Text of buttons and labels uses the console font at the given size.
+    #[cfg(feature = "ui")]
+    pub fn egui_style(&self, font: &FontId) -> egui::Style {
+        let mut style = egui::Style::default();
+        let sized = |scale: f32| FontId::new(font.size * scale, font.family.clone());
+        style.text_styles = [
+            (egui::TextStyle::Small, sized(0.75)),
+            (egui::TextStyle::Body, sized(1.0)),
+            (egui::TextStyle::Button, sized(1.0)),
+            (egui::TextStyle::Monospace, sized(1.0)),
+            (egui::TextStyle::Heading, sized(1.4)),
+        ]
+        .into();
+        style.visuals.window_fill = self.background_color.into();
+        style.visuals.panel_fill = self.background_color.into();
+        style.visuals.extreme_bg_color = self.background_color.into();
+        style.visuals.override_text_color = Some(self.foreground_color.into());
+        style
+    }
+}
+
+impl Default for ConsoleTheme {
+    fn default() -> Self {
+        Self {
+            background_color: Colour::from_rgb(102, 102, 102),
+            foreground_color: Colour::from_rgb(220, 220, 220),
+            severity_styles: BTreeMap::from([
+                (
+                    ConsoleSeverity::Debug,
+                    SeverityStyle {
+                        color: Some(Colour::from_rgb(150, 150, 150)),
+                        prefix: None,
+                    },
+                ),
+                (
+                    ConsoleSeverity::Warn,
+                    SeverityStyle::new(Colour::from_rgb(255, 199, 6), "[WARN] "),
+                ),
+                (
+                    ConsoleSeverity::Error,
+                    SeverityStyle::new(Colour::from_rgb(222, 56, 43), "[ERROR] "),
+                ),
+            ]),
+            font_family: None,
+            font_size: 14.0,
+            line_spacing: 3.0,
+            backdrop_color: None,
+            inherit_app_style: false,
+        }
+    }
+}
+
+impl Default for ConsoleConfiguration {
+    fn default() -> Self {
+        Self {
+            keys: vec![KeyCode::Backquote],
+            left_pos: 200.0,
+            top_pos: 100.0,
+            height: 400.0,
+            width: 800.0,
+            commands: BTreeMap::new(),
+            history_size: 20,
+            history_policy: HistoryPolicy::default(),
+            history_preview: None,
+            symbol: "$ ".to_owned(),
+            collapsible: false,
+            title_name: "Console".to_string(),
+            resizable: true,
+            moveable: true,
+            show_title_bar: true,
+            theme: ConsoleTheme::default(),
+            num_suggestions: 4,
+            watch_interval: 10,
+            vars: BTreeMap::from([("platform".to_owned(), std::env::consts::OS.to_owned())]),
+            groups: BTreeMap::new(),
+            paste_mode: PasteMode::default(),
+            paste_confirm_threshold: 5,
+            disabled_commands: BTreeSet::new(),
+            log_file: None,
+            ansi: AnsiPolicy::default(),
+            wrap_mode: WrapMode::default(),
+            continuation_indicator: true,
+            tabs: Vec::new(),
+            channels: BTreeMap::new(),
+            throttle: None,
+            highlights: Vec::new(),
+            examples: BTreeMap::new(),
+            block_invalid_input: false,
+            scrollback_budget: None,
+            mirror_replies: true,
+            eof_action: EofAction::default(),
+            dock: ConsoleDock::default(),
+            window: None,
+            edit_mode: EditMode::default(),
+            status_bar: false,
+            status_markers: StatusMarkers::default(),
+            right_to_left: false,
+            touch: cfg!(any(target_os = "android", target_os = "ios")),
+            middle_click_paste: false,
+            save_dir: PathBuf::from("."),
+            touch_toggle: None,
+            focus_policy: InputFocusPolicy::default(),
+            popup_max_height: 240.0,
+            open_policy: OpenPolicy::default(),
+            completion_root: PathBuf::from("assets"),
+        }
+    }
+}
+
+/// A namespace for console commands named `<group>.<command>`, e.g. `render.wireframe`.
+///
+/// Commands are placed in a group by their name alone, registering the group
+/// adds a description to it which is shown by the built-in `help` command.
+#[derive(Clone, Debug)]
+pub struct CommandGroup {
+    /// Group name, the part of a command name before the first `.`
+    pub name: &'static str,
+    /// Description of the group
+    pub about: String,
+}
+
+impl CommandGroup {
+    /// Creates a new command group.
+    pub fn new(name: &'static str, about: impl Into<String>) -> Self {
+        Self {
+            name,
+            about: about.into(),
+        }
+    }
+}
+
+/// Returns the group of a command name, if it is namespaced.
+pub(crate) fn command_group(name: &str) -> Option<&str> {
+    name.split_once('.').map(|(group, _)| group)
+}
+
+/// Returns the completions for the given input, collapsing the commands of
+/// a group into a single `<group>.` entry until the group has been typed.
+pub(crate) fn command_suggestions<'a>(
+    commands: impl IntoIterator<Item = &'a str>,
+    input: &str,
+) -> Vec<String> {
+    let mut suggestions: Vec<String> = Vec::new();
+    for name in commands.into_iter().filter(|name| name.starts_with(input)) {
+        let suggestion = match command_group(name) {
+            Some(group) if !input.contains('.') => format!("{group}."),
+            _ => name.to_owned(),
+        };
+        if !suggestions.contains(&suggestion) {
+            suggestions.push(suggestion);
+        }
+    }
+    suggestions
+}
+
+/// Returns the suggestions shown for the input: the [`command_suggestions`] starting with it,
+/// then the commands matching it as a subsequence, e.g. `tpw` for `teleport_waypoint`, best
+/// matches first.
+///
+/// Each suggestion comes with the byte offsets of its characters matching the input.
 #[cfg(feature = "ui")]
-fn style_ansi_text(str: &str, config: &ConsoleConfiguration) -> LayoutJob {
+pub(crate) fn fuzzy_suggestions<'a>(
+    commands: impl IntoIterator<Item = &'a str> + Clone,
+    input: &str,
+) -> Vec<(String, Vec<usize>)> {
+    let typed = input
+        .char_indices()
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    let mut suggestions = command_suggestions(commands.clone(), input)
+        .into_iter()
+        .map(|suggestion| (suggestion, typed.clone()))
+        .collect::<Vec<_>>();
+    // arguments are never matched fuzzily
+    if input.contains(char::is_whitespace) {
+        return suggestions;
+    }
+
+    let mut fuzzy = commands
+        .into_iter()
+        .filter(|name| !name.starts_with(input))
+        .filter_map(|name| {
+            let (score, matched) = fuzzy_match(input, name)?;
+            Some((Reverse(score), name, matched))
+        })
+        .collect::<Vec<_>>();
+    fuzzy.sort();
+    suggestions.extend(
+        fuzzy
+            .into_iter()
+            .map(|(_, name, matched)| (name.to_owned(), matched)),
+    );
+    suggestions
+}
+
+/// Returns the arguments still expected after the typed input, e.g. `<MSG> [NUM]`,
+/// descending into subcommands as they are typed.
+pub(crate) fn argument_hint(
+    commands: &BTreeMap<&'static str, clap::Command>,
+    input: &str,
+) -> Option<String> {
+    let mut words = Shlex::new(input).collect::<Vec<_>>();
+    // the last word is still being typed unless followed by a space
+    if !input.ends_with(' ') {
+        words.pop();
+    }
+    let (name, args) = words.split_first()?;
+    let mut command = commands.get(name.as_str())?;
+
+    let mut positionals = 0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let option = if let Some(long) = arg.strip_prefix("--") {
+            (!long.contains('='))
+                .then(|| command.get_arguments().find(|a| a.get_long() == Some(long)))
+                .flatten()
+        } else if let Some(short) = arg.strip_prefix('-').filter(|short| short.len() == 1) {
+            let short = short.chars().next();
+            command.get_arguments().find(|a| a.get_short() == short)
+        } else if positionals == 0 && command.has_subcommands() {
+            match command.find_subcommand(arg) {
+                Some(subcommand) => command = subcommand,
+                None => positionals += 1,
+            }
+            continue;
+        } else {
+            positionals += 1;
+            continue;
+        };
+        if option.is_some_and(|option| option.get_action().takes_values()) {
+            args.next();
+        }
+    }
+
+    let mut hints = Vec::new();
+    let mut remaining = positionals;
+    for arg in command.get_positionals().filter(|arg| !arg.is_hide_set()) {
+        let multiple = arg
+            .get_num_args()
+            .is_some_and(|range| range.max_values() > 1);
+        if remaining > 0 && !multiple {
+            remaining -= 1;
+            continue;
+        }
+        let name = arg
+            .get_value_names()
+            .and_then(|names| names.first())
+            .map(ToString::to_string)
+            .unwrap_or_else(|| arg.get_id().as_str().to_uppercase());
+        let dots = if multiple { "..." } else { "" };
+        hints.push(if arg.is_required_set() {
+            format!("<{name}>{dots}")
+        } else {
+            format!("[{name}]{dots}")
+        });
+    }
+
+    if positionals == 0 && command.has_subcommands() {
+        let names = command
+            .get_subcommands()
+            .filter(|subcommand| !subcommand.is_hide_set())
+            .map(|subcommand| subcommand.get_name())
+            .collect::<Vec<_>>();
+        hints.push(format!("<{}>", names.join("|")));
+    }
+
+    let has_options = command
+        .get_arguments()
+        .any(|arg| !arg.is_positional() && !arg.is_hide_set() && arg.get_id() != "help");
+    if has_options {
+        hints.push("[OPTIONS]".to_owned());
+    }
+
+    (!hints.is_empty()).then(|| hints.join(" "))
+}
+
+/// Error found while validating the input before it is submitted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct InputError {
+    pub(crate) message: String,
+    /// Byte range of the offending argument in the input
+    pub(crate) span: Option<Range<usize>>,
+}
+
+/// Parses the input against its command. While `complete` is false the input is still
+/// being typed, so missing arguments and the word currently being typed aren't errors.
+pub(crate) fn validate_input(
+    commands: &BTreeMap<&'static str, clap::Command>,
+    input: &str,
+    complete: bool,
+) -> Option<InputError> {
+    let mut words = Shlex::new(input).collect::<Vec<_>>();
+    if !complete && !input.ends_with(' ') {
+        words.pop();
+    }
+    let (name, args) = words.split_first()?;
+    let command = commands.get(name.as_str())?;
+
+    let err = match command.clone().try_get_matches_from(args) {
+        Ok(_) if !complete => return typed_value_error(commands, input),
+        Ok(_) => return None,
+        Err(err) => err,
+    };
+    match err.kind() {
+        ErrorKind::DisplayHelp
+        | ErrorKind::DisplayVersion
+        | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => return None,
+        ErrorKind::MissingRequiredArgument | ErrorKind::MissingSubcommand if !complete => {
+            return typed_value_error(commands, input)
+        }
+        // the value of the last option is still being typed
+        ErrorKind::InvalidValue
+            if !complete
+                && matches!(err.get(ContextKind::InvalidValue), Some(ContextValue::String(value)) if value.is_empty()) =>
+        {
+            return typed_value_error(commands, input)
+        }
+        _ => {}
+    }
+
+    let offending = [ContextKind::InvalidValue, ContextKind::InvalidArg]
+        .into_iter()
+        .filter_map(|kind| match err.get(kind) {
+            Some(ContextValue::String(value)) => Some(value.as_str()),
+            _ => None,
+        })
+        .find(|value| !value.is_empty() && !value.starts_with(['<', '[']));
+    let span = offending.and_then(|value| {
+        let name_end = first_word_end(input);
+        let start = input[name_end..].find(value)? + name_end;
+        Some(start..start + value.len())
+    });
+    let message = err.to_string();
+    let message = message.lines().next().unwrap_or_default();
+    Some(InputError {
+        message: message.trim_start_matches("error: ").to_owned(),
+        span,
+    })
+}
+
+/// Byte offset of the end of the first word, which may be indented, quoted or escaped like
+/// `shlex` allows
+fn first_word_end(input: &str) -> usize {
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in input.char_indices().skip_while(|&(_, c)| c.is_whitespace()) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quote != Some('\'') => escaped = true,
+            '"' | '\'' if quote == Some(c) => quote = None,
+            '"' | '\'' if quote.is_none() => quote = Some(c),
+            c if c.is_whitespace() && quote.is_none() => return index,
+            _ => {}
+        }
+    }
+    input.len()
+}
+
+/// Flags the value being typed if none of the possible values of its argument start with it
+fn typed_value_error(
+    commands: &BTreeMap<&'static str, clap::Command>,
+    input: &str,
+) -> Option<InputError> {
+    let (arg, start) = completing_arg(commands, input)?;
+    let typed = &input[start..];
+    let values = possible_values(arg);
+    if typed.is_empty() || values.is_empty() || values.iter().any(|v| v.starts_with(typed)) {
+        return None;
+    }
+    let name = arg
+        .get_value_names()
+        .and_then(|names| names.first())
+        .map(ToString::to_string)
+        .unwrap_or_else(|| arg.get_id().as_str().to_uppercase());
+    Some(InputError {
+        message: format!(
+            "invalid value '{typed}' for '<{name}>' [possible values: {}]",
+            values.join(", ")
+        ),
+        span: Some(start..input.len()),
+    })
+}
+
+/// Renders a parse error as styled lines: the message in red, offending values in yellow,
+/// suggestions in green, the usage dimmed, and a clickable hint to the command's help
+fn clap_error_lines(
+    err: &clap::Error,
+    command_name: &str,
+    locale: &ConsoleLocale,
+) -> Vec<PrintConsoleLine> {
+    // clap styles its output with ANSI escapes, which the console turns into text formatting
+    let rendered = err.render().ansi().to_string();
+    // `--help` and `--version` output
+    if !err.use_stderr() {
+        return vec![PrintConsoleLine::new(rendered)];
+    }
+
+    let mut lines = rendered
+        .lines()
+        .filter(|line| {
+            let text = strip_ansi(line);
+            !text.trim().is_empty() && !text.starts_with("For more information")
+        })
+        .map(|line| PrintConsoleLine::new(line.to_owned()))
+        .collect::<Vec<_>>();
+    lines.push(
+        PrintConsoleLine::new(locale.format(
+            "more-information",
+            &[("help", &format!("\x1b[4mhelp {command_name}\x1b[0m"))],
+        ))
+        .with_action(format!("help {command_name}")),
+    );
+    lines
+}
+
+/// Add a console commands to Bevy app.
+pub trait AddConsoleCommand {
+    /// Add a console command with a given system.
+    ///
+    /// This registers the console command so it will print with the built-in `help` console command.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_console::{AddConsoleCommand, ConsoleCommand};
+    /// # use clap::Parser;
+    /// App::new()
+    ///     .add_console_command::<LogCommand, _>(log_command);
+    /// #
+    /// # /// Prints given arguments to the console.
+    /// # #[derive(Parser, ConsoleCommand)]
+    /// # #[command(name = "log")]
+    /// # struct LogCommand;
+    /// #
+    /// # fn log_command(mut log: ConsoleCommand<LogCommand>) {}
+    /// ```
+    fn add_console_command<T: Command, Params>(
+        &mut self,
+        system: impl IntoSystemConfigs<Params>,
+    ) -> &mut Self;
+
+    /// Add a description to a group of namespaced commands.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_console::{AddConsoleCommand, CommandGroup};
+    /// App::new()
+    ///     .add_console_command_group(CommandGroup::new("render", "Rendering debug commands"));
+    /// ```
+    fn add_console_command_group(&mut self, group: CommandGroup) -> &mut Self;
+
+    /// Add a middleware which is run for every entered command, see [`ConsoleMiddleware`].
+    ///
+    /// Middleware runs in the order it was added.
+    fn add_console_middleware(&mut self, middleware: impl ConsoleMiddleware) -> &mut Self;
+
+    /// Only allow opening the console while the state `S` is one of `states`,
+    /// closing it when another state is entered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_console::AddConsoleCommand;
+    /// # #[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
+    /// # enum GameState {
+    /// #     #[default]
+    /// #     Menu,
+    /// #     Editor,
+    /// # }
+    /// App::new().allow_console_in_states([GameState::Menu, GameState::Editor]);
+    /// ```
+    fn allow_console_in_states<S: States>(
+        &mut self,
+        states: impl IntoIterator<Item = S>,
+    ) -> &mut Self;
+}
+
+/// Names of the commands the app registered itself, which builtins with the same name give way to
+#[derive(Default, Resource)]
+pub(crate) struct AppCommands(pub(crate) HashSet<&'static str>);
+
+impl AppCommands {
+    /// Returns whether the app registered a command with the given name itself
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.0.contains(name)
+    }
+}
+
+/// Adds the command to the configuration at startup, so `help` and completion know about it
+pub(crate) fn add_command_info<T: Command>(app: &mut App) -> &mut App {
+    app.world_mut()
+        .get_resource_or_insert_with(AppCommands::default)
+        .0
+        .insert(T::name());
+    app.add_systems(Startup, |mut config: ResMut<ConsoleConfiguration>| {
+        insert_command_info::<T>(&mut config);
+    })
+}
+
+/// Adds the info of a builtin command like [`add_command_info`], unless the app registers a
+/// command with the same name
+pub(crate) fn add_builtin_info<T: Command>(app: &mut App) -> &mut App {
+    let sys = |mut config: ResMut<ConsoleConfiguration>, app_commands: Res<AppCommands>| {
+        if app_commands.contains(T::name()) {
+            debug!(
+                "builtin console command '{}' replaced by the app's",
+                T::name()
+            );
+        } else {
+            insert_command_info::<T>(&mut config);
+        }
+    };
+
+    app.init_resource::<AppCommands>().add_systems(Startup, sys)
+}
+
+/// Adds a builtin command, whose handler doesn't run if the app registers a command with the same
+/// name, as both would handle the same [`ConsoleCommandEntered`]
+pub(crate) fn add_builtin_command<T: Command, Params>(
+    app: &mut App,
+    system: impl IntoSystemConfigs<Params>,
+) -> &mut App {
+    add_builtin_info::<T>(app).add_systems(
+        Update,
+        system
+            .in_set(ConsoleSet::Commands)
+            .run_if(|app_commands: Res<AppCommands>| !app_commands.contains(T::name())),
+    )
+}
+
+fn insert_command_info<T: Command>(config: &mut ConsoleConfiguration) {
+    let command = T::command().no_binary_name(true);
+    // .color(clap::ColorChoice::Always);
+    let name = T::name();
+    if config.commands.contains_key(name) {
+        warn!(
+            "console command '{}' already registered and was overwritten",
+            name
+        );
+    }
+    config.commands.insert(name, command);
+    if !T::examples().is_empty() {
+        config.examples.insert(
+            name,
+            T::examples().iter().map(ToString::to_string).collect(),
+        );
+    }
+}
+
+impl AddConsoleCommand for App {
+    fn add_console_command<T: Command, Params>(
+        &mut self,
+        system: impl IntoSystemConfigs<Params>,
+    ) -> &mut Self {
+        #[cfg(feature = "auto_register")]
+        self.world_mut()
+            .get_resource_or_insert_with(RegisteredCommands::default)
+            .0
+            .insert(std::any::TypeId::of::<T>());
+
+        add_command_info::<T>(self).add_systems(Update, system.in_set(ConsoleSet::Commands))
+    }
+
+    fn add_console_command_group(&mut self, group: CommandGroup) -> &mut Self {
+        let sys = move |mut config: ResMut<ConsoleConfiguration>| {
+            config.groups.insert(group.name, group.clone());
+        };
+
+        self.add_systems(Startup, sys)
+    }
+
+    fn add_console_middleware(&mut self, middleware: impl ConsoleMiddleware) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(ConsoleMiddlewares::default)
+            .0
+            .push(Box::new(middleware));
+        self
+    }
+
+    fn allow_console_in_states<S: States>(
+        &mut self,
+        states: impl IntoIterator<Item = S>,
+    ) -> &mut Self {
+        let allowed = states.into_iter().collect::<Vec<_>>();
+        let restrict = move |current: Option<Res<State<S>>>,
+                             mut console_state: ResMut<ConsoleState>,
+                             mut console_open: ResMut<ConsoleOpen>| {
+            let name = std::any::type_name::<S>();
+            let available = current.is_some_and(|current| allowed.contains(current.get()));
+            if available {
+                if console_state.restricted_by.contains(name) {
+                    console_state.restricted_by.remove(name);
+                }
+            } else {
+                if !console_state.restricted_by.contains(name) {
+                    console_state.restricted_by.insert(name);
+                }
+                if console_open.open {
+                    console_open.open = false;
+                }
+            }
+        };
+        self.add_systems(Update, restrict.before(ConsoleSet::ConsoleUI))
+    }
+}
+
+/// Console open state
+#[derive(Default, Resource)]
+pub struct ConsoleOpen {
+    /// Console open
+    pub open: bool,
+}
+
+/// Scrolls the scrollback of the console window when sent, like the `scroll` command and the
+/// Page Up, Page Down, Home and End keys.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_console::ConsoleScroll;
+/// fn scroll_to_top(mut scroll: EventWriter<ConsoleScroll>) {
+///     scroll.send(ConsoleScroll::to_top());
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Event, PartialEq)]
+pub struct ConsoleScroll(pub(crate) ScrollTarget);
+
+/// Where a [`ConsoleScroll`] scrolls to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ScrollTarget {
+    Top,
+    Bottom,
+    /// Lines down, or up if negative
+    Lines(i32),
+}
+
+impl ConsoleScroll {
+    /// Scrolls to the oldest line.
+    pub const fn to_top() -> Self {
+        Self(ScrollTarget::Top)
+    }
+
+    /// Scrolls to the most recent line, following new lines again.
+    pub const fn to_bottom() -> Self {
+        Self(ScrollTarget::Bottom)
+    }
+
+    /// Scrolls the given number of lines down, or up if negative.
+    pub const fn by_lines(lines: i32) -> Self {
+        Self(ScrollTarget::Lines(lines))
+    }
+}
+
+/// Whether the console input has the keyboard focus, for other UI deciding whether to handle
+/// keyboard input, see [`ConsoleConfiguration::focus_policy`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Resource)]
+pub struct ConsoleHasFocus {
+    /// The input or the command palette has focus
+    pub focused: bool,
+}
+
+#[derive(Resource)]
+pub(crate) struct ConsoleState {
+    pub(crate) buf: String,
+    pub(crate) scrollback: Vec<PrintConsoleLine>,
+    pub(crate) history: VecDeque<String>,
+    pub(crate) history_index: usize,
+    pub(crate) watches: BTreeMap<String, String>,
+    /// Lines pinned to the header, by key
+    pub(crate) pinned: BTreeMap<String, PrintConsoleLine>,
+    /// Rendered status bar segments
+    pub(crate) status: Vec<String>,
+    pub(crate) palette: Option<CommandPalette>,
+    pub(crate) input_undo: InputUndo,
+    pub(crate) kill_ring: KillRing,
+    /// Whether the input is in vi normal mode
+    pub(crate) vi_normal: bool,
+    pub(crate) ime_preedit: String,
+    pub(crate) pending_paste: Vec<String>,
+    /// Lines submitted in the window, read by its [`ConsoleFrontend`] implementation
+    pub(crate) submitted: VecDeque<String>,
+    pub(crate) prompt: String,
+    pub(crate) active_tab: usize,
+    pub(crate) zoom: f32,
+    pub(crate) submit_error: Option<String>,
+    pub(crate) input_prompt: Option<PendingPrompt>,
+    /// Prompts of the commands entered by remote clients and players, answered by their next line
+    pub(crate) remote_prompts: HashMap<CommandSource, PendingPrompt>,
+    /// Input the completions of the console window were computed for, with the completions
+    #[cfg(feature = "ui")]
+    pub(crate) completions: Option<(String, Option<Completions>)>,
+    /// Lines pushed straight into the scrollback since the last frame, see [`ConsoleState::push_local`]
+    pub(crate) unlogged: Vec<PrintConsoleLine>,
+    /// Type names of the states which currently don't allow opening the console
+    pub(crate) restricted_by: BTreeSet<&'static str>,
+    /// Id of the input in the console window, to release its focus when the console closes
+    #[cfg(feature = "ui")]
+    pub(crate) input_id: Option<Id>,
+    /// Page of the `man` command shown instead of the scrollback
+    #[cfg(feature = "ui")]
+    pub(crate) man_page: Option<ManPage>,
+}
+
+/// Documentation of a command shown by the console window, see the `man` command
+#[cfg(feature = "ui")]
+pub(crate) struct ManPage {
+    /// Name of the documented command
+    pub(crate) command: String,
+    pub(crate) lines: Vec<PrintConsoleLine>,
+}
+
+impl Default for ConsoleState {
+    fn default() -> Self {
+        ConsoleState {
+            buf: String::default(),
+            scrollback: Vec::new(),
+            history: VecDeque::from([String::new()]),
+            history_index: 0,
+            watches: BTreeMap::new(),
+            pinned: BTreeMap::new(),
+            status: Vec::new(),
+            palette: None,
+            input_undo: InputUndo::default(),
+            kill_ring: KillRing::default(),
+            vi_normal: false,
+            ime_preedit: String::new(),
+            pending_paste: Vec::new(),
+            submitted: VecDeque::new(),
+            prompt: String::new(),
+            active_tab: 0,
+            zoom: 1.0,
+            submit_error: None,
+            input_prompt: None,
+            remote_prompts: HashMap::new(),
+            #[cfg(feature = "ui")]
+            completions: None,
+            unlogged: Vec::new(),
+            restricted_by: BTreeSet::new(),
+            #[cfg(feature = "ui")]
+            input_id: None,
+            #[cfg(feature = "ui")]
+            man_page: None,
+        }
+    }
+}
+
+impl ConsoleState {
+    /// Number of bytes of text held in the scrollback
+    pub(crate) fn scrollback_bytes(&self) -> usize {
+        self.scrollback
+            .iter()
+            .map(PrintConsoleLine::text_bytes)
+            .sum()
+    }
+
+    /// Removes the oldest scrollback lines until at most `budget` bytes of text are held
+    pub(crate) fn trim_scrollback(&mut self, budget: usize) {
+        let mut bytes = self.scrollback_bytes();
+        let excess = self
+            .scrollback
+            .iter()
+            .take_while(|line| {
+                let over = bytes > budget;
+                bytes -= line.text_bytes();
+                over
+            })
+            .count();
+        self.scrollback.drain(..excess);
+    }
+
+    /// Feeds `line` to the [`InputPrompt`] pending for `source`, returns `false` if there is none.
+    ///
+    /// Local sources answer the prompt of the console window, remote clients and players only
+    /// their own prompts.
+    pub(crate) fn answer_prompt(
+        &mut self,
+        commands: &mut Commands,
+        line: String,
+        source: &CommandSource,
+    ) -> bool {
+        let pending = if source.is_local() {
+            self.input_prompt.take()
+        } else {
+            self.remote_prompts.remove(source)
+        };
+        let Some(PendingPrompt {
+            prompt,
+            entered,
+            on_input,
+        }) = pending
+        else {
+            return false;
+        };
+        if source.is_local() {
+            let echo = if prompt.secret {
+                prompt.message
+            } else {
+                format!("{} {line}", prompt.message)
+            };
+            self.push_local(PrintConsoleLine {
+                is_command: true,
+                ..PrintConsoleLine::new(echo)
+            });
+        }
+        commands.add(move |world: &mut World| {
+            let result = on_input(world, line);
+            send_world_reply(world, entered, result);
+        });
+        true
+    }
+
+    /// Pushes a line into the scrollback without sending it as a [`PrintConsoleLine`] event,
+    /// it is still appended to [`ConsoleConfiguration::log_file`]
+    pub(crate) fn push_local(&mut self, line: PrintConsoleLine) {
+        self.unlogged.push(line.clone());
+        self.scrollback.push(line);
+    }
+
+    /// Drops the prompt pending for a remote client or player who disconnected
+    pub(crate) fn forget_prompt(&mut self, source: &CommandSource) {
+        self.remote_prompts.remove(source);
+    }
+
+    /// Cancels the pending [`InputPrompt`], failing its command
+    pub(crate) fn cancel_prompt(&mut self, commands: &mut Commands) {
+        if let Some(PendingPrompt { entered, .. }) = self.input_prompt.take() {
+            commands.add(move |world: &mut World| {
+                let cancelled = world
+                    .get_resource::<ConsoleLocale>()
+                    .cloned()
+                    .unwrap_or_default()
+                    .get("prompt-cancelled")
+                    .to_owned();
+                send_world_reply(world, entered, Err(cancelled));
+            });
+        }
+    }
+}
+
+/// Shows a popup below the input, or above it when there is more space there, e.g. while docked
+/// at the bottom of the window. It is kept on screen, and scrolls once it is higher than
+/// [`ConsoleConfiguration::popup_max_height`].
+#[cfg(feature = "ui")]
+fn input_popup<R>(
+    ui: &egui::Ui,
+    id_source: &str,
+    input: egui::Rect,
+    config: &ConsoleConfiguration,
+    add_contents: impl FnOnce(&mut egui::Ui) -> R,
+) -> R {
+    let id = ui.auto_id_with(id_source);
+    let screen = ui.ctx().screen_rect();
+    let below = screen.bottom() - input.bottom();
+    let above = input.top() - screen.top();
+    // the height of the popup isn't known before it is shown, use its height in the last frame
+    let height = ui
+        .ctx()
+        .memory(|m| m.area_rect(id))
+        .map_or(0.0, |rect| rect.height())
+        .min(config.popup_max_height);
+    let (pivot, pos, space) = match (height > below && above > below, config.right_to_left) {
+        (false, false) => (egui::Align2::LEFT_TOP, input.left_bottom(), below),
+        (false, true) => (egui::Align2::RIGHT_TOP, input.right_bottom(), below),
+        (true, false) => (egui::Align2::LEFT_BOTTOM, input.left_top(), above),
+        (true, true) => (egui::Align2::RIGHT_BOTTOM, input.right_top(), above),
+    };
+    egui::Area::new(id)
+        .fixed_pos(pos)
+        .pivot(pivot)
+        .constrain(true)
+        .movable(false)
+        .show(ui.ctx(), |ui| {
+            ScrollArea::vertical()
+                .max_height(config.popup_max_height.min(space))
+                .show(ui, add_contents)
+                .inner
+        })
+        .inner
+}
+
+#[cfg(feature = "ui")]
+fn default_style(
+    config: &ConsoleConfiguration,
+    severity: ConsoleSeverity,
+    font: &FontId,
+) -> TextFormat {
+    let color = config
+        .theme
+        .severity_style(severity)
+        .color
+        .unwrap_or(config.theme.foreground_color);
+    TextFormat::simple(font.clone(), color.into())
+}
+
+/// Font of console text, from the theme scaled by the current zoom
+#[cfg(feature = "ui")]
+pub(crate) fn console_font(ctx: &Context, config: &ConsoleConfiguration, zoom: f32) -> FontId {
+    let size = config.theme.font_size * zoom;
+    match &config.theme.font_family {
+        Some(name) => {
+            let family = egui::FontFamily::Name(name.as_str().into());
+            if ctx.fonts(|fonts| fonts.families().contains(&family)) {
+                FontId::new(size, family)
+            } else {
+                FontId::monospace(size)
+            }
+        }
+        None => FontId::monospace(size),
+    }
+}
+
+#[cfg(feature = "ui")]
+/// Lays out the line with its ANSI styling, along with the layout job sections of OSC 8 hyperlinks
+pub(crate) fn style_ansi_text(
+    line: &PrintConsoleLine,
+    config: &ConsoleConfiguration,
+    font: &FontId,
+) -> (LayoutJob, Vec<(u32, String)>) {
+    let str = line.line.as_str();
     let mut layout_job = LayoutJob::default();
-    let mut current_style = default_style(config);
+    let mut links = Vec::new();
+    let mut current_link: Option<String> = None;
+    let mut current_style = default_style(config, line.severity, font);
+    if let Some(prefix) = config.theme.severity_style(line.severity).prefix {
+        layout_job.append(&prefix, 0f32, current_style.clone());
+    }
+    if let Some(channel) = &line.channel {
+        let mut channel_style = current_style.clone();
+        if let Some(color) = config.channels.get(channel).and_then(|c| c.color) {
+            channel_style.color = color.into();
+        }
+        layout_job.append(&format!("[{channel}] "), 0f32, channel_style);
+    }
+    let text_start = layout_job.text.len();
+    if let Some(styled) = line
+        .styled
+        .as_ref()
+        .filter(|_| config.ansi.window == AnsiMode::Keep)
+    {
+        for span in &styled.spans {
+            let mut style = current_style.clone();
+            if let Some(color) = span.color {
+                style.color = color.into();
+            }
+            if span.bold {
+                style.font_id.size = font.size * 8.0 / 7.0;
+            }
+            style.italics = span.italic;
+            if span.underline {
+                style.underline = egui::Stroke::new(1., style.color);
+            }
+            layout_job.append(&span.text, 0f32, style);
+        }
+        apply_highlights(&mut layout_job, text_start, &config.highlights, font);
+        return match reorder_job(&layout_job, config.right_to_left) {
+            Some((job, _)) => (job, Vec::new()),
+            None => (layout_job, Vec::new()),
+        };
+    }
+
     let mut last_offset = 0;
-    let str_without_ansi = strip_ansi_escapes::strip_str(str);
-    for (offset, overrides) in parse_ansi_styled_str(str)
+    let str_without_ansi = strip_ansi(str);
+    let styles = match config.ansi.window {
+        AnsiMode::Keep => parse_ansi_styled_str(str),
+        AnsiMode::Strip => Vec::new(),
+    };
+    for (offset, overrides) in styles
         .into_iter()
         .chain(once((str_without_ansi.len(), Default::default())))
     {
@@ -370,244 +2247,811 @@ fn style_ansi_text(str: &str, config: &ConsoleConfiguration) -> LayoutJob {
         // 01234
         let text = &str_without_ansi[(last_offset)..offset];
         if !text.is_empty() {
-            layout_job.append(text, 0f32, current_style.clone());
+            let mut style = current_style.clone();
+            if let Some(uri) = &current_link {
+                links.push((layout_job.sections.len() as u32, uri.clone()));
+                style.underline = egui::Stroke::new(1., style.color);
+            }
+            layout_job.append(text, 0f32, style);
         }
 
         if overrides.contains(&TextFormattingOverride::Reset) {
-            current_style = default_style(config);
+            current_style = default_style(config, line.severity, font);
         }
 
         for o in overrides {
             match o {
-                TextFormattingOverride::Bold => current_style.font_id.size = 16f32, // no support for bold font families in egui TODO: when egui supports bold font families, use them here
-                TextFormattingOverride::Dim => current_style.font_id.size = 12f32, // no support for dim font families in egui TODO: when egui supports dim font families, use them here
+                TextFormattingOverride::Bold => current_style.font_id.size = font.size * 8.0 / 7.0, // no support for bold font families in egui TODO: when egui supports bold font families, use them here
+                TextFormattingOverride::Dim => current_style.font_id.size = font.size * 6.0 / 7.0, // no support for dim font families in egui TODO: when egui supports dim font families, use them here
                 TextFormattingOverride::Italic => current_style.italics = true,
                 TextFormattingOverride::Underline => {
-                    current_style.underline = egui::Stroke::new(1., config.foreground_color)
+                    current_style.underline = egui::Stroke::new(1., config.theme.foreground_color)
                 }
                 TextFormattingOverride::Strikethrough => {
-                    current_style.strikethrough = egui::Stroke::new(1., config.foreground_color)
+                    current_style.strikethrough =
+                        egui::Stroke::new(1., config.theme.foreground_color)
                 }
                 TextFormattingOverride::Foreground(c) => current_style.color = c.into(),
                 TextFormattingOverride::Background(c) => current_style.background = c.into(),
+                TextFormattingOverride::Hyperlink(uri) => current_link = uri,
                 _ => {}
             }
         }
 
         last_offset = offset;
     }
-    layout_job
+
+    let highlight_origins = apply_highlights(&mut layout_job, text_start, &config.highlights, font);
+    let links = highlight_origins
+        .iter()
+        .enumerate()
+        .filter_map(|(section, origin)| {
+            let (_, uri) = links.iter().find(|(link, _)| *link as usize == *origin)?;
+            Some((section as u32, uri.clone()))
+        })
+        .collect::<Vec<_>>();
+
+    match reorder_job(&layout_job, config.right_to_left) {
+        Some((job, origins)) => {
+            let links = origins
+                .iter()
+                .enumerate()
+                .filter_map(|(section, origin)| {
+                    let (_, uri) = links.iter().find(|(link, _)| link == origin)?;
+                    Some((section as u32, uri.clone()))
+                })
+                .collect();
+            (job, links)
+        }
+        None => (layout_job, links),
+    }
+}
+
+/// Shows or hides the soft keyboard of the activity bevy runs in
+#[cfg(all(feature = "ui", target_os = "android"))]
+fn show_soft_keyboard(show: bool) {
+    let Some(android_app) = bevy::winit::ANDROID_APP.get() else {
+        return;
+    };
+    // explicit requests, so the keyboard isn't hidden again right away
+    if show {
+        android_app.show_soft_input(false);
+    } else {
+        android_app.hide_soft_input(false);
+    }
 }
 
 #[cfg(feature = "ui")]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn console_ui(
     mut egui_context: EguiContexts,
     config: Res<ConsoleConfiguration>,
     mut keyboard_input_events: EventReader<KeyboardInput>,
     keys: Res<ButtonInput<KeyCode>>,
     mut state: ResMut<ConsoleState>,
-    mut command_entered: EventWriter<ConsoleCommandEntered>,
     mut console_open: ResMut<ConsoleOpen>,
+    mut ime_events: EventReader<Ime>,
+    mut windows: Query<&mut Window>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    surfaces: Query<Entity, With<ConsoleSurface>>,
+    locale: Res<ConsoleLocale>,
+    mut accessibility: ResMut<ConsoleAccessibility>,
+    mut has_focus: ResMut<ConsoleHasFocus>,
+    mut scroll_events: EventReader<ConsoleScroll>,
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))] mut clipboard: Option<
+        ResMut<EguiClipboard>,
+    >,
+    mut commands: Commands,
 ) {
     let keyboard_input_events = keyboard_input_events.read().collect::<Vec<_>>();
+    let ime_events = ime_events.read().collect::<Vec<_>>();
 
+    let Some(target) = console_target(&config, &surfaces, &windows, &primary_window) else {
+        return;
+    };
     // If there is no egui context, return, this can happen when exiting the app
-    let ctx = if let Some(ctxt) = egui_context.try_ctx_mut() {
+    let ctx = if let Some(ctxt) = egui_context.try_ctx_for_entity_mut(target) {
         ctxt
     } else {
         return;
     };
 
+    // Read out printed lines and suggestions
+    for announcement in accessibility.pending.drain(..) {
+        let info = egui::WidgetInfo::labeled(egui::WidgetType::Label, true, announcement.text());
+        ctx.output_mut(|output| {
+            output
+                .events
+                .push(egui::output::OutputEvent::ValueChanged(info))
+        });
+    }
+
     let pressed = keyboard_input_events
         .iter()
         .any(|code| console_key_pressed(code, &config.keys));
 
     // always close if console open
-    // avoid opening console if typing in another text input
-    if pressed && (console_open.open || !ctx.wants_keyboard_input()) {
+    // avoid opening console in a restricted state, or if typing in another text input unless the
+    // open policy allows it
+    let typing = ctx.wants_keyboard_input();
+    if pressed
+        && state.restricted_by.is_empty()
+        && (console_open.open || config.open_policy.allows_open(typing, &keys))
+    {
         console_open.open = !console_open.open;
+        if console_open.open && typing {
+            ctx.memory_mut(|m| {
+                if let Some(focused) = m.focused() {
+                    m.surrender_focus(focused);
+                }
+            });
+        }
+    }
+
+    // egui doesn't handle IME itself, enable it for the console input while the console is open.
+    // winit doesn't show the soft keyboard for it, see `ConsoleConfiguration::touch`
+    if console_open.is_changed() && !console_open.is_added() {
+        if let Ok(mut window) = windows.get_mut(target) {
+            window.ime_enabled = console_open.open;
+        }
+        #[cfg(target_os = "android")]
+        if config.touch {
+            show_soft_keyboard(console_open.open);
+        }
+        state.ime_preedit.clear();
+        // the hidden input would keep egui wanting keyboard input
+        if let Some(input_id) = state.input_id.filter(|_| !console_open.open) {
+            ctx.memory_mut(|m| m.surrender_focus(input_id));
+        }
+    }
+    if !console_open.open {
+        has_focus.set_if_neq(ConsoleHasFocus { focused: false });
     }
 
-    if console_open.open {
-        egui::Window::new(&config.title_name)
-            .collapsible(config.collapsible)
-            .default_pos([config.left_pos, config.top_pos])
-            .default_size([config.width, config.height])
-            .resizable(config.resizable)
-            .movable(config.moveable)
-            .title_bar(config.show_title_bar)
-            .frame(egui::Frame {
-                fill: config.background_color.into(),
-                ..Default::default()
-            })
-            .show(ctx, |ui| {
-                ui.style_mut().visuals.extreme_bg_color = config.background_color.into();
-                ui.style_mut().visuals.override_text_color = Some(config.foreground_color.into());
-
-                ui.vertical(|ui| {
-                    let scroll_height = ui.available_height() - 30.0;
-
-                    // Scroll area
-                    ScrollArea::vertical()
-                        .auto_shrink([false, false])
-                        .stick_to_bottom(true)
-                        .max_height(scroll_height)
-                        .show(ui, |ui| {
-                            ui.vertical(|ui| {
-                                for line in &state.scrollback {
-                                    ui.label(style_ansi_text(line, &config));
-                                }
-                            });
+    if console_open.open {
+        let font = console_font(ctx, &config, state.zoom);
+        let contents = |ui: &mut egui::Ui| {
+            ui.style_mut().visuals.extreme_bg_color = config.theme.background_color.into();
+            ui.style_mut().visuals.override_text_color = Some(config.theme.foreground_color.into());
+            // selecting label text would swallow the drags flinging the scrollback
+            if config.touch {
+                ui.style_mut().interaction.selectable_labels = false;
+            }
+
+            // Mirrored for right-to-left languages
+            let (align, row_layout) = if config.right_to_left {
+                (Align::Max, egui::Layout::right_to_left(Align::Center))
+            } else {
+                (Align::Min, egui::Layout::left_to_right(Align::Center))
+            };
+            ui.with_layout(egui::Layout::top_down(align), |ui| {
+                // Pinned lines
+                if !state.pinned.is_empty() {
+                    for line in state.pinned.values() {
+                        ui.label(style_ansi_text(line, &config, &font).0);
+                    }
+                    ui.separator();
+                }
+
+                // Watched values
+                if !state.watches.is_empty() {
+                    for (expr, value) in &state.watches {
+                        ui.label(
+                            egui::RichText::new(format!("{expr} = {value}")).font(font.clone()),
+                        );
+                    }
+                    ui.separator();
+                }
+
+                // Tabs
+                if !config.tabs.is_empty() {
+                    ui.with_layout(row_layout, |ui| {
+                        for (index, tab) in config.tabs.iter().enumerate() {
+                            if ui
+                                .selectable_label(state.active_tab == index, &tab.name)
+                                .clicked()
+                            {
+                                state.active_tab = index;
+                            }
+                        }
+                    });
+                    ui.separator();
+                }
+
+                // Manual page header
+                let mut close_man_page = false;
+                if let Some(page) = &state.man_page {
+                    ui.with_layout(row_layout, |ui| {
+                        close_man_page = ui.small_button("✖").clicked();
+                        ui.label(
+                            egui::RichText::new(format!("man {}", page.command))
+                                .font(font.clone())
+                                .strong(),
+                        );
+                    });
+                    ui.separator();
+                }
+
+                // Scroll area
+                let scroll = scroll_events.read().last().map(|scroll| scroll.0);
+                scrollback_ui(
+                    ui,
+                    &mut state,
+                    &config,
+                    &locale,
+                    &font,
+                    align,
+                    scroll,
+                    has_focus.focused,
+                    console_open.is_changed(),
+                );
+                if close_man_page {
+                    state.man_page = None;
+                }
+
+                // Separator
+                ui.separator();
+
+                // Input
+                let input_id = ui.make_persistent_id("console_input");
+
+                // Paste the clipboard into the input when it is middle clicked, through the
+                // same handling as pasting with the keyboard
+                #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+                if config.middle_click_paste
+                    && ui
+                        .ctx()
+                        .read_response(input_id)
+                        .is_some_and(|input| input.clicked_by(egui::PointerButton::Middle))
+                {
+                    if let Some(text) = clipboard.as_mut().and_then(|c| c.get_contents()) {
+                        ui.memory_mut(|m| m.request_focus(input_id));
+                        ui.input_mut(|i| i.events.push(egui::Event::Paste(text)));
+                    }
+                }
 
-                            // Scroll to bottom if console just opened
-                            if console_open.is_changed() {
-                                ui.scroll_to_cursor(Some(Align::BOTTOM));
-                            }
-                        });
+                // Execute multi-line pastes line by line instead of letting egui join them
+                paste_lines(ui, input_id, &mut state, &config);
+
+                // Underline the invalid argument of the input
+                let input_error = state
+                    .input_prompt
+                    .is_none()
+                    .then(|| validate_input(&config.commands, &state.buf, false))
+                    .flatten();
+                let error_color = config
+                    .theme
+                    .severity_style(ConsoleSeverity::Error)
+                    .color
+                    .map_or(Color32::RED, Into::into);
+                let error_span = input_error.as_ref().and_then(|error| error.span.clone());
+                let mut layouter = |ui: &egui::Ui, text: &str, _wrap_width: f32| {
+                    let format =
+                        TextFormat::simple(font.clone(), config.theme.foreground_color.into());
+                    let mut job = LayoutJob::default();
+                    match error_span
+                        .clone()
+                        .filter(|span| text.get(span.clone()).is_some())
+                    {
+                        Some(span) => {
+                            job.append(&text[..span.start], 0.0, format.clone());
+                            job.append(
+                                &text[span.clone()],
+                                0.0,
+                                TextFormat {
+                                    underline: egui::Stroke::new(1.5, error_color),
+                                    ..format.clone()
+                                },
+                            );
+                            job.append(&text[span.end..], 0.0, format);
+                        }
+                        None => job.append(text, 0.0, format),
+                    }
+                    // shown in visual order like the scrollback, the cursor still moves through
+                    // the characters in logical order
+                    let job = reorder_job(&job, config.right_to_left).map_or(job, |(job, _)| job);
+                    ui.fonts(|fonts| fonts.layout_job(job))
+                };
+
+                // Show what a command is asking for while it is reading the input
+                let secret = match &state.input_prompt {
+                    Some(PendingPrompt { prompt, .. }) => {
+                        ui.label(egui::RichText::new(&prompt.message).font(font.clone()));
+                        prompt.secret
+                    }
+                    None => false,
+                };
 
-                    // Separator
-                    ui.separator();
+                // Undo, redo and the keys of the edit mode, before egui handles them
+                edit_keys(ui, input_id, &mut state, &config);
 
-                    // Input
-                    let text_edit = TextEdit::singleline(&mut state.buf)
-                        .desired_width(f32::INFINITY)
-                        .lock_focus(true)
-                        .font(egui::TextStyle::Monospace);
+                // a wide cursor shows vi normal mode
+                let text_cursor = ui.visuals().text_cursor.clone();
+                if state.vi_normal {
+                    ui.visuals_mut().text_cursor.stroke.width = font.size * 0.5;
+                    ui.visuals_mut().text_cursor.blink = false;
+                }
+
+                let text_edit = TextEdit::singleline(&mut state.buf)
+                    .id(input_id)
+                    .password(secret)
+                    .desired_width(f32::INFINITY)
+                    .horizontal_align(if config.right_to_left {
+                        Align::RIGHT
+                    } else {
+                        Align::LEFT
+                    })
+                    .lock_focus(true)
+                    .font(font.clone())
+                    .layouter(&mut layouter);
+
+                let text_edit_response = ui.add(text_edit);
+                ui.visuals_mut().text_cursor = text_cursor;
+                if text_edit_response.changed() {
+                    state.submit_error = None;
+                }
 
-                    let text_edit_response = ui.add(text_edit);
+                // Complete on tab and insert the text composed by the IME
+                let completions_shown =
+                    complete_on_tab(ui, &text_edit_response, &mut state, &config);
+                if text_edit_response.has_focus() {
+                    let window = windows.get_mut(target).ok();
+                    compose_ime(ui, &text_edit_response, &ime_events, &mut state, window);
+                }
 
+                // show the text being composed by the IME instead of suggestions
+                let mut shown_suggestions = Vec::new();
+                if text_edit_response.has_focus() && !state.ime_preedit.is_empty() {
+                    input_popup(ui, "ime_preedit", text_edit_response.rect, &config, |ui| {
+                        ui.label(
+                            egui::RichText::new(&state.ime_preedit)
+                                .font(font.clone())
+                                .underline(),
+                        );
+                    });
+                } else if let Some(entries) = config
+                    .history_preview
+                    .filter(|_| text_edit_response.has_focus() && state.history_index > 0)
+                {
+                    // show where the current entry is in history, oldest entries at the top
+                    input_popup(
+                        ui,
+                        "history_preview",
+                        text_edit_response.rect,
+                        &config,
+                        |ui| {
+                            let window =
+                                history_window(state.history.len(), state.history_index, entries);
+                            for index in window.rev() {
+                                let color = if index == state.history_index {
+                                    Color32::WHITE
+                                } else {
+                                    Color32::GRAY
+                                };
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "{index:>4}  {}",
+                                        state.history[index]
+                                    ))
+                                    .font(font.clone())
+                                    .color(color),
+                                );
+                            }
+                        },
+                    );
+                } else if !state.pending_paste.is_empty() {
+                    input_popup(
+                        ui,
+                        "pending_paste",
+                        text_edit_response.rect,
+                        &config,
+                        |ui| {
+                            let count = state.pending_paste.len().to_string();
+                            ui.label(locale.format("paste-confirm", &[("count", &count)]));
+                        },
+                    );
+                } else if text_edit_response.has_focus()
+                    && !state.buf.is_empty()
+                    && state.input_prompt.is_none()
+                {
                     // show a few suggestions
-                    if text_edit_response.has_focus() && !state.buf.is_empty() {
-                        // create the area to show suggestions
-                        let suggestions_area = egui::Area::new(ui.auto_id_with("suggestions"))
-                            .fixed_pos(ui.next_widget_position())
-                            .movable(false);
-                        suggestions_area.show(ui.ctx(), |ui| {
-                            // collect the given number of commands starting
-                            // with the given text
-                            let command_names = &config
-                                .commands
-                                .iter()
-                                .map(|c| *c.0)
-                                .filter(|c| c.starts_with(&state.buf))
-                                .collect::<Vec<_>>();
-
-                            // show each command in the list
-                            for command in command_names.iter().take(config.num_suggestions) {
-                                let mut layout_job = egui::text::LayoutJob::default();
-                                layout_job.append(
-                                    state.buf.as_str(),
-                                    0.0,
+                    input_popup(ui, "suggestions", text_edit_response.rect, &config, |ui| {
+                        // collect the given number of commands starting with or fuzzily
+                        // matching the given text
+                        let command_names =
+                            fuzzy_suggestions(config.commands.keys().copied(), &state.buf);
+                        shown_suggestions = command_names
+                            .iter()
+                            .map(|(command, _)| command)
+                            .chain(completions_shown.iter().flat_map(|(_, c)| c))
+                            .take(config.num_suggestions)
+                            .cloned()
+                            .collect();
+
+                        // show each command in the list, underlining the matched characters
+                        for (command, matched) in command_names.iter().take(config.num_suggestions)
+                        {
+                            // grey out disabled commands
+                            let (typed_color, rest_color) = if config.is_command_enabled(command) {
+                                (Color32::WHITE, Color32::LIGHT_GRAY)
+                            } else {
+                                (Color32::GRAY, Color32::DARK_GRAY)
+                            };
+                            let mut layout_job = egui::text::LayoutJob::default();
+                            for (index, c) in command.char_indices() {
+                                let format = if matched.contains(&index) {
                                     TextFormat {
-                                        font_id: FontId::new(14.0, egui::FontFamily::Monospace),
-                                        underline: egui::Stroke::new(1., Color32::WHITE),
-                                        color: Color32::WHITE,
+                                        font_id: font.clone(),
+                                        underline: egui::Stroke::new(1., typed_color),
+                                        color: typed_color,
                                         ..default()
-                                    },
-                                );
-                                layout_job.append(
-                                    &command[state.buf.len()..],
-                                    0.0,
+                                    }
+                                } else {
                                     TextFormat {
-                                        font_id: FontId::new(14.0, egui::FontFamily::Monospace),
-                                        color: Color32::LIGHT_GRAY,
+                                        font_id: font.clone(),
+                                        color: rest_color,
                                         ..default()
-                                    },
-                                );
-                                ui.label(layout_job);
+                                    }
+                                };
+                                let end = index + c.len_utf8();
+                                layout_job.append(&command[index..end], 0.0, format);
                             }
-                        });
-                    }
+                            ui.label(layout_job);
+                        }
 
-                    // Handle enter
-                    if text_edit_response.lost_focus()
-                        && ui.input(|i| i.key_pressed(egui::Key::Enter))
-                    {
-                        if state.buf.trim().is_empty() {
-                            state.scrollback.push(String::new());
-                        } else {
-                            let msg = format!("{}{}", config.symbol, state.buf);
-                            state.scrollback.push(msg);
-                            let cmd_string = state.buf.clone();
-                            state.history.insert(1, cmd_string);
-                            if state.history.len() > config.history_size + 1 {
-                                state.history.pop_back();
+                        // show the values completed by tab, highlighting the typed one
+                        if let Some((start, completions)) = &completions_shown {
+                            let typed = state.buf.get(*start..).unwrap_or_default();
+                            for completion in completions.iter().take(config.num_suggestions) {
+                                let name = completion.trim_end_matches('/');
+                                let name = name.rsplit('/').next().unwrap_or(name);
+                                let dir = if completion.ends_with('/') { "/" } else { "" };
+                                let color = if completion == typed {
+                                    Color32::WHITE
+                                } else {
+                                    Color32::LIGHT_GRAY
+                                };
+                                let mut text = egui::RichText::new(format!("{name}{dir}"))
+                                    .font(font.clone())
+                                    .color(color);
+                                if completion == typed {
+                                    text = text.underline();
+                                }
+                                ui.label(text);
                             }
+                        }
 
-                            let mut args = Shlex::new(&state.buf).collect::<Vec<_>>();
+                        // show why the input is invalid
+                        let error = state
+                            .submit_error
+                            .as_deref()
+                            .or(input_error.as_ref().map(|error| error.message.as_str()));
+                        if let Some(error) = error {
+                            ui.label(
+                                egui::RichText::new(error)
+                                    .font(font.clone())
+                                    .color(error_color),
+                            );
+                        }
 
-                            if !args.is_empty() {
-                                let command_name = args.remove(0);
-                                debug!("Command entered: `{command_name}`, with args: `{args:?}`");
+                        // show the arguments still expected
+                        if let Some(hint) = argument_hint(&config.commands, &state.buf) {
+                            ui.label(
+                                egui::RichText::new(hint)
+                                    .font(font.clone())
+                                    .color(Color32::GRAY),
+                            );
+                        }
 
-                                let command = config.commands.get(command_name.as_str());
+                        // show an example once a command name is fully typed
+                        if let Some(example) = config
+                            .examples
+                            .get(state.buf.trim_end())
+                            .and_then(|examples| examples.first())
+                        {
+                            ui.label(
+                                egui::RichText::new(
+                                    locale.format("example", &[("example", example)]),
+                                )
+                                .font(font.clone())
+                                .color(Color32::GRAY),
+                            );
+                        }
+                    });
+                }
 
-                                if command.is_some() {
-                                    command_entered
-                                        .send(ConsoleCommandEntered { command_name, args });
-                                } else {
-                                    debug!(
-                                        "Command not recognized, recognized commands: `{:?}`",
-                                        config.commands.keys().collect::<Vec<_>>()
-                                    );
+                accessibility.announce_suggestions(&shown_suggestions);
+
+                // Handle enter, escape, zoom, clearing and browsing history
+                shortcut_keys(
+                    ui,
+                    &text_edit_response,
+                    &mut state,
+                    &config,
+                    &mut commands,
+                    &keyboard_input_events,
+                    &keys,
+                );
 
-                                    state.scrollback.push("error: Invalid command".into());
-                                }
+                // Status bar
+                if !state.status.is_empty() {
+                    ui.with_layout(row_layout, |ui| {
+                        for (index, segment) in state.status.iter().enumerate() {
+                            if index > 0 {
+                                ui.separator();
                             }
+                            ui.label(
+                                egui::RichText::new(segment)
+                                    .font(font.clone())
+                                    .color(Color32::GRAY),
+                            );
+                        }
+                    });
+                }
 
-                            state.buf.clear();
+                // Command palette
+                if text_edit_response.has_focus()
+                    && ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::P))
+                {
+                    state.palette = Some(CommandPalette::default());
+                }
+                if let Some(palette) = &mut state.palette {
+                    match palette_ui(ui.ctx(), palette, &config, &font) {
+                        PaletteResponse::Open => {}
+                        PaletteResponse::Closed => {
+                            state.palette = None;
+                            ui.memory_mut(|m| m.request_focus(input_id));
+                        }
+                        PaletteResponse::Selected(name) => {
+                            state.palette = None;
+                            state.buf = format!("{name} ");
+                            set_cursor_pos(ui.ctx(), input_id, state.buf.chars().count());
+                            ui.memory_mut(|m| m.request_focus(input_id));
                         }
                     }
+                }
 
-                    // Clear on ctrl+l
-                    if keyboard_input_events
-                        .iter()
-                        .any(|&k| k.state.is_pressed() && k.key_code == KeyCode::KeyL)
-                        && (keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]))
-                    {
-                        state.scrollback.clear();
-                    }
+                // Record changes made to the input this frame for undo
+                if state.input_prompt.is_none() {
+                    let ConsoleState {
+                        buf, input_undo, ..
+                    } = &mut *state;
+                    input_undo.record(buf);
+                }
 
-                    // Handle up and down through history
-                    if text_edit_response.has_focus()
-                        && ui.input(|i| i.key_pressed(egui::Key::ArrowUp))
-                        && state.history.len() > 1
-                        && state.history_index < state.history.len() - 1
-                    {
-                        if state.history_index == 0 && !state.buf.trim().is_empty() {
-                            *state.history.get_mut(0).unwrap() = state.buf.clone();
-                        }
+                // Focus on input according to the focus policy without taking it from other
+                // widgets, requesting it every frame interrupts IME composition
+                let (nothing_focused, other_focused) = ui.memory(|m| {
+                    let focused = m.focused();
+                    (
+                        focused.is_none(),
+                        focused.is_some_and(|id| id != text_edit_response.id),
+                    )
+                });
+                let opened = console_open.is_changed() && !other_focused;
+                let grab = match config.focus_policy {
+                    InputFocusPolicy::Keep => opened || nothing_focused,
+                    InputFocusPolicy::OnOpen => opened,
+                    InputFocusPolicy::Manual => false,
+                };
+                if grab {
+                    ui.memory_mut(|m| m.request_focus(text_edit_response.id));
+                }
+                state.input_id = Some(text_edit_response.id);
+                has_focus.set_if_neq(ConsoleHasFocus {
+                    focused: text_edit_response.has_focus() || state.palette.is_some(),
+                });
+            });
+        };
 
-                        state.history_index += 1;
-                        let previous_item = state.history.get(state.history_index).unwrap().clone();
-                        state.buf = previous_item.to_string();
+        show_docked(ctx, &config, target, &font, contents);
+    }
+}
 
-                        set_cursor_pos(ui.ctx(), text_edit_response.id, state.buf.len());
-                    } else if text_edit_response.has_focus()
-                        && ui.input(|i| i.key_pressed(egui::Key::ArrowDown))
-                        && state.history_index > 0
-                    {
-                        state.history_index -= 1;
-                        let next_item = state.history.get(state.history_index).unwrap().clone();
-                        state.buf = next_item.to_string();
+/// Queues an input line of the console window for [`parse_and_dispatch`]
+#[cfg(feature = "ui")]
+pub(crate) fn submit_line(line: &str, state: &mut ConsoleState) {
+    state.history_index = 0;
+    state.input_undo = InputUndo::default();
+    state.vi_normal = false;
+    state.submitted.push_back(line.to_owned());
+}
 
-                        set_cursor_pos(ui.ctx(), text_edit_response.id, state.buf.len());
-                    }
+/// Splits an input line into a command and sends it through the middleware,
+/// if the source may run it and the command is registered and enabled. Blank lines are ignored.
+///
+/// An `if` line dispatches its command the same way if its comparison holds, see [`IfCommand`].
+///
+/// Returns the id of the sent command, `None` for a blank line or an `if` whose comparison
+/// doesn't hold.
+///
+/// Entered lines go through [`parse_and_dispatch`] instead. Only lines nobody typed call this
+/// directly: the commands of jobs and macros, which don't answer prompts and aren't echoed or
+/// stored in the history, and HTTP requests, which respond with the result right away.
+pub(crate) fn dispatch_line(
+    line: &str,
+    source: CommandSource,
+    config: &ConsoleConfiguration,
+    app_commands: &AppCommands,
+    locale: &ConsoleLocale,
+    middlewares: &mut ConsoleMiddlewares,
+    command_entered: &mut EventWriter<ConsoleCommandEntered>,
+) -> Result<Option<EventId<ConsoleCommandEntered>>, String> {
+    let mut args = Shlex::new(line).collect::<Vec<_>>();
+    if args.is_empty() {
+        return Ok(None);
+    }
+    let command_name = args.remove(0);
+    debug!("Command entered: `{command_name}`, with args: `{args:?}`");
+
+    if !middlewares.allows(&source, &command_name) {
+        Err(locale.format("permission-denied", &[("command", &command_name)]))
+    } else if !config.commands.contains_key(command_name.as_str()) {
+        debug!(
+            "Command not recognized, recognized commands: `{:?}`",
+            config.commands.keys().collect::<Vec<_>>()
+        );
+        Err(locale.get("invalid-command").to_owned())
+    } else if !config.is_command_enabled(&command_name) {
+        Err(locale.format("command-disabled", &[("command", &command_name)]))
+    } else if command_name == IfCommand::name() && !app_commands.contains(IfCommand::name()) {
+        let condition = IfCommand::try_parse_from(once(command_name).chain(args))
+            .map_err(|err| err.to_string())?;
+        let Some(command) = condition.evaluate(config) else {
+            return Ok(None);
+        };
+        let line =
+            shlex::try_join(command.iter().map(String::as_str)).map_err(|err| err.to_string())?;
+        dispatch_line(
+            &line,
+            source,
+            config,
+            app_commands,
+            locale,
+            middlewares,
+            command_entered,
+        )
+    } else {
+        let command = ConsoleCommandEntered {
+            command_name,
+            args,
+            source,
+        };
+        middlewares.dispatch(command, command_entered).map(Some)
+    }
+}
 
-                    // Focus on input
-                    ui.memory_mut(|m| m.request_focus(text_edit_response.id));
-                });
+/// Parses the lines submitted by the frontends, remote clients and players and dispatches their
+/// commands.
+///
+/// A pending [`InputPrompt`] is answered by the next line of the source it was asked to instead,
+/// the lines after it wait for the following frame as the command may prompt again. Lines of the
+/// console window are echoed and stored in its history, errors are printed to where the line
+/// came from.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn parse_and_dispatch(
+    mut submitted: EventReader<ConsoleInputSubmitted>,
+    mut waiting: Local<VecDeque<ConsoleInputSubmitted>>,
+    config: Res<ConsoleConfiguration>,
+    app_commands: Res<AppCommands>,
+    locale: Res<ConsoleLocale>,
+    mut state: ResMut<ConsoleState>,
+    mut middlewares: ResMut<ConsoleMiddlewares>,
+    mut command_entered: EventWriter<ConsoleCommandEntered>,
+    mut console_line: EventWriter<PrintConsoleLine>,
+    mut commands: Commands,
+) {
+    waiting.extend(submitted.read().cloned());
+    while let Some(input) = waiting.pop_front() {
+        if state.answer_prompt(&mut commands, input.line.clone(), &input.source) {
+            break;
+        }
+
+        if input.source == CommandSource::LocalUi {
+            let msg = format!("{}{}", state.prompt, input.line);
+            state.push_local(PrintConsoleLine {
+                is_command: true,
+                ..PrintConsoleLine::new(msg)
             });
+            let previous = state.history.get(1).map(String::as_str);
+            if config.history_policy.should_store(&input.line, previous) {
+                state.history.insert(1, input.line.clone());
+                if state.history.len() > config.history_size + 1 {
+                    state.history.pop_back();
+                }
+            }
+        }
+
+        if let Err(reason) = dispatch_line(
+            &input.line,
+            input.source.clone(),
+            &config,
+            &app_commands,
+            &locale,
+            &mut middlewares,
+            &mut command_entered,
+        ) {
+            console_line.send(
+                PrintConsoleLine::new(reason)
+                    .with_severity(ConsoleSeverity::Error)
+                    .with_target(input.source.clone()),
+            );
+        }
+    }
+}
+
+/// Indices of the history entries previewed around the current one, the draft at index 0 excluded
+#[cfg(feature = "ui")]
+fn history_window(len: usize, index: usize, entries: usize) -> RangeInclusive<usize> {
+    let first = index.saturating_sub(entries).max(1);
+    let last = (index + entries).min(len.saturating_sub(1));
+    first..=last
+}
+
+/// The console window, showing printed lines in its scrollback
+impl ConsoleFrontend for ConsoleState {
+    fn source(&self) -> CommandSource {
+        CommandSource::LocalUi
+    }
+
+    fn next_line(&mut self) -> Option<String> {
+        self.submitted.pop_front()
+    }
+
+    fn accepts(&self, line: &PrintConsoleLine, config: &ConsoleConfiguration) -> bool {
+        // pinned lines are shared by everyone using the window, whoever they reply to
+        line.pin.is_some()
+            || config.mirror_replies
+            || line
+                .target
+                .as_ref()
+                .map_or(true, |target| *target == CommandSource::LocalUi)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.scrollback.reserve(additional);
+    }
+
+    fn print_line(&mut self, line: &PrintConsoleLine, _config: &ConsoleConfiguration) {
+        let (clear_screen, erased) = line.erased();
+        match &line.pin {
+            Some(key) => {
+                self.pinned.insert(key.clone(), erased);
+            }
+            None => {
+                if clear_screen {
+                    self.scrollback.clear();
+                }
+                // a bare clear-screen sequence doesn't leave an empty line behind
+                if clear_screen && erased.line.is_empty() {
+                    return;
+                }
+                match self.scrollback.last_mut() {
+                    Some(last) if line.updates(last) => *last = erased,
+                    _ => self.scrollback.push(erased),
+                }
+            }
+        }
+    }
+
+    fn flush(&mut self, config: &ConsoleConfiguration) {
+        if let Some(budget) = config.scrollback_budget {
+            self.trim_scrollback(budget);
+        }
     }
 }
 
-pub(crate) fn receive_console_line(
-    mut console_state: ResMut<ConsoleState>,
-    mut events: EventReader<PrintConsoleLine>,
+/// Adds the channels of printed lines to the configuration, so they can be filtered
+pub(crate) fn register_channels(
+    mut config: ResMut<ConsoleConfiguration>,
+    mut lines: EventReader<PrintConsoleLine>,
 ) {
-    for event in events.read() {
-        let event: &PrintConsoleLine = event;
-        console_state.scrollback.push(event.line.clone());
+    for channel in lines.read().filter_map(|line| line.channel.as_ref()) {
+        if !config.channels.contains_key(channel) {
+            config
+                .channels
+                .insert(channel.clone(), ConsoleChannel::default());
+        }
     }
 }
 
@@ -625,22 +3069,758 @@ fn console_key_pressed(keyboard_input: &KeyboardInput, configured_keys: &[KeyCod
     false
 }
 
-#[cfg(feature = "ui")]
-fn set_cursor_pos(ctx: &Context, id: Id, pos: usize) {
-    if let Some(mut state) = TextEdit::load_state(ctx, id) {
-        state
-            .cursor
-            .set_char_range(Some(CCursorRange::one(CCursor::new(pos))));
-        state.store(ctx, id);
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use bevy::input::keyboard::{Key, NativeKey, NativeKeyCode};
     use bevy::input::ButtonState;
+    use clap::Parser;
 
     use super::*;
+    use crate as bevy_console;
+    use crate::frontend::print_to_frontend;
+    use crate::testing::test_app;
+    use crate::throttle::ThrottledLines;
+    use crate::ConsoleCommand;
+
+    #[test]
+    fn test_trim_scrollback() {
+        let mut state = ConsoleState::default();
+        for line in ["aaaa", "bbbb", "cccc"] {
+            state
+                .scrollback
+                .push(PrintConsoleLine::new(line.to_owned()));
+        }
+        state
+            .scrollback
+            .push(PrintConsoleLine::channel("net", "dd"));
+        assert_eq!(state.scrollback_bytes(), 17);
+
+        state.trim_scrollback(13);
+        assert_eq!(state.scrollback.len(), 3);
+        state.trim_scrollback(5);
+        assert_eq!(state.scrollback.len(), 1);
+        assert_eq!(state.scrollback[0].line, "dd");
+        state.trim_scrollback(0);
+        assert!(state.scrollback.is_empty());
+    }
+
+    #[test]
+    fn test_print_erase_sequences() {
+        let mut state = ConsoleState::default();
+        let config = ConsoleConfiguration::default();
+        for line in ["old", "\x1b[2J", "new\r\x1b[Knewer"] {
+            state.print_line(&PrintConsoleLine::new(line.to_owned()), &config);
+        }
+        assert_eq!(state.scrollback.len(), 1);
+        assert_eq!(state.scrollback[0].line, "newer");
+
+        // progress updates replace the last line of their channel
+        for line in ["0%", "\r50%", "\r100%"] {
+            state.print_line(&PrintConsoleLine::channel("stdout", line), &config);
+        }
+        state.print_line(&PrintConsoleLine::new("\rdone".to_owned()), &config);
+        let lines = state.scrollback.iter().map(|line| line.line.as_str());
+        assert_eq!(lines.collect::<Vec<_>>(), ["newer", "100%", "done"]);
+    }
+
+    #[test]
+    fn test_answer_prompt() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        #[derive(Resource)]
+        struct Password(String);
+
+        let mut app = App::new();
+        app.add_event::<PrintConsoleLine>()
+            .add_event::<CommandOutcome>()
+            .init_resource::<ConsoleState>();
+        app.world_mut().resource_mut::<ConsoleState>().input_prompt = Some(PendingPrompt {
+            prompt: InputPrompt::secret("password:"),
+            entered: None,
+            on_input: Box::new(|world, line| {
+                world.insert_resource(Password(line));
+                Ok(vec!["logged in".to_owned()])
+            }),
+        });
+
+        let answered = app.world_mut().run_system_once(
+            |mut state: ResMut<ConsoleState>, mut commands: Commands| {
+                state.answer_prompt(&mut commands, "hunter2".to_owned(), &CommandSource::LocalUi)
+            },
+        );
+        assert!(answered);
+        assert_eq!(app.world().resource::<Password>().0, "hunter2");
+
+        let state = app.world().resource::<ConsoleState>();
+        assert!(state.input_prompt.is_none());
+        assert_eq!(state.scrollback.len(), 1);
+        assert_eq!(state.scrollback[0].line, "password:");
+
+        let answered = app.world_mut().run_system_once(
+            |mut state: ResMut<ConsoleState>, mut commands: Commands| {
+                state.answer_prompt(&mut commands, "help".to_owned(), &CommandSource::LocalUi)
+            },
+        );
+        assert!(!answered);
+    }
+
+    #[test]
+    fn test_allow_console_in_states() {
+        #[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
+        enum GameState {
+            #[default]
+            InGame,
+            Paused,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(bevy::state::app::StatesPlugin)
+            .init_state::<GameState>()
+            .init_resource::<ConsoleState>()
+            .insert_resource(ConsoleOpen { open: true })
+            .allow_console_in_states([GameState::Paused]);
+
+        app.update();
+        assert!(!app.world().resource::<ConsoleOpen>().open);
+        assert!(!app
+            .world()
+            .resource::<ConsoleState>()
+            .restricted_by
+            .is_empty());
+
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Paused);
+        app.update();
+        assert!(app
+            .world()
+            .resource::<ConsoleState>()
+            .restricted_by
+            .is_empty());
+    }
+
+    #[test]
+    fn test_mirror_replies() {
+        let mut app = App::new();
+        app.add_event::<PrintConsoleLine>()
+            .init_resource::<ConsoleState>()
+            .init_resource::<ThrottledLines>()
+            .insert_resource(ConsoleConfiguration {
+                mirror_replies: false,
+                ..default()
+            })
+            .add_systems(Update, print_to_frontend::<ConsoleState>);
+        app.world_mut().send_event_batch([
+            PrintConsoleLine::new("everyone".to_owned()),
+            PrintConsoleLine::new("window".to_owned()).with_target(CommandSource::LocalUi),
+            PrintConsoleLine::new("terminal".to_owned()).with_target(CommandSource::Terminal),
+        ]);
+        app.update();
+
+        let scrollback = &app.world().resource::<ConsoleState>().scrollback;
+        let lines = scrollback.iter().map(|line| line.line.as_str());
+        assert!(lines.eq(["everyone", "window"]));
+    }
+
+    /// Repeats a word
+    #[derive(Parser, ConsoleCommand)]
+    #[command(name = "say", version = "1.0")]
+    struct SayCommand {
+        word: String,
+    }
+
+    fn say_command(mut say: ConsoleCommand<SayCommand>) {
+        while let Some(command) = say.take() {
+            match command {
+                Ok(SayCommand { word }) => say.reply(word),
+                Err(_) => say.reply("error"),
+            }
+        }
+    }
+
+    fn say_app() -> App {
+        let mut app = App::new();
+        app.add_event::<ConsoleCommandEntered>()
+            .add_event::<PrintConsoleLine>()
+            .add_event::<CommandOutcome>()
+            .add_systems(Update, say_command);
+        app
+    }
+
+    #[test]
+    fn test_repeated_invocations() {
+        let mut app = say_app();
+        let say = |word: &str, source| ConsoleCommandEntered {
+            command_name: "say".to_owned(),
+            args: vec![word.to_owned()],
+            source,
+        };
+        app.world_mut().send_event_batch([
+            say("hello", CommandSource::LocalUi),
+            say("world", CommandSource::Terminal),
+        ]);
+        app.update();
+
+        let events = app.world().resource::<Events<PrintConsoleLine>>();
+        let replies = events
+            .iter_current_update_events()
+            .map(|line| (line.line.as_str(), line.target.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            replies,
+            [
+                ("hello", Some(CommandSource::LocalUi)),
+                ("world", Some(CommandSource::Terminal))
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_reply_value() {
+        let mut app = App::new();
+        app.add_event::<ConsoleCommandEntered>()
+            .add_event::<PrintConsoleLine>()
+            .add_event::<CommandOutcome>()
+            .add_systems(Update, |mut say: ConsoleCommand<SayCommand>| {
+                while let Some(Ok(SayCommand { word })) = say.take() {
+                    say.reply_value(&BTreeMap::from([("word", word)]));
+                }
+            });
+        app.world_mut().send_event(ConsoleCommandEntered {
+            command_name: "say".to_owned(),
+            args: vec!["hi".to_owned()],
+            source: CommandSource::LocalUi,
+        });
+        app.update();
+
+        let lines = app.world().resource::<Events<PrintConsoleLine>>();
+        let line = lines.iter_current_update_events().next().unwrap();
+        assert_eq!(line.line, "{\n  \"word\": \"hi\"\n}");
+        assert_eq!(line.json.as_deref(), Some(r#"{"word":"hi"}"#));
+    }
+
+    #[test]
+    fn test_help_flag() {
+        let mut app = say_app();
+        let say = |args: &[&str]| ConsoleCommandEntered {
+            command_name: "say".to_owned(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            source: CommandSource::LocalUi,
+        };
+        app.world_mut()
+            .send_event_batch([say(&["--help"]), say(&["--version"]), say(&[])]);
+        app.update();
+
+        let lines = app.world().resource::<Events<PrintConsoleLine>>();
+        let lines = lines
+            .iter_current_update_events()
+            .map(|line| strip_ansi(&line.line))
+            .collect::<Vec<_>>();
+        assert!(lines[0].starts_with("Repeats a word"));
+        assert_eq!(lines[1].trim(), "say 1.0");
+        assert!(lines[2].starts_with("error:"));
+        assert_eq!(lines.last().unwrap(), "error");
+        assert_eq!(lines.iter().filter(|line| *line == "error").count(), 1);
+
+        let outcomes = app.world().resource::<Events<CommandOutcome>>();
+        let statuses = outcomes
+            .iter_current_update_events()
+            .map(|outcome| outcome.status)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                CommandStatus::Ok,
+                CommandStatus::Ok,
+                CommandStatus::InvalidArguments
+            ]
+        );
+    }
+
+    #[test]
+    fn test_status_markers() {
+        fn judge_command(mut say: ConsoleCommand<SayCommand>) {
+            while let Some(Ok(SayCommand { word })) = say.take() {
+                if word == "good" {
+                    say.ok();
+                } else {
+                    say.reply_failed(word);
+                }
+            }
+        }
+
+        let mut app = App::new();
+        app.add_event::<ConsoleCommandEntered>()
+            .add_event::<PrintConsoleLine>()
+            .add_event::<CommandOutcome>()
+            .init_resource::<ActiveStatusMarkers>()
+            .insert_resource(ConsoleConfiguration {
+                status_markers: StatusMarkers {
+                    ok: Some("\x1b[32mdone\x1b[0m".to_owned()),
+                    failed: None,
+                },
+                ..default()
+            })
+            .add_systems(Update, (sync_status_markers, judge_command).chain());
+        let say = |word: &str| ConsoleCommandEntered {
+            command_name: "say".to_owned(),
+            args: vec![word.to_owned()],
+            source: CommandSource::LocalUi,
+        };
+        app.world_mut().send_event_batch([say("good"), say("bad")]);
+        app.update();
+
+        let lines = app.world().resource::<Events<PrintConsoleLine>>();
+        let lines = lines
+            .iter_current_update_events()
+            .map(|line| (line.line.as_str(), line.severity))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                ("\x1b[32mdone\x1b[0m", ConsoleSeverity::Info),
+                ("bad", ConsoleSeverity::Error)
+            ]
+        );
+        let outcomes = app.world().resource::<Events<CommandOutcome>>();
+        assert_eq!(outcomes.iter_current_update_events().count(), 2);
+    }
+
+    #[test]
+    fn test_pinned_lines() {
+        let mut app = App::new();
+        app.add_event::<PrintConsoleLine>()
+            .init_resource::<ConsoleState>()
+            .init_resource::<ThrottledLines>()
+            .init_resource::<ConsoleConfiguration>()
+            .add_systems(Update, print_to_frontend::<ConsoleState>);
+        app.world_mut().send_event_batch([
+            PrintConsoleLine::new("seed: 1".to_owned()).with_pin("seed"),
+            PrintConsoleLine::new("scrolling".to_owned()),
+            PrintConsoleLine::new("seed: 2".to_owned()).with_pin("seed"),
+            PrintConsoleLine::new("server: localhost".to_owned())
+                .with_pin("connect")
+                .with_target(CommandSource::Terminal),
+        ]);
+        app.update();
+
+        let state = app.world().resource::<ConsoleState>();
+        let lines = state.scrollback.iter().map(|line| line.line.as_str());
+        assert!(lines.eq(["scrolling"]));
+        let pinned = state.pinned.values().map(|line| line.line.as_str());
+        assert!(pinned.eq(["server: localhost", "seed: 2"]));
+    }
+
+    #[test]
+    fn test_history_policy() {
+        let policy = HistoryPolicy {
+            ignore_duplicates: true,
+            ignore_space: true,
+            max_entry_len: Some(8),
+        };
+        assert!(policy.should_store("help", Some("clear")));
+        assert!(!policy.should_store("help", Some("help")));
+        assert!(!policy.should_store(" secret", None));
+        assert!(!policy.should_store("echo 123456789", None));
+        assert!(!policy.should_store("  ", None));
+        assert!(HistoryPolicy::default().should_store("help", Some("help")));
+    }
+
+    #[test]
+    fn test_parse_and_dispatch() {
+        let mut app = test_app();
+        app.add_systems(Update, parse_and_dispatch);
+        app.world_mut().send_event_batch([
+            ConsoleInputSubmitted::new("say 'hello there'", CommandSource::LocalUi),
+            ConsoleInputSubmitted::new("  ", CommandSource::Terminal),
+            ConsoleInputSubmitted::new("shout hi", CommandSource::Terminal),
+        ]);
+        app.update();
+
+        let entered = app.world().resource::<Events<ConsoleCommandEntered>>();
+        let entered = entered.iter_current_update_events().collect::<Vec<_>>();
+        assert_eq!(
+            entered,
+            [&ConsoleCommandEntered {
+                command_name: "say".to_owned(),
+                args: vec!["hello there".to_owned()],
+                source: CommandSource::LocalUi,
+            }]
+        );
+        let lines = app.world().resource::<Events<PrintConsoleLine>>();
+        let errors = lines.iter_current_update_events().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, "error: Invalid command");
+        assert_eq!(errors[0].target, Some(CommandSource::Terminal));
+
+        // only lines of the console window are echoed and stored in its history
+        let state = app.world().resource::<ConsoleState>();
+        assert!(state.history.iter().eq(["", "say 'hello there'"]));
+        assert_eq!(state.scrollback.len(), 1);
+        assert!(state.scrollback[0].is_command);
+    }
+
+    #[test]
+    fn test_if_dispatches_on_vars() {
+        let mut app = test_app();
+        app.add_systems(Update, parse_and_dispatch);
+        let mut config = app.world_mut().resource_mut::<ConsoleConfiguration>();
+        config
+            .commands
+            .insert("if", IfCommand::command().no_binary_name(true));
+        config.vars.insert("mode".to_owned(), "hard".to_owned());
+        let platform = format!("if platform == {} then say os", std::env::consts::OS);
+        app.world_mut().send_event_batch(
+            [
+                platform.as_str(),
+                "if mode == easy then say easy",
+                "if mode != easy then say 'not easy'",
+                "if level == 1 then say level",
+                "if mode == hard then shout hi",
+                "if mode == hard",
+            ]
+            .map(|line| ConsoleInputSubmitted::new(line, CommandSource::Terminal)),
+        );
+        app.update();
+
+        let entered = app.world().resource::<Events<ConsoleCommandEntered>>();
+        let said = entered
+            .iter_current_update_events()
+            .map(|command| command.args.join(" "))
+            .collect::<Vec<_>>();
+        assert_eq!(said, ["os", "not easy"]);
+        // the command of the `if` is checked like any other, the `if` itself has to be complete
+        let lines = app.world().resource::<Events<PrintConsoleLine>>();
+        let errors = lines.iter_current_update_events().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].line, "error: Invalid command");
+    }
+
+    /// Pauses the game of the app
+    #[derive(Parser, ConsoleCommand)]
+    #[command(name = "pause")]
+    struct AppPauseCommand;
+
+    #[test]
+    fn test_app_command_replaces_builtin() {
+        use crate::commands::time::{pause_command, PauseCommand, PendingSteps};
+
+        fn app_pause_command(mut pause: ConsoleCommand<AppPauseCommand>) {
+            while let Some(Ok(AppPauseCommand)) = pause.take() {
+                pause.reply_ok("game paused");
+            }
+        }
+
+        for app_first in [false, true] {
+            let mut app = App::new();
+            app.add_event::<ConsoleCommandEntered>()
+                .add_event::<PrintConsoleLine>()
+                .add_event::<CommandOutcome>()
+                .init_resource::<ConsoleConfiguration>()
+                .init_resource::<ConsoleLocale>()
+                .init_resource::<PendingSteps>()
+                .init_resource::<Time<Virtual>>();
+            if app_first {
+                app.add_console_command::<AppPauseCommand, _>(app_pause_command);
+                add_builtin_command::<PauseCommand, _>(&mut app, pause_command);
+            } else {
+                add_builtin_command::<PauseCommand, _>(&mut app, pause_command);
+                app.add_console_command::<AppPauseCommand, _>(app_pause_command);
+            }
+            app.world_mut().send_event(ConsoleCommandEntered {
+                command_name: "pause".to_owned(),
+                args: vec![],
+                source: CommandSource::LocalUi,
+            });
+            app.update();
+
+            let config = app.world().resource::<ConsoleConfiguration>();
+            assert_eq!(
+                config.commands["pause"]
+                    .get_about()
+                    .map(ToString::to_string),
+                Some("Pauses the game of the app".to_owned())
+            );
+            assert!(!app.world().resource::<Time<Virtual>>().is_paused());
+            let outcomes = app.world().resource::<Events<CommandOutcome>>();
+            assert_eq!(outcomes.iter_current_update_events().count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_touch_configuration() {
+        let touch = ConsoleTheme::touch();
+        let theme = ConsoleTheme::default();
+        assert!(touch.font_size > theme.font_size);
+        assert!(touch.line_spacing > theme.line_spacing);
+        assert_eq!(touch.severity_styles, theme.severity_styles);
+
+        let config = ConsoleConfiguration::default();
+        assert_eq!(
+            config.touch,
+            cfg!(any(target_os = "android", target_os = "ios"))
+        );
+        let plugin = crate::ConsolePlugin::new().with_touch();
+        assert!(plugin.config.touch);
+        assert_eq!(plugin.config.theme, touch);
+    }
+
+    #[test]
+    fn test_prompts_answered_by_their_source() {
+        #[derive(Default, Resource)]
+        struct Answers(Vec<String>);
+
+        fn pending(from: &'static str) -> PendingPrompt {
+            PendingPrompt {
+                prompt: InputPrompt::new("name?"),
+                entered: None,
+                on_input: Box::new(move |world, line| {
+                    world
+                        .resource_mut::<Answers>()
+                        .0
+                        .push(format!("{from}: {line}"));
+                    Ok(Vec::new())
+                }),
+            }
+        }
+
+        let mut app = test_app();
+        app.init_resource::<Answers>()
+            .add_systems(Update, parse_and_dispatch);
+        let player = CommandSource::Player(PlayerId(1));
+        let mut state = app.world_mut().resource_mut::<ConsoleState>();
+        state.input_prompt = Some(pending("local"));
+        state
+            .remote_prompts
+            .insert(player.clone(), pending("player"));
+
+        app.world_mut().send_event_batch([
+            ConsoleInputSubmitted::new("say hi", CommandSource::Player(PlayerId(2))),
+            ConsoleInputSubmitted::new("alice", player),
+            ConsoleInputSubmitted::new("bob", CommandSource::LocalUi),
+        ]);
+        app.update();
+        // players without a prompt run commands, the local prompt waits for the console window
+        let entered = app.world().resource::<Events<ConsoleCommandEntered>>();
+        assert_eq!(entered.iter_current_update_events().count(), 1);
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<Answers>().0,
+            ["player: alice", "local: bob"]
+        );
+        let state = app.world().resource::<ConsoleState>();
+        assert!(state.input_prompt.is_none());
+        assert!(state.remote_prompts.is_empty());
+        // only answers of the console window are echoed
+        assert!(state
+            .scrollback
+            .iter()
+            .map(|line| line.line.as_str())
+            .eq(["name? bob"]));
+    }
+
+    #[derive(Parser)]
+    struct HintCommand {
+        target: String,
+        count: Option<u32>,
+        #[arg(long)]
+        times: Option<u32>,
+        #[arg(long, value_enum)]
+        rarity: Option<Rarity>,
+    }
+
+    #[derive(Clone, clap::ValueEnum)]
+    enum Rarity {
+        Common,
+        Rare,
+    }
+
+    #[derive(Parser)]
+    struct HintSubcommands {
+        #[command(subcommand)]
+        subcommand: HintSubcommand,
+    }
+
+    #[derive(clap::Subcommand)]
+    enum HintSubcommand {
+        List,
+        Show { name: String },
+    }
+
+    #[test]
+    #[cfg(feature = "ui")]
+    fn test_history_window() {
+        assert_eq!(history_window(10, 1, 2), 1..=3);
+        assert_eq!(history_window(10, 5, 2), 3..=7);
+        assert_eq!(history_window(10, 9, 2), 7..=9);
+        assert_eq!(history_window(3, 1, 0), 1..=1);
+    }
+
+    #[test]
+    #[cfg(feature = "ui")]
+    fn test_egui_style() {
+        let theme = ConsoleTheme::default();
+        let font = FontId::monospace(16.0);
+        let style = theme.egui_style(&font);
+        assert_eq!(style.text_styles[&egui::TextStyle::Button], font);
+        assert_eq!(style.text_styles[&egui::TextStyle::Body], font);
+        assert_eq!(
+            style.visuals.window_fill,
+            Color32::from(theme.background_color)
+        );
+        assert_eq!(
+            style.visuals.override_text_color,
+            Some(theme.foreground_color.into())
+        );
+        // spacing isn't taken from the app's style
+        assert_eq!(style.spacing, egui::Style::default().spacing);
+    }
+
+    #[test]
+    fn test_validate_input() {
+        let commands = BTreeMap::from([("give", HintCommand::command().no_binary_name(true))]);
+
+        assert_eq!(validate_input(&commands, "give ", false), None);
+        assert_eq!(validate_input(&commands, "give sword --tim", false), None);
+        assert!(validate_input(&commands, "give ", true).is_some());
+
+        let error = validate_input(&commands, "give sword abc ", false).unwrap();
+        assert_eq!(error.span, Some(11..14));
+        let error = validate_input(&commands, "give sword --nope ", false).unwrap();
+        assert_eq!(error.span, Some(11..17));
+        assert!(error.message.contains("--nope"));
+
+        // the value being typed is checked against the possible values
+        assert_eq!(
+            validate_input(&commands, "give sword --rarity ra", false),
+            None
+        );
+        let error = validate_input(&commands, "give sword --rarity x", false).unwrap();
+        assert_eq!(error.span, Some(20..21));
+        assert!(error.message.contains("common, rare"));
+
+        // the command name may be indented or quoted, the value is searched after it
+        let error = validate_input(&commands, "  give sword ve ", false).unwrap();
+        assert_eq!(error.span, Some(13..15));
+        let error = validate_input(&commands, "'give' x ve ", false).unwrap();
+        assert_eq!(error.span, Some(9..11));
+        let commands = BTreeMap::from([("gïve", HintCommand::command().no_binary_name(true))]);
+        let error = validate_input(&commands, "\"gïve\" sword ï ", false).unwrap();
+        assert_eq!(error.span, Some(14..16));
+    }
+
+    #[test]
+    fn test_first_word_end() {
+        assert_eq!(first_word_end("give sword"), 4);
+        assert_eq!(first_word_end("  give"), 6);
+        assert_eq!(first_word_end("'gi ve' sword"), 7);
+        assert_eq!(first_word_end("gi\\ ve sword"), 6);
+        assert_eq!(first_word_end("ï"), 2);
+    }
+
+    #[test]
+    fn test_clap_error_lines() {
+        let err = HintCommand::command()
+            .no_binary_name(true)
+            .try_get_matches_from(["sword", "--time", "3"])
+            .unwrap_err();
+        let lines = clap_error_lines(&err, "give", &ConsoleLocale::default());
+        let text = lines
+            .iter()
+            .map(|line| line.line.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            text[0],
+            "\x1b[1m\x1b[31merror:\x1b[0m unexpected argument '\x1b[33m--time\x1b[0m' found"
+        );
+        assert!(text
+            .iter()
+            .any(|line| line.contains("tip:\x1b[0m a similar argument exists: '\x1b[32m--times")));
+        assert!(text
+            .iter()
+            .any(|line| line.starts_with("\x1b[1m\x1b[4mUsage:")));
+        let hint = lines.last().unwrap();
+        assert_eq!(hint.action.as_deref(), Some("help give"));
+        assert!(lines[..lines.len() - 1]
+            .iter()
+            .all(|line| line.action.is_none()));
+    }
+
+    #[test]
+    fn test_argument_hint() {
+        let commands = BTreeMap::from([
+            ("give", HintCommand::command()),
+            ("diag", HintSubcommands::command()),
+        ]);
+        let hint = |input| argument_hint(&commands, input);
+
+        assert_eq!(hint("give"), None);
+        assert_eq!(hint("give "), Some("<TARGET> [COUNT] [OPTIONS]".to_owned()));
+        assert_eq!(
+            hint("give swo"),
+            Some("<TARGET> [COUNT] [OPTIONS]".to_owned())
+        );
+        assert_eq!(hint("give sword "), Some("[COUNT] [OPTIONS]".to_owned()));
+        assert_eq!(
+            hint("give --times 2 sword "),
+            Some("[COUNT] [OPTIONS]".to_owned())
+        );
+        assert_eq!(hint("give sword 3 "), Some("[OPTIONS]".to_owned()));
+        assert_eq!(hint("diag "), Some("<list|show>".to_owned()));
+        assert_eq!(hint("diag show "), Some("<NAME>".to_owned()));
+        assert_eq!(hint("unknown "), None);
+    }
+
+    #[test]
+    fn test_command_suggestions_collapse_groups() {
+        let commands = ["clear", "net.stats", "render.msaa", "render.wireframe"];
+
+        assert_eq!(command_suggestions(commands, "c"), vec!["clear"]);
+        assert_eq!(command_suggestions(commands, "re"), vec!["render."]);
+        assert_eq!(
+            command_suggestions(commands, "render."),
+            vec!["render.msaa", "render.wireframe"]
+        );
+        assert_eq!(
+            command_suggestions(commands, ""),
+            vec!["clear", "net.", "render."]
+        );
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn test_fuzzy_suggestions() {
+        let commands = ["teleport", "teleport_waypoint", "tp", "net.stats"];
+
+        let suggestions = fuzzy_suggestions(commands, "tpw");
+        assert_eq!(
+            suggestions,
+            vec![("teleport_waypoint".to_owned(), vec![0, 4, 9])]
+        );
+        // prefix matches come first, then equally good matches by name
+        let suggestions = fuzzy_suggestions(commands, "tp");
+        let names = suggestions.iter().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names, ["tp", "teleport", "teleport_waypoint"]);
+        assert_eq!(suggestions[0].1, vec![0, 1]);
+        assert_eq!(fuzzy_suggestions(commands, "ns")[0].0, "net.stats");
+        assert!(fuzzy_suggestions(commands, "tp 3").is_empty());
+    }
+
+    #[test]
+    fn test_open_policy() {
+        let mut keys = ButtonInput::<KeyCode>::default();
+        let modifier = OpenPolicy::RequireModifier(vec![KeyCode::ControlLeft]);
+        assert!(OpenPolicy::NeverWhileTyping.allows_open(false, &keys));
+        assert!(!OpenPolicy::NeverWhileTyping.allows_open(true, &keys));
+        assert!(OpenPolicy::AlwaysOpen.allows_open(true, &keys));
+        assert!(modifier.allows_open(false, &keys));
+        assert!(!modifier.allows_open(true, &keys));
+        keys.press(KeyCode::ControlLeft);
+        assert!(modifier.allows_open(true, &keys));
+    }
 
     #[test]
     fn test_console_key_pressed_scan_code() {
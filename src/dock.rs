@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::egui::{self, Context, FontId};
+
+use crate::surface::ConsoleSurface;
+use crate::{ConsoleConfiguration, ConsoleDock, ConsoleOpen};
+
+/// Marks the window spawned by [`ConsolePlugin::with_dedicated_window`](crate::ConsolePlugin::with_dedicated_window)
+#[derive(Component)]
+pub(crate) struct DedicatedConsoleWindow;
+
+/// Creates a startup system spawning the window the console is shown in, and opening the console
+pub(crate) fn spawn_dedicated_window(
+    window: Window,
+) -> impl FnMut(Commands, ResMut<ConsoleConfiguration>, ResMut<ConsoleOpen>) {
+    move |mut commands, mut config, mut console_open| {
+        config.window = Some(
+            commands
+                .spawn((window.clone(), DedicatedConsoleWindow))
+                .id(),
+        );
+        console_open.open = true;
+    }
+}
+
+/// The window or surface the console is shown in.
+///
+/// A surface takes precedence, falling back to the primary window once the configured one is
+/// closed.
+pub(crate) fn console_target(
+    config: &ConsoleConfiguration,
+    surfaces: &Query<Entity, With<ConsoleSurface>>,
+    windows: &Query<&mut Window>,
+    primary_window: &Query<Entity, With<PrimaryWindow>>,
+) -> Option<Entity> {
+    surfaces.iter().next().or_else(|| {
+        config
+            .window
+            .filter(|window| windows.contains(*window))
+            .or_else(|| primary_window.get_single().ok())
+    })
+}
+
+/// Lays out the contents of the console in the container of its [`ConsoleDock`], over the
+/// backdrop of its theme and with its egui style
+pub(crate) fn show_docked(
+    ctx: &Context,
+    config: &ConsoleConfiguration,
+    target: Entity,
+    font: &FontId,
+    contents: impl FnOnce(&mut egui::Ui),
+) {
+    if let Some(backdrop) = config.theme.backdrop_color {
+        ctx.layer_painter(egui::LayerId::background()).rect_filled(
+            ctx.screen_rect(),
+            0.0,
+            backdrop,
+        );
+    }
+    let frame = egui::Frame {
+        fill: config.theme.background_color.into(),
+        ..Default::default()
+    };
+
+    // a console filling its closed dedicated window shouldn't cover the primary window
+    let dock = match config.dock {
+        ConsoleDock::Fill if config.window.is_some_and(|window| window != target) => {
+            ConsoleDock::Floating
+        }
+        dock => dock,
+    };
+    // scoped to the console, the app's style is restored once it is laid out
+    let app_style = ctx.style();
+    if !config.theme.inherit_app_style {
+        ctx.set_style(config.theme.egui_style(font));
+    }
+    match dock {
+        ConsoleDock::Floating => {
+            egui::Window::new(&config.title_name)
+                .collapsible(config.collapsible)
+                .default_pos([config.left_pos, config.top_pos])
+                .default_size([config.width, config.height])
+                .resizable(config.resizable)
+                .movable(config.moveable)
+                .title_bar(config.show_title_bar)
+                .frame(frame)
+                .show(ctx, contents);
+        }
+        ConsoleDock::Left | ConsoleDock::Right => {
+            let panel = if dock == ConsoleDock::Left {
+                egui::SidePanel::left("console_dock")
+            } else {
+                egui::SidePanel::right("console_dock")
+            };
+            panel
+                .default_width(config.width)
+                .resizable(config.resizable)
+                .frame(frame)
+                .show(ctx, contents);
+        }
+        ConsoleDock::Top | ConsoleDock::Bottom => {
+            let panel = if dock == ConsoleDock::Top {
+                egui::TopBottomPanel::top("console_dock")
+            } else {
+                egui::TopBottomPanel::bottom("console_dock")
+            };
+            panel
+                .default_height(config.height)
+                .resizable(config.resizable)
+                .frame(frame)
+                .show(ctx, contents);
+        }
+        ConsoleDock::Fill => {
+            egui::CentralPanel::default()
+                .frame(frame)
+                .show(ctx, contents);
+        }
+    }
+    ctx.set_style(app_style);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawn_dedicated_window() {
+        let mut app = App::new();
+        app.init_resource::<ConsoleConfiguration>()
+            .init_resource::<ConsoleOpen>()
+            .add_systems(
+                Startup,
+                spawn_dedicated_window(Window {
+                    title: "Console".to_owned(),
+                    ..default()
+                }),
+            );
+        app.update();
+
+        let window = app
+            .world()
+            .resource::<ConsoleConfiguration>()
+            .window
+            .unwrap();
+        assert_eq!(app.world().get::<Window>(window).unwrap().title, "Console");
+        assert!(app.world().resource::<ConsoleOpen>().open);
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy_egui::egui::{FontData, FontFamily};
+use bevy_egui::EguiContexts;
+
+use crate::ConsoleConfiguration;
+
+/// Name of the egui font family registered by [`ConsolePlugin::with_font`](crate::ConsolePlugin::with_font)
+pub const CONSOLE_FONT_FAMILY: &str = "bevy_console";
+
+/// Raw TTF or OTF data of the console font
+#[derive(Asset, TypePath)]
+pub(crate) struct ConsoleFont(Vec<u8>);
+
+#[derive(Default)]
+pub(crate) struct ConsoleFontLoader;
+
+/// Error while loading a console font
+#[derive(Debug)]
+pub(crate) struct ConsoleFontError(std::io::Error);
+
+impl fmt::Display for ConsoleFontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not read console font: {}", self.0)
+    }
+}
+
+impl std::error::Error for ConsoleFontError {}
+
+impl AssetLoader for ConsoleFontLoader {
+    type Asset = ConsoleFont;
+    type Settings = ();
+    type Error = ConsoleFontError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(ConsoleFontError)?;
+        Ok(ConsoleFont(bytes))
+    }
+
+    /// Picked by asset type only, the `ttf` and `otf` extensions belong to the font loader of
+    /// `bevy_text`
+    fn extensions(&self) -> &[&str] {
+        &[]
+    }
+}
+
+#[derive(Resource)]
+pub(crate) struct ConsoleFontHandle(Handle<ConsoleFont>);
+
+/// Creates a startup system loading the console font at the given path
+pub(crate) fn load_console_font(path: String) -> impl FnMut(Commands, Res<AssetServer>) {
+    move |mut commands, asset_server| {
+        let handle = asset_server.load::<ConsoleFont>(path.clone());
+        commands.insert_resource(ConsoleFontHandle(handle));
+    }
+}
+
+/// Registers the loaded font as the [`CONSOLE_FONT_FAMILY`] family, keeping all other fonts,
+/// and switches the console theme to it.
+pub(crate) fn install_console_font(
+    mut events: EventReader<AssetEvent<ConsoleFont>>,
+    handle: Option<Res<ConsoleFontHandle>>,
+    fonts: Res<Assets<ConsoleFont>>,
+    mut egui_context: EguiContexts,
+    mut config: ResMut<ConsoleConfiguration>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    let loaded = events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0));
+    let (true, Some(font), Some(ctx)) = (loaded, fonts.get(&handle.0), egui_context.try_ctx_mut())
+    else {
+        return;
+    };
+
+    let mut definitions = ctx.fonts(|fonts| fonts.lock().fonts.definitions().clone());
+    definitions.font_data.insert(
+        CONSOLE_FONT_FAMILY.to_owned(),
+        FontData::from_owned(font.0.clone()),
+    );
+    // fall back to the monospace fonts for glyphs missing in the console font
+    let mut family = vec![CONSOLE_FONT_FAMILY.to_owned()];
+    family.extend(
+        definitions
+            .families
+            .get(&FontFamily::Monospace)
+            .cloned()
+            .unwrap_or_default(),
+    );
+    definitions
+        .families
+        .insert(FontFamily::Name(CONSOLE_FONT_FAMILY.into()), family);
+    ctx.set_fonts(definitions);
+
+    config.theme.font_family = Some(CONSOLE_FONT_FAMILY.to_owned());
+}
@@ -0,0 +1,240 @@
+use bevy::prelude::*;
+
+use crate::console::parse_and_dispatch;
+use crate::throttle::ThrottledLines;
+use crate::{
+    CommandSource, ConsoleConfiguration, ConsoleInputSubmitted, ConsoleOpen, ConsoleSet,
+    PrintConsoleLine,
+};
+
+/// Most lines read from a frontend per frame, the rest are left for the following frames
+pub(crate) const MAX_LINES_PER_FRAME: usize = 64;
+
+/// The user inputted a console interrupt, pressing Ctrl+C in the terminal
+#[cfg(any(feature = "rustyline", feature = "tui"))]
+#[derive(Event)]
+pub struct ConsoleInterrupted;
+
+/// The terminal input ended, e.g. the user pressed Ctrl+D on an empty line.
+///
+/// Sent before the [`ConsoleConfiguration::eof_action`] is carried out.
+#[cfg(any(feature = "rustyline", feature = "tui"))]
+#[derive(Event)]
+pub struct ConsoleEof;
+
+/// A way of showing the console and entering commands, like the egui console window or the
+/// terminal read with rustyline.
+///
+/// Lines entered in a frontend are parsed and dispatched like lines typed in the console window,
+/// answering a pending [`InputPrompt`](crate::InputPrompt) first. Printed lines are handed back
+/// to the frontends which [`accept`](Self::accepts) them.
+///
+/// # Example
+///
+/// ```
+/// # use std::collections::VecDeque;
+/// # use bevy::prelude::*;
+/// # use bevy_console::{
+/// #     AddConsoleFrontend, CommandSource, ConsoleConfiguration, ConsoleFrontend, PrintConsoleLine,
+/// # };
+/// /// Mirrors the console to an in-game computer screen
+/// #[derive(Default, Resource)]
+/// struct ComputerScreen {
+///     typed: VecDeque<String>,
+///     lines: Vec<String>,
+/// }
+///
+/// impl ConsoleFrontend for ComputerScreen {
+///     fn source(&self) -> CommandSource {
+///         CommandSource::LocalUi
+///     }
+///
+///     fn next_line(&mut self) -> Option<String> {
+///         self.typed.pop_front()
+///     }
+///
+///     fn print_line(&mut self, line: &PrintConsoleLine, _config: &ConsoleConfiguration) {
+///         self.lines.push(line.line.to_string());
+///     }
+/// }
+///
+/// App::new().add_console_frontend(ComputerScreen::default());
+/// ```
+pub trait ConsoleFrontend: Resource {
+    /// Where lines entered in this frontend come from, replies to them are targeted there
+    fn source(&self) -> CommandSource;
+
+    /// Takes the next line entered since the last call, if any
+    fn next_line(&mut self) -> Option<String>;
+
+    /// Whether a printed line is shown, by default lines which aren't targeted at another source
+    fn accepts(&self, line: &PrintConsoleLine, config: &ConsoleConfiguration) -> bool {
+        let _ = config;
+        line.target
+            .as_ref()
+            .map_or(true, |target| *target == self.source())
+    }
+
+    /// Makes room for the given number of lines about to be printed, so a frontend keeping
+    /// them can grow its buffers once per frame
+    fn reserve(&mut self, additional: usize) {
+        let _ = additional;
+    }
+
+    /// Shows a printed line
+    fn print_line(&mut self, line: &PrintConsoleLine, config: &ConsoleConfiguration);
+
+    /// Called once the lines printed this frame were handed over, e.g. to flush a buffer
+    fn flush(&mut self, config: &ConsoleConfiguration) {
+        let _ = config;
+    }
+
+    /// Shows or hides the frontend when the console is opened or closed
+    fn set_open(&mut self, open: bool) {
+        let _ = open;
+    }
+}
+
+/// Add a console frontend to Bevy app.
+pub trait AddConsoleFrontend {
+    /// Add a frontend, see [`ConsoleFrontend`].
+    ///
+    /// The frontend is inserted as a resource, lines entered in it are read after
+    /// [`ConsoleSet::ConsoleUI`] and printed lines are handed over in
+    /// [`ConsoleSet::PostCommands`].
+    fn add_console_frontend<F: ConsoleFrontend>(&mut self, frontend: F) -> &mut Self;
+}
+
+impl AddConsoleFrontend for App {
+    fn add_console_frontend<F: ConsoleFrontend>(&mut self, frontend: F) -> &mut Self {
+        self.insert_resource(frontend)
+            .init_resource::<ThrottledLines>()
+            .add_systems(
+                Update,
+                (
+                    read_frontend::<F>
+                        .after(ConsoleSet::ConsoleUI)
+                        .before(parse_and_dispatch),
+                    print_to_frontend::<F>.in_set(ConsoleSet::PostCommands),
+                    open_frontend::<F>.before(ConsoleSet::ConsoleUI),
+                ),
+            )
+    }
+}
+
+/// Submits the lines entered in the frontend, in the order they were entered
+pub(crate) fn read_frontend<F: ConsoleFrontend>(
+    mut frontend: ResMut<F>,
+    mut submitted: EventWriter<ConsoleInputSubmitted>,
+) {
+    let source = frontend.source();
+    for _ in 0..MAX_LINES_PER_FRAME {
+        let Some(line) = frontend.next_line() else {
+            return;
+        };
+        submitted.send(ConsoleInputSubmitted::new(line, source.clone()));
+    }
+}
+
+/// Hands the lines printed this frame to the frontend
+pub(crate) fn print_to_frontend<F: ConsoleFrontend>(
+    mut frontend: ResMut<F>,
+    config: Res<ConsoleConfiguration>,
+    throttled: Res<ThrottledLines>,
+    mut lines: EventReader<PrintConsoleLine>,
+) {
+    if lines.is_empty() {
+        return;
+    }
+    frontend.reserve(lines.len());
+    for line in throttled.read(&mut lines) {
+        if frontend.accepts(line, &config) {
+            frontend.print_line(line, &config);
+        }
+    }
+    frontend.flush(&config);
+}
+
+fn open_frontend<F: ConsoleFrontend>(mut frontend: ResMut<F>, console_open: Res<ConsoleOpen>) {
+    if console_open.is_changed() {
+        frontend.set_open(console_open.open);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bevy::ecs::event::ManualEventReader;
+
+    use super::*;
+
+    #[derive(Default, Resource)]
+    struct TestFrontend {
+        typed: VecDeque<String>,
+        printed: Vec<String>,
+        open: bool,
+    }
+
+    impl ConsoleFrontend for TestFrontend {
+        fn source(&self) -> CommandSource {
+            CommandSource::Terminal
+        }
+
+        fn next_line(&mut self) -> Option<String> {
+            self.typed.pop_front()
+        }
+
+        fn print_line(&mut self, line: &PrintConsoleLine, _config: &ConsoleConfiguration) {
+            self.printed.push(line.line.to_string());
+        }
+
+        fn set_open(&mut self, open: bool) {
+            self.open = open;
+        }
+    }
+
+    #[test]
+    fn test_frontend() {
+        let mut app = App::new();
+        let typed = (0..MAX_LINES_PER_FRAME + 1).map(|i| format!("echo {i}"));
+        app.add_event::<ConsoleInputSubmitted>()
+            .add_event::<PrintConsoleLine>()
+            .init_resource::<ConsoleConfiguration>()
+            .insert_resource(ConsoleOpen { open: true })
+            .add_console_frontend(TestFrontend {
+                typed: typed.collect(),
+                ..default()
+            });
+        app.world_mut().send_event_batch([
+            PrintConsoleLine::new("everyone".to_owned()),
+            PrintConsoleLine::new("terminal".to_owned()).with_target(CommandSource::Terminal),
+            PrintConsoleLine::new("window".to_owned()).with_target(CommandSource::LocalUi),
+        ]);
+
+        let mut reader = ManualEventReader::<ConsoleInputSubmitted>::default();
+        let mut submitted_lines = |app: &App| {
+            let events = app.world().resource::<Events<ConsoleInputSubmitted>>();
+            reader
+                .read(events)
+                .map(|input| input.line.clone())
+                .collect::<Vec<_>>()
+        };
+        app.update();
+        let lines = submitted_lines(&app);
+        assert_eq!(lines.len(), MAX_LINES_PER_FRAME);
+        assert!(lines
+            .iter()
+            .enumerate()
+            .all(|(i, line)| *line == format!("echo {i}")));
+        app.update();
+        assert_eq!(
+            submitted_lines(&app),
+            [format!("echo {MAX_LINES_PER_FRAME}")]
+        );
+
+        let frontend = app.world().resource::<TestFrontend>();
+        assert_eq!(frontend.printed, ["everyone", "terminal"]);
+        assert!(frontend.open);
+    }
+}
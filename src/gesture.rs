@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::prelude::*;
+
+use crate::console::ConsoleState;
+use crate::{ConsoleConfiguration, ConsoleOpen};
+
+/// Distance in logical pixels a finger may move before a gesture is no longer recognized
+const TOUCH_SLOP: f32 = 20.0;
+/// Longest time between the first finger touching and the last one lifting in a tap
+const TAP_DURATION: Duration = Duration::from_millis(500);
+/// Width and height in logical pixels of the area recognized as a corner
+const CORNER_SIZE: f32 = 80.0;
+
+/// Touch gesture toggling the console, for touchscreens without a toggle key, see
+/// [`ConsoleConfiguration::touch_toggle`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TouchToggle {
+    /// Tapping with the given number of fingers at once
+    Tap {
+        /// Number of fingers, e.g. 4
+        fingers: usize,
+    },
+    /// Holding a single finger still in a corner of the window
+    LongPress {
+        /// Corner to hold
+        corner: ScreenCorner,
+        /// How long to hold it
+        duration: Duration,
+    },
+}
+
+impl Default for TouchToggle {
+    fn default() -> Self {
+        Self::Tap { fingers: 4 }
+    }
+}
+
+/// A corner of the window, see [`TouchToggle::LongPress`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScreenCorner {
+    /// The top left corner
+    #[default]
+    TopLeft,
+    /// The top right corner
+    TopRight,
+    /// The bottom left corner
+    BottomLeft,
+    /// The bottom right corner
+    BottomRight,
+}
+
+impl ScreenCorner {
+    /// Whether the position lies in this corner of a window of the given size
+    fn contains(self, position: Vec2, size: Vec2) -> bool {
+        let left = position.x <= CORNER_SIZE;
+        let right = position.x >= size.x - CORNER_SIZE;
+        let top = position.y <= CORNER_SIZE;
+        let bottom = position.y >= size.y - CORNER_SIZE;
+        match self {
+            Self::TopLeft => top && left,
+            Self::TopRight => top && right,
+            Self::BottomLeft => bottom && left,
+            Self::BottomRight => bottom && right,
+        }
+    }
+}
+
+/// Recognizes a [`TouchToggle`] from the touches of the fingers on screen
+#[derive(Default)]
+pub(crate) struct GestureRecognizer {
+    /// Start positions of the fingers on screen, by touch id
+    touches: BTreeMap<u64, Vec2>,
+    /// Most fingers on screen at once since the first one touched
+    fingers: usize,
+    /// When the first finger touched
+    start: Duration,
+    /// Whether the first finger touched in the long press corner
+    in_corner: bool,
+    /// Whether the gesture can no longer toggle, because a finger moved or it already toggled
+    spent: bool,
+}
+
+impl GestureRecognizer {
+    /// Processes a touch at the given time, returns whether it completes the gesture
+    fn touch(
+        &mut self,
+        toggle: TouchToggle,
+        event: &TouchInput,
+        window_size: Vec2,
+        now: Duration,
+    ) -> bool {
+        match event.phase {
+            TouchPhase::Started => {
+                if self.touches.is_empty() {
+                    let in_corner = match toggle {
+                        TouchToggle::LongPress { corner, .. } => {
+                            corner.contains(event.position, window_size)
+                        }
+                        TouchToggle::Tap { .. } => false,
+                    };
+                    *self = Self {
+                        start: now,
+                        in_corner,
+                        ..default()
+                    };
+                }
+                self.touches.insert(event.id, event.position);
+                self.fingers = self.fingers.max(self.touches.len());
+            }
+            TouchPhase::Moved => {
+                if self
+                    .touches
+                    .get(&event.id)
+                    .is_some_and(|start| start.distance(event.position) > TOUCH_SLOP)
+                {
+                    self.spent = true;
+                }
+            }
+            TouchPhase::Ended => {
+                self.touches.remove(&event.id);
+                if let TouchToggle::Tap { fingers } = toggle {
+                    if self.touches.is_empty()
+                        && !self.spent
+                        && self.fingers == fingers
+                        && now - self.start <= TAP_DURATION
+                    {
+                        self.spent = true;
+                        return true;
+                    }
+                }
+            }
+            TouchPhase::Canceled => {
+                self.touches.remove(&event.id);
+                self.spent = true;
+            }
+        }
+        false
+    }
+
+    /// Returns whether a finger has been held long enough to complete the gesture
+    fn hold(&mut self, toggle: TouchToggle, now: Duration) -> bool {
+        let TouchToggle::LongPress { duration, .. } = toggle else {
+            return false;
+        };
+        let held = self.in_corner && self.fingers == 1 && self.touches.len() == 1;
+        if held && !self.spent && now - self.start >= duration {
+            self.spent = true;
+            return true;
+        }
+        false
+    }
+}
+
+/// Toggles the console when the [`ConsoleConfiguration::touch_toggle`] gesture is recognized
+pub(crate) fn toggle_by_gesture(
+    config: Res<ConsoleConfiguration>,
+    state: Res<ConsoleState>,
+    time: Res<Time<Real>>,
+    windows: Query<&Window>,
+    mut touch_events: EventReader<TouchInput>,
+    mut recognizer: Local<GestureRecognizer>,
+    mut console_open: ResMut<ConsoleOpen>,
+) {
+    let Some(toggle) = config.touch_toggle else {
+        touch_events.clear();
+        return;
+    };
+    let now = time.elapsed();
+    let mut recognized = false;
+    for event in touch_events.read() {
+        let window_size = windows
+            .get(event.window)
+            .map(|window| Vec2::new(window.width(), window.height()))
+            .unwrap_or_default();
+        recognized |= recognizer.touch(toggle, event, window_size, now);
+    }
+    recognized |= recognizer.hold(toggle, now);
+
+    // like the toggle keys, the gesture doesn't open the console in restricted states
+    if recognized && state.restricted_by.is_empty() {
+        console_open.open = !console_open.open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch_app(toggle: TouchToggle) -> (App, Entity) {
+        let mut app = App::new();
+        app.add_event::<TouchInput>()
+            .insert_resource(ConsoleConfiguration {
+                touch_toggle: Some(toggle),
+                ..default()
+            })
+            .init_resource::<ConsoleState>()
+            .init_resource::<ConsoleOpen>()
+            .init_resource::<Time<Real>>()
+            .add_systems(Update, toggle_by_gesture);
+        let window = app.world_mut().spawn(Window::default()).id();
+        (app, window)
+    }
+
+    fn touch(window: Entity, id: u64, phase: TouchPhase, x: f32, y: f32) -> TouchInput {
+        TouchInput {
+            phase,
+            position: Vec2::new(x, y),
+            window,
+            force: None,
+            id,
+        }
+    }
+
+    fn is_open(app: &App) -> bool {
+        app.world().resource::<ConsoleOpen>().open
+    }
+
+    #[test]
+    fn test_tap_toggle() {
+        let (mut app, window) = touch_app(TouchToggle::Tap { fingers: 2 });
+        app.world_mut().send_event_batch([
+            touch(window, 0, TouchPhase::Started, 100.0, 100.0),
+            touch(window, 1, TouchPhase::Started, 200.0, 100.0),
+        ]);
+        app.update();
+        assert!(!is_open(&app));
+        app.world_mut().send_event_batch([
+            touch(window, 0, TouchPhase::Ended, 100.0, 100.0),
+            touch(window, 1, TouchPhase::Ended, 200.0, 100.0),
+        ]);
+        app.update();
+        assert!(is_open(&app));
+
+        // a two finger swipe isn't a tap
+        app.world_mut().send_event_batch([
+            touch(window, 2, TouchPhase::Started, 100.0, 100.0),
+            touch(window, 3, TouchPhase::Started, 200.0, 100.0),
+            touch(window, 2, TouchPhase::Moved, 100.0, 300.0),
+            touch(window, 2, TouchPhase::Ended, 100.0, 300.0),
+            touch(window, 3, TouchPhase::Ended, 200.0, 100.0),
+        ]);
+        app.update();
+        assert!(is_open(&app));
+
+        // neither is a single finger tap
+        app.world_mut().send_event_batch([
+            touch(window, 4, TouchPhase::Started, 100.0, 100.0),
+            touch(window, 4, TouchPhase::Ended, 100.0, 100.0),
+        ]);
+        app.update();
+        assert!(is_open(&app));
+    }
+
+    #[test]
+    fn test_long_press_toggle() {
+        let (mut app, window) = touch_app(TouchToggle::LongPress {
+            corner: ScreenCorner::BottomRight,
+            duration: Duration::from_secs(1),
+        });
+        fn advance(app: &mut App, secs: f32) {
+            app.world_mut()
+                .resource_mut::<Time<Real>>()
+                .update_with_duration(Duration::from_secs_f32(secs));
+            app.update();
+        }
+        // the default window is 1280 by 720 logical pixels
+        app.world_mut()
+            .send_event(touch(window, 0, TouchPhase::Started, 1250.0, 700.0));
+        // the first update only starts the clock
+        advance(&mut app, 0.0);
+        advance(&mut app, 0.5);
+        assert!(!is_open(&app));
+        advance(&mut app, 0.6);
+        assert!(is_open(&app));
+        advance(&mut app, 2.0);
+        assert!(is_open(&app));
+        app.world_mut()
+            .send_event(touch(window, 0, TouchPhase::Ended, 1250.0, 700.0));
+
+        // holding outside of the corner does nothing
+        app.world_mut()
+            .send_event(touch(window, 1, TouchPhase::Started, 600.0, 700.0));
+        advance(&mut app, 2.0);
+        assert!(is_open(&app));
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt::Display;
+
+use crate::ConsoleCommand;
+
+/// Output of a handler wrapped with [`console_command_handler`](crate::console_command_handler),
+/// replied to the invocation it handled.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_console::{console_command_handler, AddConsoleCommand, ConsoleCommand};
+/// # use clap::Parser;
+/// /// Spawns the given number of enemies
+/// #[derive(Parser, ConsoleCommand)]
+/// #[command(name = "spawn")]
+/// struct SpawnCommand {
+///     count: usize,
+/// }
+///
+/// #[console_command_handler]
+/// fn spawn(SpawnCommand { count }: SpawnCommand, mut commands: Commands) -> Result<String, String> {
+///     if count > 100 {
+///         return Err(format!("{count} enemies are too many"));
+///     }
+///     for _ in 0..count {
+///         commands.spawn_empty();
+///     }
+///     Ok(format!("spawned {count} enemies"))
+/// }
+///
+/// App::new().add_console_command::<SpawnCommand, _>(spawn);
+/// ```
+pub trait HandlerOutput {
+    /// Replies to the command the output was returned for.
+    fn reply<T>(self, command: &mut ConsoleCommand<T>);
+}
+
+/// Prints `[ok]`
+impl HandlerOutput for () {
+    fn reply<T>(self, command: &mut ConsoleCommand<T>) {
+        command.ok();
+    }
+}
+
+/// Prints the string followed by `[ok]`
+impl HandlerOutput for String {
+    fn reply<T>(self, command: &mut ConsoleCommand<T>) {
+        command.reply_ok(self);
+    }
+}
+
+/// Prints every line followed by `[ok]`
+impl HandlerOutput for Vec<String> {
+    fn reply<T>(self, command: &mut ConsoleCommand<T>) {
+        for line in self {
+            command.reply(line);
+        }
+        command.ok();
+    }
+}
+
+/// Replies the output, or prints the error followed by `[failed]`
+impl<O: HandlerOutput, E: Display> HandlerOutput for Result<O, E> {
+    fn reply<T>(self, command: &mut ConsoleCommand<T>) {
+        match self {
+            Ok(output) => output.reply(command),
+            Err(err) => command.reply_failed(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use clap::Parser;
+
+    use crate as bevy_console;
+    use crate::{
+        console_command_handler, CommandOutcome, CommandSource, ConsoleCommandEntered,
+        PrintConsoleLine,
+    };
+
+    /// Halves a number
+    #[derive(Parser, crate::ConsoleCommand)]
+    #[command(name = "half")]
+    struct HalfCommand {
+        number: i32,
+    }
+
+    #[derive(Default, Resource)]
+    struct Halved(Vec<i32>);
+
+    #[console_command_handler]
+    fn half(HalfCommand { number }: HalfCommand, mut halved: ResMut<Halved>) -> Result<(), String> {
+        if number % 2 != 0 {
+            return Err(format!("{number} is odd"));
+        }
+        halved.0.push(number / 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_console_command_handler() {
+        let mut app = App::new();
+        app.add_event::<ConsoleCommandEntered>()
+            .add_event::<PrintConsoleLine>()
+            .add_event::<CommandOutcome>()
+            .init_resource::<Halved>()
+            .add_systems(Update, half);
+        let half = |number: &str| ConsoleCommandEntered {
+            command_name: "half".to_owned(),
+            args: vec![number.to_owned()],
+            source: CommandSource::LocalUi,
+        };
+        app.world_mut()
+            .send_event_batch([half("4"), half("3"), half("8")]);
+        app.update();
+
+        assert_eq!(app.world().resource::<Halved>().0, [2, 4]);
+        let lines = app
+            .world()
+            .resource::<Events<PrintConsoleLine>>()
+            .iter_current_update_events()
+            .map(|line| line.line.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, ["[ok]", "3 is odd", "[failed]", "[ok]"]);
+    }
+}
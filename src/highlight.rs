@@ -0,0 +1,210 @@
+#[cfg(feature = "ui")]
+use bevy_egui::egui::{
+    self,
+    text::{LayoutJob, LayoutSection},
+    FontId, TextFormat,
+};
+use regex::Regex;
+
+use crate::Colour;
+
+/// Styles the text of printed lines matching a regular expression in the console window, e.g.
+/// `ERROR`, player names or entity ids, see [`ConsoleConfiguration::highlights`].
+///
+/// Rules are applied in order, a later rule overriding the style set by an earlier one.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_console::{Colour, ConsolePlugin, HighlightRule};
+/// ConsolePlugin::new()
+///     .with_highlight(HighlightRule::new(r"\bERROR\b").unwrap().color(Colour::from_rgb(222, 56, 43)).bold())
+///     .with_highlight(HighlightRule::new(r"\d+v\d+").unwrap().underline());
+/// ```
+///
+/// [`ConsoleConfiguration::highlights`]: crate::ConsoleConfiguration::highlights
+#[derive(Clone, Debug)]
+pub struct HighlightRule {
+    /// Pattern of the highlighted text
+    pub pattern: Regex,
+    /// Text color
+    pub color: Option<Colour>,
+    /// Background color
+    pub background: Option<Colour>,
+    /// Bold text
+    pub bold: bool,
+    /// Underlined text
+    pub underline: bool,
+    /// Highlight the whole text of lines containing a match, instead of only the match
+    pub whole_line: bool,
+}
+
+impl HighlightRule {
+    /// Creates a rule highlighting text matching `pattern`, without any style yet.
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            pattern: Regex::new(pattern)?,
+            color: None,
+            background: None,
+            bold: false,
+            underline: false,
+            whole_line: false,
+        })
+    }
+
+    /// Sets the text color.
+    pub fn color(mut self, color: Colour) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the background color.
+    pub fn background(mut self, color: Colour) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Makes the text bold.
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Underlines the text.
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Highlights the whole text of lines containing a match.
+    pub fn whole_line(mut self) -> Self {
+        self.whole_line = true;
+        self
+    }
+
+    /// Applies the style of the rule to a section of text
+    #[cfg(feature = "ui")]
+    fn apply(&self, format: &mut TextFormat, font: &FontId) {
+        if let Some(color) = self.color {
+            format.color = color.into();
+        }
+        if let Some(background) = self.background {
+            format.background = background.into();
+        }
+        if self.bold {
+            // no support for bold font families in egui, like the ANSI bold style
+            format.font_id.size = font.size * 8.0 / 7.0;
+        }
+        if self.underline {
+            format.underline = egui::Stroke::new(1., format.color);
+        }
+    }
+}
+
+/// Highlights the text of the job from `start` on, splitting its sections at the highlighted
+/// ranges.
+///
+/// Returns the index of the section of the original job each section comes from.
+#[cfg(feature = "ui")]
+pub(crate) fn apply_highlights(
+    job: &mut LayoutJob,
+    start: usize,
+    rules: &[HighlightRule],
+    font: &FontId,
+) -> Vec<usize> {
+    let LayoutJob { text, sections, .. } = job;
+    let mut origins = (0..sections.len()).collect::<Vec<_>>();
+    let Some(highlighted) = text.get(start..) else {
+        return origins;
+    };
+    for rule in rules {
+        let ranges = if rule.whole_line {
+            rule.pattern
+                .is_match(highlighted)
+                .then_some(start..text.len())
+                .into_iter()
+                .collect::<Vec<_>>()
+        } else {
+            rule.pattern
+                .find_iter(highlighted)
+                .map(|found| start + found.start()..start + found.end())
+                .collect()
+        };
+        for range in ranges.into_iter().filter(|range| !range.is_empty()) {
+            split_section(sections, &mut origins, range.start);
+            split_section(sections, &mut origins, range.end);
+            for section in sections.iter_mut().filter(|section| {
+                range.start <= section.byte_range.start && section.byte_range.end <= range.end
+            }) {
+                rule.apply(&mut section.format, font);
+            }
+        }
+    }
+    origins
+}
+
+/// Splits the section containing the byte offset in two, unless it starts or ends there
+#[cfg(feature = "ui")]
+fn split_section(sections: &mut Vec<LayoutSection>, origins: &mut Vec<usize>, at: usize) {
+    let Some(index) = sections
+        .iter()
+        .position(|section| section.byte_range.start < at && at < section.byte_range.end)
+    else {
+        return;
+    };
+    let mut second = sections[index].clone();
+    second.byte_range.start = at;
+    second.leading_space = 0.0;
+    sections[index].byte_range.end = at;
+    sections.insert(index + 1, second);
+    origins.insert(index + 1, origins[index]);
+}
+
+#[cfg(all(test, feature = "ui"))]
+mod tests {
+    use bevy_egui::egui::Color32;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_highlights() {
+        let font = FontId::default();
+        let mut job = LayoutJob::default();
+        job.append(
+            "[net] ",
+            0.0,
+            TextFormat::simple(font.clone(), Color32::GRAY),
+        );
+        job.append(
+            "ERROR from ",
+            0.0,
+            TextFormat::simple(font.clone(), Color32::WHITE),
+        );
+        job.append(
+            "ferris",
+            0.0,
+            TextFormat::simple(font.clone(), Color32::BLUE),
+        );
+
+        let red = Colour::from_rgb(222, 56, 43);
+        let rules = [
+            HighlightRule::new("ERROR").unwrap().color(red),
+            HighlightRule::new("fer").unwrap().underline(),
+            // the channel prefix isn't highlighted
+            HighlightRule::new("net").unwrap().bold(),
+        ];
+        let origins = apply_highlights(&mut job, "[net] ".len(), &rules, &font);
+
+        let sections = job
+            .sections
+            .iter()
+            .map(|section| &job.text[section.byte_range.clone()])
+            .collect::<Vec<_>>();
+        assert_eq!(sections, ["[net] ", "ERROR", " from ", "fer", "ris"]);
+        assert_eq!(origins, [0, 1, 1, 2, 2]);
+        assert_eq!(job.sections[1].format.color, Color32::from(red));
+        assert_eq!(job.sections[2].format.color, Color32::WHITE);
+        assert!(job.sections[3].format.underline.width > 0.0);
+        assert_eq!(job.sections[0].format.font_id.size, font.size);
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::console::{dispatch_line, AppCommands};
+use crate::middleware::{report_outcomes, ConsoleMiddlewares};
+use crate::remote::{token_permission, ConnectionLimiter, ConnectionPermit};
+use crate::{
+    AuthFailed, CommandOutcome, CommandSource, CommandStatus, ConsoleCommandEntered,
+    ConsoleConfiguration, ConsoleLocale, ConsoleMacros, ConsoleScheduler, ConsoleSet,
+    PrintConsoleLine, RemotePermission,
+};
+
+/// Time a client has to send its request, and the app has to execute the command
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest accepted request body
+const MAX_BODY_LEN: usize = 64 * 1024;
+
+/// Longest accepted request line or header in bytes, including its line ending
+const MAX_LINE_LEN: u64 = 8 * 1024;
+
+/// Most headers accepted in a request
+const MAX_HEADERS: usize = 64;
+
+/// Settings of the HTTP endpoint, see [`ConsolePlugin::with_http_endpoint`](crate::ConsolePlugin::with_http_endpoint).
+///
+/// Commands are executed with `POST /command` requests with a `{ "line": "fps" }` body,
+/// authenticated with an `Authorization: Bearer <token>` header. The response contains the
+/// printed output and how the command finished:
+///
+/// ```json
+/// { "status": "ok", "output": ["fps: 60.00", "[ok]"] }
+/// ```
+///
+/// Replies made with [`ConsoleCommand::reply_value`](crate::ConsoleCommand::reply_value) are
+/// included as their JSON value instead of a string.
+///
+/// `status` is one of `ok`, `failed`, `invalid_arguments`, `unreported`, or `rejected` if the
+/// command wasn't executed at all.
+///
+/// The permission of the token only lasts for the request, commands it scheduled to run later,
+/// e.g. with `after` or `playback`, are cancelled once the response was sent.
+///
+/// At most 32 requests are handled at once, and an address which sent an invalid token is
+/// refused for a second, doubling with every further failure up to a minute.
+///
+/// The connection isn't encrypted, tunnel it through SSH or a VPN when exposing the endpoint
+/// beyond a trusted network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpEndpoint {
+    /// Address to listen on, e.g. `127.0.0.1:7780`
+    pub address: SocketAddr,
+    /// Accepted bearer tokens and the permission they grant
+    pub tokens: Vec<(String, RemotePermission)>,
+}
+
+impl HttpEndpoint {
+    /// Listens on `address`, accepting `token` with [`RemotePermission::Full`].
+    pub fn new(address: SocketAddr, token: impl Into<String>) -> Self {
+        Self {
+            address,
+            tokens: vec![(token.into(), RemotePermission::Full)],
+        }
+    }
+
+    /// Also accepts `token`, granting the given permission.
+    pub fn with_token(mut self, token: impl Into<String>, permission: RemotePermission) -> Self {
+        self.tokens.push((token.into(), permission));
+        self
+    }
+}
+
+#[derive(Deserialize)]
+struct CommandRequest {
+    line: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct CommandResponse {
+    status: &'static str,
+    output: Vec<Value>,
+}
+
+enum HttpMessage {
+    Command {
+        address: SocketAddr,
+        permission: RemotePermission,
+        line: String,
+        respond: Sender<CommandResponse>,
+    },
+    AuthFailed(SocketAddr),
+}
+
+/// Requests received by the listener thread, and the commands waiting for their outcome
+#[derive(Resource)]
+pub(crate) struct HttpRequests {
+    rx: Mutex<Receiver<HttpMessage>>,
+    pending: HashMap<SocketAddr, (Vec<Value>, Sender<CommandResponse>)>,
+}
+
+/// Listens for HTTP requests on a background thread
+pub(crate) fn setup_http_endpoint(app: &mut App, endpoint: &HttpEndpoint) {
+    let (tx, rx) = mpsc::channel();
+    let tokens = Arc::new(endpoint.tokens.clone());
+    match TcpListener::bind(endpoint.address) {
+        Ok(listener) => {
+            info!("Console HTTP endpoint listening on {}", endpoint.address);
+            let limiter = ConnectionLimiter::default();
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let Some(permit) = stream
+                        .peer_addr()
+                        .ok()
+                        .and_then(|address| limiter.admit(address.ip(), Instant::now()))
+                    else {
+                        continue;
+                    };
+                    let (tokens, tx) = (tokens.clone(), tx.clone());
+                    thread::spawn(move || {
+                        if let Err(err) = handle_request(stream, &permit, &tokens, &tx) {
+                            debug!("Console HTTP request failed: {err}");
+                        }
+                    });
+                }
+            });
+        }
+        Err(err) => warn!(
+            "Failed to start the console HTTP endpoint on {}: {err}",
+            endpoint.address
+        ),
+    }
+
+    if !app.world().contains_resource::<Events<AuthFailed>>() {
+        app.add_event::<AuthFailed>();
+    }
+    app.insert_resource(HttpRequests {
+        rx: Mutex::new(rx),
+        pending: HashMap::new(),
+    })
+    .add_systems(
+        Update,
+        (
+            receive_http_requests.in_set(ConsoleSet::ConsoleUI),
+            respond_http_requests
+                .in_set(ConsoleSet::PostCommands)
+                .after(report_outcomes),
+        ),
+    );
+}
+
+fn handle_request(
+    stream: TcpStream,
+    permit: &ConnectionPermit,
+    tokens: &[(String, RemotePermission)],
+    tx: &Sender<HttpMessage>,
+) -> io::Result<()> {
+    let address = stream.peer_addr()?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+
+    let Some((request_line, headers)) = read_head(&mut reader)? else {
+        let response = error("request line or headers too large");
+        return write_response(&stream, "431 Request Header Fields Too Large", &response);
+    };
+    let mut content_length = 0;
+    let mut authorization = None;
+    for header in &headers {
+        if let Some((name, value)) = header.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                "authorization" => authorization = Some(value.trim().to_owned()),
+                _ => {}
+            }
+        }
+    }
+
+    let mut request = request_line.split_whitespace();
+    let (status, response) = match (request.next(), request.next()) {
+        (Some("POST"), Some("/command")) if content_length > MAX_BODY_LEN => {
+            ("413 Payload Too Large", error("request body too large"))
+        }
+        (Some("POST"), Some("/command")) => {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            let permission = authorization
+                .as_deref()
+                .and_then(|authorization| authorization.strip_prefix("Bearer "))
+                .and_then(|token| token_permission(tokens, token));
+            permit.authenticated(permission.is_some(), Instant::now());
+            match (permission, serde_json::from_slice::<CommandRequest>(&body)) {
+                (None, _) => {
+                    let _ = tx.send(HttpMessage::AuthFailed(address));
+                    ("401 Unauthorized", error("invalid or missing bearer token"))
+                }
+                (Some(_), Err(err)) => ("400 Bad Request", error(&err.to_string())),
+                (Some(permission), Ok(CommandRequest { line })) => {
+                    let (respond, response) = mpsc::channel();
+                    let _ = tx.send(HttpMessage::Command {
+                        address,
+                        permission,
+                        line,
+                        respond,
+                    });
+                    match response.recv_timeout(REQUEST_TIMEOUT) {
+                        Ok(response) => ("200 OK", response),
+                        Err(_) => (
+                            "503 Service Unavailable",
+                            error("the command wasn't executed in time"),
+                        ),
+                    }
+                }
+            }
+        }
+        (Some(_), Some("/command")) => ("405 Method Not Allowed", error("use POST")),
+        _ => ("404 Not Found", error("not found, use POST /command")),
+    };
+    write_response(&stream, status, &response)
+}
+
+/// Writes a JSON response with the given status
+fn write_response(
+    mut stream: &TcpStream,
+    status: &str,
+    response: &CommandResponse,
+) -> io::Result<()> {
+    let body = serde_json::to_string(response).unwrap_or_default();
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Reads the request line and the headers, `None` if a line is longer than [`MAX_LINE_LEN`] or
+/// there are more than [`MAX_HEADERS`] headers
+fn read_head(reader: &mut impl BufRead) -> io::Result<Option<(String, Vec<String>)>> {
+    let Some(request_line) = read_head_line(reader)? else {
+        return Ok(None);
+    };
+    let mut headers = Vec::new();
+    loop {
+        let Some(header) = read_head_line(reader)? else {
+            return Ok(None);
+        };
+        if header.is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Ok(None);
+        }
+        headers.push(header);
+    }
+    Ok(Some((request_line, headers)))
+}
+
+/// Reads a line of the request head without its line ending, `None` if it is too long
+fn read_head_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    let read = reader.by_ref().take(MAX_LINE_LEN).read_line(&mut line)?;
+    if read as u64 == MAX_LINE_LEN && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(line.trim_end().to_owned()))
+}
+
+fn error(message: &str) -> CommandResponse {
+    CommandResponse {
+        status: "rejected",
+        output: vec![message.into()],
+    }
+}
+
+fn receive_http_requests(
+    mut requests: ResMut<HttpRequests>,
+    config: Res<ConsoleConfiguration>,
+    app_commands: Res<AppCommands>,
+    locale: Res<ConsoleLocale>,
+    mut middlewares: ResMut<ConsoleMiddlewares>,
+    mut command_entered: EventWriter<ConsoleCommandEntered>,
+    mut auth_failed: EventWriter<AuthFailed>,
+) {
+    let messages = match requests.rx.lock() {
+        Ok(rx) => rx.try_iter().collect::<Vec<_>>(),
+        Err(_) => return,
+    };
+    for message in messages {
+        match message {
+            HttpMessage::Command {
+                address,
+                permission,
+                line,
+                respond,
+            } => {
+                let source = CommandSource::Remote(address);
+                middlewares.grant(source.clone(), permission);
+                match dispatch_line(
+                    &line,
+                    source.clone(),
+                    &config,
+                    &app_commands,
+                    &locale,
+                    &mut middlewares,
+                    &mut command_entered,
+                ) {
+                    Ok(Some(_)) => {
+                        requests.pending.insert(address, (Vec::new(), respond));
+                    }
+                    Ok(None) if line.trim().is_empty() => {
+                        middlewares.revoke(&source);
+                        let _ = respond.send(error(locale.get("empty-command")));
+                    }
+                    // an `if` whose comparison doesn't hold
+                    Ok(None) => {
+                        middlewares.revoke(&source);
+                        let _ = respond.send(CommandResponse {
+                            status: "ok",
+                            output: Vec::new(),
+                        });
+                    }
+                    Err(reason) => {
+                        middlewares.revoke(&source);
+                        let _ = respond.send(error(&reason));
+                    }
+                }
+            }
+            HttpMessage::AuthFailed(address) => {
+                warn!("Console HTTP client {address} failed to authenticate");
+                auth_failed.send(AuthFailed { address });
+            }
+        }
+    }
+}
+
+/// Collects the output of requested commands and responds once they finished
+#[allow(clippy::too_many_arguments)]
+fn respond_http_requests(
+    mut requests: ResMut<HttpRequests>,
+    config: Res<ConsoleConfiguration>,
+    mut middlewares: ResMut<ConsoleMiddlewares>,
+    mut scheduler: ResMut<ConsoleScheduler>,
+    mut macros: ResMut<ConsoleMacros>,
+    mut lines: EventReader<PrintConsoleLine>,
+    mut outcomes: EventReader<CommandOutcome>,
+) {
+    if requests.pending.is_empty() {
+        lines.clear();
+        outcomes.clear();
+        return;
+    }
+
+    for line in lines.read() {
+        if let Some(CommandSource::Remote(address)) = &line.target {
+            if let Some((output, _)) = requests.pending.get_mut(address) {
+                let value = match &line.json {
+                    Some(json) => {
+                        serde_json::from_str(json).unwrap_or_else(|_| json.clone().into())
+                    }
+                    None => config.ansi.http.apply(&line.line).into_owned().into(),
+                };
+                output.push(value);
+            }
+        }
+    }
+    for outcome in outcomes.read() {
+        let CommandSource::Remote(address) = &outcome.command.source else {
+            continue;
+        };
+        if let Some((output, respond)) = requests.pending.remove(address) {
+            middlewares.revoke(&outcome.command.source);
+            scheduler.cancel_from(&outcome.command.source);
+            macros.stop_from(&outcome.command.source);
+            let status = match outcome.status {
+                CommandStatus::Ok => "ok",
+                CommandStatus::Failed => "failed",
+                CommandStatus::InvalidArguments => "invalid_arguments",
+                CommandStatus::Unreported => "unreported",
+            };
+            let _ = respond.send(CommandResponse { status, output });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(request: &str, respond: Option<CommandResponse>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client.write_all(request.as_bytes()).unwrap();
+        let (stream, address) = listener.accept().unwrap();
+        let permit = ConnectionLimiter::default()
+            .admit(address.ip(), Instant::now())
+            .unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let server = thread::spawn(move || {
+            let tokens = [("secret".to_owned(), RemotePermission::Full)];
+            handle_request(stream, &permit, &tokens, &tx).unwrap();
+        });
+        match (rx.recv(), respond) {
+            (Ok(HttpMessage::Command { line, respond, .. }), Some(response)) => {
+                assert_eq!(line, "fps");
+                respond.send(response).unwrap();
+            }
+            (Ok(HttpMessage::AuthFailed(_)), None) | (Err(_), None) => {}
+            _ => panic!("unexpected message"),
+        }
+        server.join().unwrap();
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    fn post(authorization: &str, body: &str) -> String {
+        format!(
+            "POST /command HTTP/1.1\r\nAuthorization: {authorization}\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[test]
+    fn test_handle_request() {
+        let response = request(
+            &post("Bearer secret", r#"{"line": "fps"}"#),
+            Some(CommandResponse {
+                status: "ok",
+                output: vec!["fps: 60.00".into()],
+            }),
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(r#"{"status":"ok","output":["fps: 60.00"]}"#));
+
+        let response = request(&post("Bearer wrong", r#"{"line": "fps"}"#), None);
+        assert!(response.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+
+        let response = request(&post("Bearer secret", "fps"), None);
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+
+        let response = request("GET /command HTTP/1.1\r\n\r\n", None);
+        assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+
+        let response = request("GET / HTTP/1.1\r\n\r\n", None);
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+
+    #[test]
+    fn test_read_head() {
+        let mut head = io::Cursor::new("POST /command HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}");
+        let (request_line, headers) = read_head(&mut head).unwrap().unwrap();
+        assert_eq!(request_line, "POST /command HTTP/1.1");
+        assert_eq!(headers, ["Content-Length: 2"]);
+
+        let long = format!(
+            "GET /{} HTTP/1.1\r\n\r\n",
+            "a".repeat(MAX_LINE_LEN as usize)
+        );
+        assert_eq!(read_head(&mut io::Cursor::new(long)).unwrap(), None);
+
+        let many = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-A: 1\r\n".repeat(MAX_HEADERS + 1)
+        );
+        assert_eq!(read_head(&mut io::Cursor::new(many)).unwrap(), None);
+        let most = format!("GET / HTTP/1.1\r\n{}\r\n", "X-A: 1\r\n".repeat(MAX_HEADERS));
+        assert!(read_head(&mut io::Cursor::new(most)).unwrap().is_some());
+    }
+}
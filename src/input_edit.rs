@@ -0,0 +1,312 @@
+use std::mem;
+
+/// Most undo steps kept for the input
+const MAX_UNDO: usize = 100;
+
+/// Undo and redo stacks of the console input.
+///
+/// Changes are recorded once per frame by comparing the input with its last known text, so text
+/// inserted by completion or history recall is undone like typing. Consecutive word characters
+/// typed one at a time are merged into a single step.
+#[derive(Default)]
+pub(crate) struct InputUndo {
+    undo: Vec<String>,
+    redo: Vec<String>,
+    current: String,
+    /// Whether the last change typed a word character
+    typing: bool,
+}
+
+impl InputUndo {
+    /// Records the input if it changed since the last call
+    pub(crate) fn record(&mut self, input: &str) {
+        if input == self.current {
+            return;
+        }
+        let typing = inserted_char(&self.current, input).is_some_and(is_word_char);
+        if typing && self.typing {
+            input.clone_into(&mut self.current);
+        } else {
+            self.undo
+                .push(mem::replace(&mut self.current, input.to_owned()));
+            if self.undo.len() > MAX_UNDO {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.typing = typing;
+    }
+
+    /// Returns the input before the last change
+    pub(crate) fn undo(&mut self) -> Option<String> {
+        let previous = self.undo.pop()?;
+        self.redo
+            .push(mem::replace(&mut self.current, previous.clone()));
+        self.typing = false;
+        Some(previous)
+    }
+
+    /// Returns the input before the last undo
+    pub(crate) fn redo(&mut self) -> Option<String> {
+        let next = self.redo.pop()?;
+        self.undo
+            .push(mem::replace(&mut self.current, next.clone()));
+        self.typing = false;
+        Some(next)
+    }
+}
+
+pub(crate) fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The character inserted into `old` to get `new`, if that's the only change
+fn inserted_char(old: &str, new: &str) -> Option<char> {
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(old.len().min(new.len()), |((index, _), _)| index);
+    let inserted = new[prefix..].chars().next()?;
+    (new[prefix + inserted.len_utf8()..] == old[prefix..]).then_some(inserted)
+}
+
+/// Most killed texts kept for yanking
+const MAX_KILLS: usize = 16;
+
+/// Readline style editing shortcuts of the console input, matching the terminal editor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LineEdit {
+    /// `Ctrl+W`: kill the whitespace delimited word before the cursor
+    KillWordBackward,
+    /// `Ctrl+U`: kill the text before the cursor
+    KillToStart,
+    /// `Ctrl+K`: kill the text after the cursor
+    KillToEnd,
+    /// `Ctrl+Y`: insert the most recently killed text
+    Yank,
+    /// `Alt+B`: move to the start of the previous word
+    WordBackward,
+    /// `Alt+F`: move to the end of the next word
+    WordForward,
+    /// `Ctrl+A`: move to the start of the line
+    Home,
+    /// `Ctrl+E`: move to the end of the line
+    End,
+}
+
+/// Texts removed by the kill shortcuts, most recent first
+#[derive(Default)]
+pub(crate) struct KillRing(Vec<String>);
+
+impl KillRing {
+    /// Applies the edit to the input, returning the new cursor position in characters
+    pub(crate) fn apply(&mut self, edit: LineEdit, input: &mut String, cursor: usize) -> usize {
+        let chars = input.chars().collect::<Vec<_>>();
+        let cursor = cursor.min(chars.len());
+        let byte = |index: usize| chars[..index].iter().map(|c| c.len_utf8()).sum::<usize>();
+        match edit {
+            LineEdit::KillWordBackward => {
+                let mut start = cursor;
+                while start > 0 && chars[start - 1].is_whitespace() {
+                    start -= 1;
+                }
+                while start > 0 && !chars[start - 1].is_whitespace() {
+                    start -= 1;
+                }
+                self.kill(input.drain(byte(start)..byte(cursor)).collect());
+                start
+            }
+            LineEdit::KillToStart => {
+                self.kill(input.drain(..byte(cursor)).collect());
+                0
+            }
+            LineEdit::KillToEnd => {
+                self.kill(input.split_off(byte(cursor)));
+                cursor
+            }
+            LineEdit::Yank => match self.0.first() {
+                Some(killed) => {
+                    input.insert_str(byte(cursor), killed);
+                    cursor + killed.chars().count()
+                }
+                None => cursor,
+            },
+            LineEdit::WordBackward => {
+                let mut index = cursor;
+                while index > 0 && !is_word_char(chars[index - 1]) {
+                    index -= 1;
+                }
+                while index > 0 && is_word_char(chars[index - 1]) {
+                    index -= 1;
+                }
+                index
+            }
+            LineEdit::WordForward => {
+                let mut index = cursor;
+                while index < chars.len() && !is_word_char(chars[index]) {
+                    index += 1;
+                }
+                while index < chars.len() && is_word_char(chars[index]) {
+                    index += 1;
+                }
+                index
+            }
+            LineEdit::Home => 0,
+            LineEdit::End => chars.len(),
+        }
+    }
+
+    fn kill(&mut self, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        self.0.insert(0, text);
+        self.0.truncate(MAX_KILLS);
+    }
+}
+
+/// Handles a key typed in vi normal mode, returning the new cursor position and whether insert
+/// mode is entered, or `None` for keys without a binding
+pub(crate) fn vi_normal_key(
+    key: char,
+    input: &mut String,
+    cursor: usize,
+    ring: &mut KillRing,
+    undo: &mut InputUndo,
+) -> Option<(usize, bool)> {
+    let len = input.chars().count();
+    let cursor = cursor.min(len);
+    let chars = input.chars().collect::<Vec<_>>();
+    let moved = |cursor| Some((cursor, false));
+    let inserting = |cursor| Some((cursor, true));
+    match key {
+        'i' => inserting(cursor),
+        'a' => inserting((cursor + 1).min(len)),
+        'I' => inserting(0),
+        'A' => inserting(len),
+        'h' => moved(cursor.saturating_sub(1)),
+        'l' => moved((cursor + 1).min(len)),
+        '0' | '^' => moved(0),
+        '$' => moved(len),
+        'w' => {
+            // start of the next word
+            let mut index = cursor;
+            while index < len && is_word_char(chars[index]) {
+                index += 1;
+            }
+            while index < len && !is_word_char(chars[index]) {
+                index += 1;
+            }
+            moved(index)
+        }
+        'b' => moved(ring.apply(LineEdit::WordBackward, input, cursor)),
+        'e' => moved(ring.apply(LineEdit::WordForward, input, (cursor + 1).min(len))),
+        'x' if cursor < len => {
+            let start = chars[..cursor].iter().map(|c| c.len_utf8()).sum::<usize>();
+            ring.kill(
+                input
+                    .drain(start..start + chars[cursor].len_utf8())
+                    .collect(),
+            );
+            moved(cursor)
+        }
+        'D' => moved(ring.apply(LineEdit::KillToEnd, input, cursor)),
+        'C' => inserting(ring.apply(LineEdit::KillToEnd, input, cursor)),
+        'S' => {
+            ring.kill(mem::take(input));
+            inserting(0)
+        }
+        'p' => moved(ring.apply(LineEdit::Yank, input, (cursor + 1).min(len))),
+        'P' => moved(ring.apply(LineEdit::Yank, input, cursor)),
+        'u' => match undo.undo() {
+            Some(previous) => {
+                *input = previous;
+                moved(input.chars().count())
+            }
+            None => moved(cursor),
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inserted_char() {
+        assert_eq!(inserted_char("", "a"), Some('a'));
+        assert_eq!(inserted_char("fps", "fpas"), Some('a'));
+        assert_eq!(inserted_char("fps", "fps "), Some(' '));
+        assert_eq!(inserted_char("fps", "fp"), None);
+        assert_eq!(inserted_char("fps", "fpsab"), None);
+    }
+
+    #[test]
+    fn test_kill_ring() {
+        let mut ring = KillRing::default();
+        let mut input = "spawn cube  --at 1".to_owned();
+        assert_eq!(ring.apply(LineEdit::KillWordBackward, &mut input, 12), 6);
+        assert_eq!(input, "spawn --at 1");
+        assert_eq!(ring.apply(LineEdit::KillToEnd, &mut input, 10), 10);
+        assert_eq!(input, "spawn --at");
+        assert_eq!(ring.apply(LineEdit::Yank, &mut input, 5), 7);
+        assert_eq!(input, "spawn 1 --at");
+        assert_eq!(ring.apply(LineEdit::KillToStart, &mut input, 6), 0);
+        assert_eq!(input, "1 --at");
+        assert_eq!(ring.apply(LineEdit::Yank, &mut input, 0), 6);
+        assert_eq!(input, "spawn 1 --at");
+
+        assert_eq!(ring.apply(LineEdit::WordBackward, &mut input, 12), 10);
+        assert_eq!(ring.apply(LineEdit::WordBackward, &mut input, 10), 6);
+        assert_eq!(ring.apply(LineEdit::WordForward, &mut input, 0), 5);
+        assert_eq!(ring.apply(LineEdit::WordForward, &mut input, 5), 7);
+        assert_eq!(ring.apply(LineEdit::Home, &mut input, 5), 0);
+        assert_eq!(ring.apply(LineEdit::End, &mut input, 5), 12);
+    }
+
+    #[test]
+    fn test_vi_normal_key() {
+        let (mut ring, mut undo) = (KillRing::default(), InputUndo::default());
+        let mut input = "spawn cube".to_owned();
+        let mut key = |key, input: &mut String, cursor| {
+            vi_normal_key(key, input, cursor, &mut ring, &mut undo)
+        };
+        assert_eq!(key('w', &mut input, 0), Some((6, false)));
+        assert_eq!(key('b', &mut input, 6), Some((0, false)));
+        assert_eq!(key('e', &mut input, 0), Some((5, false)));
+        assert_eq!(key('$', &mut input, 0), Some((10, false)));
+        assert_eq!(key('A', &mut input, 0), Some((10, true)));
+        assert_eq!(key('q', &mut input, 0), None);
+
+        assert_eq!(key('x', &mut input, 0), Some((0, false)));
+        assert_eq!(input, "pawn cube");
+        assert_eq!(key('P', &mut input, 0), Some((1, false)));
+        assert_eq!(input, "spawn cube");
+        assert_eq!(key('C', &mut input, 6), Some((6, true)));
+        assert_eq!(input, "spawn ");
+    }
+
+    #[test]
+    fn test_input_undo() {
+        let mut undo = InputUndo::default();
+        for input in ["s", "sp", "spa", "spawn", "spawn ", "spawn c", "spawn cube"] {
+            undo.record(input);
+        }
+        assert_eq!(undo.undo().as_deref(), Some("spawn c"));
+        assert_eq!(undo.undo().as_deref(), Some("spawn "));
+        // "wn" was inserted at once, e.g. by completion
+        assert_eq!(undo.undo().as_deref(), Some("spawn"));
+        assert_eq!(undo.undo().as_deref(), Some("spa"));
+        assert_eq!(undo.undo().as_deref(), Some(""));
+        assert_eq!(undo.undo(), None);
+        assert_eq!(undo.redo().as_deref(), Some("spa"));
+        assert_eq!(undo.redo().as_deref(), Some("spawn"));
+
+        // a new change drops the redo steps
+        undo.record("help");
+        assert_eq!(undo.redo(), None);
+        assert_eq!(undo.undo().as_deref(), Some("spawn"));
+    }
+}
@@ -0,0 +1,388 @@
+use bevy::input::keyboard::KeyboardInput;
+use bevy::prelude::*;
+use bevy::window::Ime;
+use bevy_egui::egui::{
+    self, epaint::text::cursor::CCursor, text_selection::CCursorRange, Context, Id, TextEdit,
+};
+use std::mem;
+
+use crate::completion::{argument_completions, common_prefix, Completions};
+use crate::console::{submit_line, validate_input, ConsoleState};
+use crate::input_edit::{vi_normal_key, LineEdit};
+use crate::{CommandSource, ConsoleConfiguration, EditMode, PasteMode, PrintConsoleLine};
+
+const ZOOM_STEP: f32 = 0.1;
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 3.0;
+
+/// Splits pasted text into the commands it contains, if there is more than one
+fn pasted_lines(text: &str) -> Option<Vec<String>> {
+    let lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+    (lines.len() > 1).then_some(lines)
+}
+
+/// Executes multi-line pastes line by line instead of letting egui join them, asking for
+/// confirmation above [`ConsoleConfiguration::paste_confirm_threshold`] lines
+pub(crate) fn paste_lines(
+    ui: &mut egui::Ui,
+    input_id: Id,
+    state: &mut ConsoleState,
+    config: &ConsoleConfiguration,
+) {
+    if config.paste_mode == PasteMode::ExecuteLines
+        && state.input_prompt.is_none()
+        && ui.memory(|m| m.has_focus(input_id))
+    {
+        let pasted = ui.input_mut(|i| {
+            let mut pasted = None;
+            i.events.retain(|event| match event {
+                egui::Event::Paste(text) if pasted.is_none() => {
+                    pasted = pasted_lines(text);
+                    pasted.is_none()
+                }
+                _ => true,
+            });
+            pasted
+        });
+
+        if let Some(lines) = pasted {
+            if lines.len() > config.paste_confirm_threshold {
+                state.pending_paste = lines;
+            } else {
+                for line in lines {
+                    submit_line(&line, state);
+                }
+            }
+        }
+    }
+}
+
+/// Handles the undo and redo keys and those of the [`EditMode`] before the input sees them
+pub(crate) fn edit_keys(
+    ui: &mut egui::Ui,
+    input_id: Id,
+    state: &mut ConsoleState,
+    config: &ConsoleConfiguration,
+) {
+    // Undo and redo before egui handles the keys, its own undo is reset by every
+    // change made to the input outside of the text edit
+    if ui.memory(|m| m.has_focus(input_id)) && state.input_prompt.is_none() {
+        let (redo, undo) = ui.input_mut(|i| {
+            let shift_command = egui::Modifiers::COMMAND | egui::Modifiers::SHIFT;
+            (
+                i.consume_key(shift_command, egui::Key::Z),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z),
+            )
+        });
+        let restored = if redo {
+            state.input_undo.redo()
+        } else if undo {
+            state.input_undo.undo()
+        } else {
+            None
+        };
+        if let Some(input) = restored {
+            state.buf = input;
+            set_cursor_pos(ui.ctx(), input_id, state.buf.chars().count());
+        }
+    }
+
+    // Vi modal editing, normal mode keys are handled before egui sees them
+    let vi = config.edit_mode == EditMode::Vi && state.input_prompt.is_none();
+    if vi && ui.memory(|m| m.has_focus(input_id)) {
+        if !state.vi_normal {
+            state.vi_normal =
+                ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+        } else {
+            let typed = ui.input_mut(|i| {
+                let mut typed = Vec::new();
+                i.events.retain(|event| match event {
+                    egui::Event::Text(text) => {
+                        typed.extend(text.chars());
+                        false
+                    }
+                    egui::Event::Key { key, .. } => matches!(
+                        key,
+                        egui::Key::Enter
+                            | egui::Key::Escape
+                            | egui::Key::ArrowUp
+                            | egui::Key::ArrowDown
+                            | egui::Key::ArrowLeft
+                            | egui::Key::ArrowRight
+                            | egui::Key::Home
+                            | egui::Key::End
+                    ),
+                    _ => true,
+                });
+                typed
+            });
+            let mut cursor =
+                get_cursor_pos(ui.ctx(), input_id).unwrap_or_else(|| state.buf.chars().count());
+            for key in typed {
+                let ConsoleState {
+                    buf,
+                    kill_ring,
+                    input_undo,
+                    ..
+                } = &mut *state;
+                if let Some((moved, insert)) =
+                    vi_normal_key(key, buf, cursor, kill_ring, input_undo)
+                {
+                    cursor = moved;
+                    state.vi_normal = !insert;
+                }
+                if !state.vi_normal {
+                    break;
+                }
+            }
+            set_cursor_pos(ui.ctx(), input_id, cursor);
+        }
+    } else if state.vi_normal && !vi {
+        state.vi_normal = false;
+    }
+
+    // Readline style editing, consistent with the terminal editor
+    if config.edit_mode == EditMode::Emacs && ui.memory(|m| m.has_focus(input_id)) {
+        let edit = ui.input_mut(|i| {
+            use egui::{Key, Modifiers};
+            [
+                (Modifiers::CTRL, Key::W, LineEdit::KillWordBackward),
+                (Modifiers::CTRL, Key::U, LineEdit::KillToStart),
+                (Modifiers::CTRL, Key::K, LineEdit::KillToEnd),
+                (Modifiers::CTRL, Key::Y, LineEdit::Yank),
+                (Modifiers::ALT, Key::B, LineEdit::WordBackward),
+                (Modifiers::ALT, Key::F, LineEdit::WordForward),
+                (Modifiers::CTRL, Key::A, LineEdit::Home),
+                (Modifiers::CTRL, Key::E, LineEdit::End),
+            ]
+            .into_iter()
+            .find(|(modifiers, key, _)| i.consume_key(*modifiers, *key))
+            .map(|(_, _, edit)| edit)
+        });
+        if let Some(edit) = edit {
+            let cursor =
+                get_cursor_pos(ui.ctx(), input_id).unwrap_or_else(|| state.buf.chars().count());
+            let ConsoleState { buf, kill_ring, .. } = &mut *state;
+            let cursor = kill_ring.apply(edit, buf, cursor);
+            set_cursor_pos(ui.ctx(), input_id, cursor);
+        }
+    }
+}
+
+/// Completes argument values and file system paths on tab, cycling through the possible values
+/// once one is typed.
+///
+/// Returns the completions of the input to suggest.
+pub(crate) fn complete_on_tab(
+    ui: &mut egui::Ui,
+    response: &egui::Response,
+    state: &mut ConsoleState,
+    config: &ConsoleConfiguration,
+) -> Option<Completions> {
+    let prompting = state.input_prompt.is_some();
+    let complete = |buf: &str| {
+        (!prompting)
+            .then(|| argument_completions(&config.commands, buf, &config.completion_root))
+            .flatten()
+    };
+    // completing paths reads directories, so it only happens again when the input
+    // changes or tab is pressed
+    let tab_pressed = response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Tab));
+    if tab_pressed
+        || state
+            .completions
+            .as_ref()
+            .map_or(true, |(buf, _)| *buf != state.buf)
+    {
+        let completions = complete(&state.buf);
+        state.completions = Some((state.buf.clone(), completions));
+    }
+    let mut completions_shown = state
+        .completions
+        .as_ref()
+        .and_then(|(_, completions)| completions.clone());
+    let completed = completions_shown
+        .as_ref()
+        .filter(|(_, completions)| !completions.is_empty())
+        .filter(|_| {
+            response.has_focus()
+                && ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Tab))
+        })
+        .map(|(start, completions)| {
+            let typed = &state.buf[*start..];
+            let prefix = common_prefix(completions);
+            let completion = match completions.iter().position(|c| c == typed) {
+                Some(index) => completions[(index + 1) % completions.len()].as_str(),
+                None if prefix.len() > typed.len() => prefix,
+                None => completions[0].as_str(),
+            };
+            format!("{}{completion}", &state.buf[..*start])
+        });
+    if let Some(buf) = completed {
+        completions_shown = complete(&buf);
+        state.completions = Some((buf.clone(), completions_shown.clone()));
+        state.buf = buf;
+        set_cursor_pos(ui.ctx(), response.id, state.buf.chars().count());
+    }
+    completions_shown
+}
+
+/// Inserts the text composed by the IME into the focused input, and places the candidate window
+/// of the OS under it
+pub(crate) fn compose_ime(
+    ui: &egui::Ui,
+    response: &egui::Response,
+    ime_events: &[&Ime],
+    state: &mut ConsoleState,
+    window: Option<Mut<Window>>,
+) {
+    if response.has_focus() {
+        for event in ime_events {
+            match event {
+                Ime::Preedit { value, .. } => state.ime_preedit.clone_from(value),
+                Ime::Commit { value, .. } => {
+                    state.ime_preedit.clear();
+                    let cursor = get_cursor_pos(ui.ctx(), response.id)
+                        .unwrap_or_else(|| state.buf.chars().count());
+                    let byte_index = state
+                        .buf
+                        .char_indices()
+                        .nth(cursor)
+                        .map_or(state.buf.len(), |(index, _)| index);
+                    state.buf.insert_str(byte_index, value);
+                    set_cursor_pos(ui.ctx(), response.id, cursor + value.chars().count());
+                }
+                Ime::Enabled { .. } | Ime::Disabled { .. } => {
+                    state.ime_preedit.clear();
+                }
+            }
+        }
+
+        // Place the OS candidate window under the input
+        if let Some(mut window) = window {
+            let scale = ui.ctx().pixels_per_point() / window.scale_factor();
+            let position = response.rect.left_bottom();
+            let position = Vec2::new(position.x, position.y) * scale;
+            if window.ime_position != position {
+                window.ime_position = position;
+            }
+        }
+    }
+}
+
+/// Handles the keys submitting, cancelling and recalling the input, zooming and clearing the
+/// console
+pub(crate) fn shortcut_keys(
+    ui: &mut egui::Ui,
+    response: &egui::Response,
+    state: &mut ConsoleState,
+    config: &ConsoleConfiguration,
+    commands: &mut Commands,
+    keyboard_input_events: &[&KeyboardInput],
+    keys: &ButtonInput<KeyCode>,
+) {
+    // Handle enter
+    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+        if state.input_prompt.is_some() {
+            let line = mem::take(&mut state.buf);
+            state.answer_prompt(commands, line, &CommandSource::LocalUi);
+        } else if !state.pending_paste.is_empty() {
+            for line in mem::take(&mut state.pending_paste) {
+                submit_line(&line, state);
+            }
+        } else if state.buf.trim().is_empty() {
+            state.push_local(PrintConsoleLine::new(String::new()));
+        } else if let Some(error) = config
+            .block_invalid_input
+            .then(|| validate_input(&config.commands, &state.buf, true))
+            .flatten()
+        {
+            state.submit_error = Some(error.message);
+        } else {
+            let line = mem::take(&mut state.buf);
+            submit_line(&line, state);
+        }
+    }
+
+    // Cancel a pending paste or prompt on escape
+    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+        state.man_page = None;
+        state.pending_paste.clear();
+        state.cancel_prompt(commands);
+    }
+
+    // Zoom the console text, consuming the keys so egui doesn't zoom the whole UI
+    if response.has_focus() {
+        let (zoom_in, zoom_out, zoom_reset) = ui.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::Equals)
+                    || i.consume_key(egui::Modifiers::COMMAND, egui::Key::Plus),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::Minus),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::Num0),
+            )
+        });
+        if zoom_in {
+            state.zoom = (state.zoom + ZOOM_STEP).min(MAX_ZOOM);
+        } else if zoom_out {
+            state.zoom = (state.zoom - ZOOM_STEP).max(MIN_ZOOM);
+        } else if zoom_reset {
+            state.zoom = 1.0;
+        }
+    }
+
+    // Clear on ctrl+l
+    if keyboard_input_events
+        .iter()
+        .any(|&k| k.state.is_pressed() && k.key_code == KeyCode::KeyL)
+        && (keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]))
+    {
+        state.scrollback.clear();
+    }
+
+    // Handle up and down through history
+    let browse_history = response.has_focus() && state.input_prompt.is_none();
+    if browse_history
+        && ui.input(|i| i.key_pressed(egui::Key::ArrowUp))
+        && state.history.len() > 1
+        && state.history_index < state.history.len() - 1
+    {
+        if state.history_index == 0 && !state.buf.trim().is_empty() {
+            *state.history.get_mut(0).unwrap() = state.buf.clone();
+        }
+
+        state.history_index += 1;
+        let previous_item = state.history.get(state.history_index).unwrap().clone();
+        state.buf = previous_item.to_string();
+
+        set_cursor_pos(ui.ctx(), response.id, state.buf.chars().count());
+    } else if browse_history
+        && ui.input(|i| i.key_pressed(egui::Key::ArrowDown))
+        && state.history_index > 0
+    {
+        state.history_index -= 1;
+        let next_item = state.history.get(state.history_index).unwrap().clone();
+        state.buf = next_item.to_string();
+
+        set_cursor_pos(ui.ctx(), response.id, state.buf.chars().count());
+    }
+}
+
+pub(crate) fn get_cursor_pos(ctx: &Context, id: Id) -> Option<usize> {
+    TextEdit::load_state(ctx, id)
+        .and_then(|state| state.cursor.char_range())
+        .map(|range| range.primary.index)
+}
+
+pub(crate) fn set_cursor_pos(ctx: &Context, id: Id, pos: usize) {
+    if let Some(mut state) = TextEdit::load_state(ctx, id) {
+        state
+            .cursor
+            .set_char_range(Some(CCursorRange::one(CCursor::new(pos))));
+        state.store(ctx, id);
+    }
+}
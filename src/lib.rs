@@ -2,41 +2,651 @@
 #![deny(missing_docs)]
 
 use bevy::prelude::*;
-pub use bevy_console_derive::ConsoleCommand;
+pub use bevy_console_derive::{console_command_handler, console_handler, ConsoleCommand};
 
 #[cfg(feature = "ui")]
 use bevy_egui::EguiPlugin;
+#[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+use bevy_egui::EguiSet;
 
-#[cfg(feature = "rustyline")]
+#[cfg(all(
+    feature = "rustyline",
+    not(feature = "tui"),
+    not(feature = "release_disabled")
+))]
 use rustyline::setup_rustyline;
+#[cfg(all(feature = "tui", not(feature = "release_disabled")))]
+use tui::setup_tui;
 
+pub use crate::accessibility::{AnnounceFn, Announcement, ConsoleAccessibility};
+#[cfg(feature = "auto_register")]
+pub use crate::auto_register::{AutoCommand, AutoHandler};
+pub use crate::banner::{ConsoleBanner, MotdFn};
+pub use crate::color::{strip_ansi, AnsiMode, AnsiPolicy, Colour, ParseColourError};
+#[cfg(feature = "audio_commands")]
+use crate::commands::audio::{audio_command, AudioCommand, AudioMute};
+use crate::commands::channel::{channel_command, ChannelCommand};
 use crate::commands::clear::{clear_command, ClearCommand};
+use crate::commands::console::{console_stats_command, ConsoleStatsCommand};
+use crate::commands::diagnostics::{
+    diag_command, fps_command, update_diag_watches, DiagCommand, DiagWatches, FpsCommand,
+};
 use crate::commands::exit::{exit_command, ExitCommand};
+#[cfg(feature = "gameplay_commands")]
+use crate::commands::gameplay::{
+    despawn_command, parent_command, spawn_command, teleport_command, DespawnCommand,
+    ParentCommand, SpawnCommand, TeleportCommand,
+};
 use crate::commands::help::{help_command, HelpCommand};
+use crate::commands::man::{man_command, ManCommand};
+use crate::commands::perf::{instrument_schedules, perf_command, PerfCommand};
+use crate::commands::pin::{unpin_command, UnpinCommand};
+pub use crate::commands::record::ConsoleMacros;
+#[cfg(not(feature = "release_disabled"))]
+use crate::commands::record::{play_macros, record_macros};
+use crate::commands::record::{playback_command, record_command, PlaybackCommand, RecordCommand};
+#[cfg(feature = "render_commands")]
+use crate::commands::render::{
+    render_command, screenshot_command, RenderCommand, ScreenshotCommand,
+};
+use crate::commands::say::{say_command, SayCommand};
+#[cfg(not(feature = "release_disabled"))]
+use crate::commands::schedule::run_jobs;
+pub use crate::commands::schedule::ConsoleScheduler;
+use crate::commands::schedule::{
+    after_command, cancel_command, every_command, jobs_command, AfterCommand, CancelCommand,
+    EveryCommand, JobsCommand,
+};
+use crate::commands::scroll::{scroll_command, ScrollCommand};
+use crate::commands::search::{grep_command, tail_command, GrepCommand, TailCommand};
+#[cfg(feature = "replicon")]
+use crate::commands::server::{server_command, ServerCommand};
+#[cfg(feature = "serde")]
+use crate::commands::snapshot::{snapshot_command, SnapshotCommand};
+use crate::commands::state::{state_command, StateCommand};
+use crate::commands::time::{
+    gate_steps, pause_command, resume_command, step_command, timescale_command, PauseCommand,
+    PendingSteps, ResumeCommand, StepCommand, TimescaleCommand,
+};
+use crate::commands::var::{set_command, IfCommand, SetCommand};
+pub use crate::commands::version::ConsoleAppInfo;
+use crate::commands::version::{about_command, version_command, AboutCommand, VersionCommand};
+use crate::commands::watch::{
+    unwatch_command, update_watches, watch_command, watches_shown, UnwatchCommand, WatchCommand,
+};
 pub use crate::console::{
-    AddConsoleCommand, Command, ConsoleCommand, ConsoleCommandEntered, ConsoleConfiguration,
-    ConsoleOpen, NamedCommand, PrintConsoleLine,
+    AddConsoleCommand, Command, CommandGroup, CommandSource, ConsoleChannel, ConsoleCommand,
+    ConsoleCommandEntered, ConsoleConfiguration, ConsoleDock, ConsoleHasFocus,
+    ConsoleInputSubmitted, ConsoleOpen, ConsoleScroll, ConsoleSeverity, ConsoleTab, ConsoleTheme,
+    EditMode, EofAction, HistoryPolicy, InputFocusPolicy, InputPrompt, NamedCommand, OpenPolicy,
+    PasteMode, PrintConsoleLine, SeverityStyle, StatusMarkers, TabFilter, WrapMode,
 };
+pub use crate::frontend::{AddConsoleFrontend, ConsoleFrontend};
+pub use crate::gesture::{ScreenCorner, TouchToggle};
+pub use crate::handler::HandlerOutput;
+pub use crate::highlight::HighlightRule;
+pub use crate::locale::ConsoleLocale;
 pub use crate::log::*;
+pub use crate::middleware::{
+    CommandExecuted, CommandOutcome, CommandStatus, ConsoleMiddleware, Decision,
+};
+pub use crate::player::{PlayerConsoles, PlayerId};
+pub use crate::prompt::{ConsolePrompt, PromptFn};
+pub use crate::status_bar::ConsoleStatusBar;
+pub use crate::styled::{StyledLine, StyledSpan};
+pub use crate::text::ConsoleText;
+pub use crate::throttle::{Throttle, ThrottleGroup};
+
+use std::collections::HashSet;
+use std::sync::Arc;
 
 #[cfg(feature = "ui")]
+pub use crate::chat::ChatBox;
+#[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+use crate::chat::{chat_ui, ChatState};
+#[cfg(all(feature = "ui", not(feature = "release_disabled")))]
 use crate::console::console_ui;
+#[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+use crate::dock::spawn_dedicated_window;
+#[cfg(feature = "ui")]
+pub use crate::font::CONSOLE_FONT_FAMILY;
+#[cfg(feature = "ui")]
+use crate::font::{install_console_font, load_console_font, ConsoleFont, ConsoleFontLoader};
+#[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+use crate::gesture::toggle_by_gesture;
+#[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+use crate::surface::forward_surface_input;
+#[cfg(feature = "ui")]
+pub use crate::surface::ConsoleSurface;
+#[cfg(feature = "ui")]
+pub use crate::toast::ConsoleToasts;
+#[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+use crate::toast::{toast_ui, ToastQueue};
 
-use crate::console::{receive_console_line, ConsoleState};
+use crate::accessibility::announce_lines;
+#[cfg(feature = "auto_register")]
+use crate::auto_register::register_auto_commands;
+use crate::banner::print_banner;
+#[cfg(not(feature = "release_disabled"))]
+use crate::console::parse_and_dispatch;
+use crate::console::{
+    add_builtin_command, add_builtin_info, register_channels, sync_status_markers,
+    ActiveStatusMarkers, AppCommands, ConsoleState,
+};
+use crate::frontend::print_to_frontend;
+use crate::log_file::write_log_file;
+use crate::middleware::{report_outcomes, ConsoleMiddlewares};
+use crate::prompt::{prompt_may_change, update_prompt};
+use crate::status_bar::{status_bar_shown, update_status_bar};
+use crate::throttle::{throttle_lines, ThrottledLines};
 pub use clap;
+#[cfg(feature = "auto_register")]
+#[doc(hidden)]
+pub use inventory;
 
 // mod color;
+mod accessibility;
+#[cfg(feature = "auto_register")]
+mod auto_register;
+mod banner;
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench;
+#[cfg(feature = "ui")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod bidi;
+#[cfg(feature = "ui")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod chat;
 mod color;
 mod commands;
+// completions are only offered by the console window and the terminal
+#[cfg_attr(
+    any(
+        all(not(feature = "ui"), not(feature = "rustyline"), not(feature = "tui")),
+        feature = "release_disabled"
+    ),
+    allow(dead_code)
+)]
+mod completion;
+#[cfg(feature = "config_asset")]
+mod config_asset;
+// the input systems aren't scheduled with `release_disabled`, leaving them unused
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
 mod console;
+#[cfg(feature = "ui")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod dock;
+#[cfg(feature = "ui")]
+mod font;
+mod frontend;
+// gestures are only recognized alongside the console window
+#[cfg_attr(
+    any(not(feature = "ui"), feature = "release_disabled"),
+    allow(dead_code)
+)]
+mod gesture;
+mod handler;
+mod highlight;
+#[cfg(feature = "http")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod http;
+// only the console window and the terminal UI edit their input
+#[cfg_attr(
+    any(
+        all(not(feature = "ui"), not(feature = "tui")),
+        feature = "release_disabled"
+    ),
+    allow(dead_code)
+)]
+mod input_edit;
+#[cfg(feature = "ui")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod input_keys;
+mod locale;
 mod log;
+mod log_file;
 mod macros;
+mod markdown;
+mod middleware;
+// the palette is only shown by the console window
+#[cfg_attr(
+    any(not(feature = "ui"), feature = "release_disabled"),
+    allow(dead_code)
+)]
+mod palette;
+mod player;
+mod prompt;
+#[cfg(feature = "remote")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod remote;
+#[cfg(feature = "replicon")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod replicon;
 #[cfg(feature = "rustyline")]
+// the terminal UI takes over the terminal when both are enabled
+#[cfg_attr(any(feature = "tui", feature = "release_disabled"), allow(dead_code))]
 mod rustyline;
-#[cfg(feature = "rustyline")]
-pub use rustyline::ConsoleInterrupted;
+#[cfg(feature = "ui")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod scrollback;
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod snapshot;
+mod status_bar;
+#[cfg(feature = "capture_stdio")]
+mod stdio;
+mod styled;
+#[cfg(feature = "ui")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod surface;
+#[cfg(test)]
+mod testing;
+mod text;
+mod throttle;
+#[cfg(feature = "tls")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod tls;
+#[cfg(feature = "ui")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod toast;
+#[cfg(feature = "tui")]
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+mod tui;
+#[cfg(any(feature = "rustyline", feature = "tui"))]
+pub use crate::frontend::{ConsoleEof, ConsoleInterrupted};
+
+#[cfg(feature = "config_asset")]
+use crate::config_asset::{apply_config_asset, load_config_asset, ConsoleConfigAssetLoader};
+#[cfg(feature = "config_asset")]
+pub use crate::config_asset::{ConsoleConfigAsset, ConsoleConfigAssetError};
+#[cfg(all(feature = "http", not(feature = "release_disabled")))]
+use crate::http::setup_http_endpoint;
+#[cfg(feature = "http")]
+pub use crate::http::HttpEndpoint;
+use crate::player::setup_player_consoles;
+#[cfg(all(feature = "remote", not(feature = "release_disabled")))]
+use crate::remote::setup_remote_console;
+#[cfg(feature = "tls")]
+pub use crate::remote::RemoteTls;
+#[cfg(feature = "remote")]
+pub use crate::remote::{AuthFailed, RemoteConsole, RemotePermission};
+#[cfg(feature = "replicon")]
+use crate::replicon::setup_networked_console;
+#[cfg(feature = "replicon")]
+pub use crate::replicon::{NetworkedConsole, NetworkedConsoleCommand, NetworkedConsoleReply};
+#[cfg(feature = "serde")]
+use crate::snapshot::{restore_session, save_session, SessionFile};
+#[cfg(all(feature = "serde", feature = "ui", not(feature = "release_disabled")))]
+use crate::snapshot::{restore_window_placement, track_window_placement, DedicatedWindowPlacement};
+#[cfg(feature = "serde")]
+pub use crate::snapshot::{ConsoleSnapshot, SnapshotLine, WindowPlacement};
+#[cfg(feature = "capture_stdio")]
+use crate::stdio::setup_stdio_capture;
+
+/// Built-in console commands, which can be left out with [`ConsolePlugin::without_builtin`].
+///
+/// A command the app registers with the same name, e.g. its own `pause`, replaces the builtin.
+pub mod builtin {
+    #[cfg(feature = "audio_commands")]
+    pub use crate::commands::audio::AudioCommand;
+    pub use crate::commands::channel::ChannelCommand;
+    pub use crate::commands::clear::ClearCommand;
+    pub use crate::commands::console::ConsoleStatsCommand;
+    pub use crate::commands::diagnostics::{DiagCommand, FpsCommand};
+    pub use crate::commands::exit::ExitCommand;
+    #[cfg(feature = "gameplay_commands")]
+    pub use crate::commands::gameplay::{
+        DespawnCommand, ParentCommand, SpawnCommand, TeleportCommand,
+    };
+    pub use crate::commands::help::HelpCommand;
+    pub use crate::commands::man::ManCommand;
+    pub use crate::commands::perf::PerfCommand;
+    pub use crate::commands::pin::UnpinCommand;
+    pub use crate::commands::record::{PlaybackCommand, RecordCommand};
+    #[cfg(feature = "render_commands")]
+    pub use crate::commands::render::{RenderCommand, ScreenshotCommand};
+    pub use crate::commands::say::SayCommand;
+    pub use crate::commands::schedule::{AfterCommand, CancelCommand, EveryCommand, JobsCommand};
+    pub use crate::commands::scroll::ScrollCommand;
+    pub use crate::commands::search::{GrepCommand, TailCommand};
+    #[cfg(feature = "replicon")]
+    pub use crate::commands::server::ServerCommand;
+    #[cfg(feature = "serde")]
+    pub use crate::commands::snapshot::SnapshotCommand;
+    pub use crate::commands::state::StateCommand;
+    pub use crate::commands::time::{PauseCommand, ResumeCommand, StepCommand, TimescaleCommand};
+    pub use crate::commands::var::{IfCommand, SetCommand};
+    pub use crate::commands::version::{AboutCommand, VersionCommand};
+    pub use crate::commands::watch::{UnwatchCommand, WatchCommand};
+}
+
 /// Console plugin.
-pub struct ConsolePlugin;
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_console::{builtin::ExitCommand, ConsolePlugin};
+/// App::new().add_plugins(
+///     ConsolePlugin::new()
+///         .with_toggle_key(KeyCode::F1)
+///         .with_history_size(100)
+///         .without_builtin::<ExitCommand>(),
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct ConsolePlugin {
+    config: ConsoleConfiguration,
+    disabled_builtins: HashSet<&'static str>,
+    banner: ConsoleBanner,
+    app_info: Option<ConsoleAppInfo>,
+    #[cfg(feature = "config_asset")]
+    config_asset: Option<String>,
+    #[cfg(feature = "ui")]
+    font: Option<String>,
+    #[cfg(feature = "ui")]
+    dedicated_window: Option<Window>,
+    #[cfg(feature = "ui")]
+    chat: Option<ChatBox>,
+    #[cfg(feature = "ui")]
+    toasts: Option<ConsoleToasts>,
+    #[cfg(feature = "capture_stdio")]
+    capture_stdio: bool,
+    #[cfg(feature = "remote")]
+    remote: Option<RemoteConsole>,
+    #[cfg(feature = "http")]
+    http: Option<HttpEndpoint>,
+    #[cfg(feature = "serde")]
+    session_file: Option<std::path::PathBuf>,
+    player_consoles: bool,
+    #[cfg(feature = "replicon")]
+    networked_console: Option<NetworkedConsole>,
+}
+
+impl ConsolePlugin {
+    /// Creates a console plugin with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the whole initial configuration.
+    pub fn with_configuration(mut self, config: ConsoleConfiguration) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the key toggling the console.
+    pub fn with_toggle_key(self, key: KeyCode) -> Self {
+        self.with_toggle_keys([key])
+    }
+
+    /// Sets the keys toggling the console.
+    pub fn with_toggle_keys(mut self, keys: impl IntoIterator<Item = KeyCode>) -> Self {
+        self.config.keys = keys.into_iter().collect();
+        self
+    }
+
+    /// Sets the colors and styling of the console window.
+    pub fn with_theme(mut self, theme: ConsoleTheme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Sets the number of commands stored in history.
+    pub fn with_history_size(mut self, history_size: usize) -> Self {
+        self.config.history_size = history_size;
+        self
+    }
+
+    /// Lists the given number of history entries before and after the current one
+    /// while browsing history.
+    pub fn with_history_preview(mut self, entries: usize) -> Self {
+        self.config.history_preview = Some(entries);
+        self
+    }
+
+    /// Limits the bytes of text held in the scrollback, removing the oldest lines once exceeded.
+    pub fn with_scrollback_budget(mut self, bytes: usize) -> Self {
+        self.config.scrollback_budget = Some(bytes);
+        self
+    }
+
+    /// Sets the prompt template, see [`ConsolePrompt`].
+    pub fn with_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.config.symbol = symbol.into();
+        self
+    }
+
+    /// Sets the title of the console window.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.config.title_name = title.into();
+        self
+    }
+
+    /// Sets the key bindings of the console input and the terminal editor.
+    pub fn with_edit_mode(mut self, edit_mode: EditMode) -> Self {
+        self.config.edit_mode = edit_mode;
+        self
+    }
+
+    /// Shows a status bar below the input, see [`ConsoleStatusBar`].
+    pub fn with_status_bar(mut self) -> Self {
+        self.config.status_bar = true;
+        self
+    }
+
+    /// Adapts the console to touchscreens with the larger [`ConsoleTheme::touch`] theme, see
+    /// [`ConsoleConfiguration::touch`].
+    pub fn with_touch(mut self) -> Self {
+        self.config.touch = true;
+        self.config.theme = ConsoleTheme::touch();
+        self
+    }
+
+    /// Toggles the console with a touch gesture, see [`ConsoleConfiguration::touch_toggle`].
+    pub fn with_touch_toggle(mut self, toggle: TouchToggle) -> Self {
+        self.config.touch_toggle = Some(toggle);
+        self
+    }
+
+    /// Docks the console to an edge of its window instead of showing it as a floating window.
+    pub fn with_dock(mut self, dock: ConsoleDock) -> Self {
+        self.config.dock = dock;
+        self
+    }
+
+    /// Adds a rule highlighting matching text of scrollback lines, see [`HighlightRule`].
+    pub fn with_highlight(mut self, rule: HighlightRule) -> Self {
+        self.config.highlights.push(rule);
+        self
+    }
+
+    /// Sets the banner printed when the console is opened for the first time.
+    pub fn with_banner(mut self, banner: impl Into<String>) -> Self {
+        self.banner.banner = Some(banner.into());
+        self
+    }
+
+    /// Sets the message of the day, printed after the banner.
+    pub fn with_motd(
+        mut self,
+        motd: impl Fn(&World) -> Vec<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.banner.motd = Some(Arc::new(motd));
+        self
+    }
+
+    /// Sets the information printed by the `version` and `about` commands,
+    /// see [`console_app_info!`].
+    pub fn with_app_info(mut self, app_info: ConsoleAppInfo) -> Self {
+        self.app_info = Some(app_info);
+        self
+    }
+
+    /// Loads the configuration from a `.console.ron` or `.console.toml` asset, see
+    /// [`ConsoleConfigAsset`].
+    ///
+    /// The asset is applied on top of the initial configuration, and re-applied when it changes.
+    #[cfg(feature = "config_asset")]
+    pub fn with_config_asset(mut self, path: impl Into<String>) -> Self {
+        self.config_asset = Some(path.into());
+        self
+    }
+
+    /// Loads a TTF or OTF font asset and uses it for console text only,
+    /// registered as the [`CONSOLE_FONT_FAMILY`] egui font family.
+    ///
+    /// Glyphs missing from the font fall back to egui's monospace fonts.
+    #[cfg(feature = "ui")]
+    pub fn with_font(mut self, path: impl Into<String>) -> Self {
+        self.font = Some(path.into());
+        self
+    }
+
+    /// Shows the console in its own OS window spawned at startup, filling it and open from the start.
+    ///
+    /// Once the window is closed, the console moves back to the primary window. With
+    /// `with_session_file` the window opens where it was, on the same monitor, when the app
+    /// last exited.
+    #[cfg(feature = "ui")]
+    pub fn with_dedicated_window(mut self, window: Window) -> Self {
+        self.config.dock = ConsoleDock::Fill;
+        self.dedicated_window = Some(window);
+        self
+    }
+
+    /// Adds a chat box, a one line input opened with `Enter` which executes commands through
+    /// the console, see [`ChatBox`].
+    ///
+    /// The chat box can be combined with the console, or replace it by clearing the toggle keys,
+    /// see [`ConsolePlugin::chat_only`].
+    #[cfg(feature = "ui")]
+    pub fn with_chat(mut self, chat: ChatBox) -> Self {
+        self.chat = Some(chat);
+        self
+    }
+
+    /// Creates a plugin with the default [`ChatBox`] and no key opening the console window.
+    #[cfg(feature = "ui")]
+    pub fn chat_only() -> Self {
+        Self::new()
+            .with_toggle_keys([])
+            .with_chat(ChatBox::default())
+    }
+
+    /// Shows important printed lines as fading toasts while the console is closed,
+    /// see [`ConsoleToasts`].
+    #[cfg(feature = "ui")]
+    pub fn with_toasts(mut self, toasts: ConsoleToasts) -> Self {
+        self.toasts = Some(toasts);
+        self
+    }
+
+    /// Mirrors everything written to stdout and stderr into the console,
+    /// printed to the `stdout` and `stderr` channels.
+    ///
+    /// Output still reaches the original streams. Only supported on unix platforms,
+    /// Bevy logs written to stderr will show up in the console, so this shouldn't be combined
+    /// with [`make_layer`]. With the `rustyline` or `tui` feature only stderr is captured,
+    /// as their frontends print to stdout.
+    #[cfg(feature = "capture_stdio")]
+    pub fn with_stdio_capture(mut self) -> Self {
+        self.capture_stdio = true;
+        self
+    }
+
+    /// Accepts commands from remote clients over TCP, see [`RemoteConsole`].
+    #[cfg(feature = "remote")]
+    pub fn with_remote_console(mut self, remote: RemoteConsole) -> Self {
+        self.remote = Some(remote);
+        self
+    }
+
+    /// Executes commands sent to `POST /command` and responds with their output as JSON,
+    /// see [`HttpEndpoint`].
+    #[cfg(feature = "http")]
+    pub fn with_http_endpoint(mut self, endpoint: HttpEndpoint) -> Self {
+        self.http = Some(endpoint);
+        self
+    }
+
+    /// Executes commands entered by the players of a networked game and keeps the replies to
+    /// send back to them, see [`PlayerConsoles`].
+    pub fn with_player_consoles(mut self) -> Self {
+        self.player_consoles = true;
+        self
+    }
+
+    /// Executes console commands sent by bevy_replicon clients on the server and sends the
+    /// replies back, adding the `server` command to send them, see [`NetworkedConsole`].
+    ///
+    /// Clients and the server need this with the same settings. Implies
+    /// [`with_player_consoles`](Self::with_player_consoles).
+    #[cfg(feature = "replicon")]
+    pub fn with_networked_console(mut self, console: NetworkedConsole) -> Self {
+        self.networked_console = Some(console);
+        self.player_consoles = true;
+        self
+    }
+
+    /// Restores the console session from the given file at startup and saves it there when the
+    /// app exits, see [`ConsoleSnapshot`].
+    #[cfg(feature = "serde")]
+    pub fn with_session_file(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.session_file = Some(path.into());
+        self
+    }
+
+    /// Doesn't register the given [`builtin`] command.
+    pub fn without_builtin<T: NamedCommand>(mut self) -> Self {
+        self.disabled_builtins.insert(T::name());
+        self
+    }
+
+    /// Names of the configured frontends which `release_disabled` leaves out
+    #[cfg(feature = "release_disabled")]
+    fn disabled_frontends(&self) -> Vec<&'static str> {
+        #[allow(unused_mut)]
+        let mut frontends = Vec::new();
+        #[cfg(feature = "ui")]
+        for (name, configured) in [
+            ("dedicated window", self.dedicated_window.is_some()),
+            ("chat box", self.chat.is_some()),
+            ("toasts", self.toasts.is_some()),
+        ] {
+            if configured {
+                frontends.push(name);
+            }
+        }
+        #[cfg(feature = "remote")]
+        if self.remote.is_some() {
+            frontends.push("remote console");
+        }
+        #[cfg(feature = "http")]
+        if self.http.is_some() {
+            frontends.push("HTTP endpoint");
+        }
+        if self.player_consoles {
+            frontends.push("player consoles");
+        }
+        #[cfg(feature = "replicon")]
+        if self.networked_console.is_some() {
+            frontends.push("networked console");
+        }
+        frontends
+    }
+
+    fn add_builtin<T: Command, Params>(
+        &self,
+        app: &mut App,
+        system: impl IntoSystemConfigs<Params>,
+    ) {
+        if !self.disabled_builtins.contains(T::name()) {
+            add_builtin_command::<T, _>(app, system);
+        }
+    }
+}
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 /// The SystemSet for console/command related systems
@@ -61,20 +671,143 @@ fn have_commands(commands: EventReader<ConsoleCommandEntered>) -> bool {
 
 impl Plugin for ConsolePlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<ConsoleConfiguration>()
-            .init_resource::<ConsoleState>()
+        // A configuration inserted before the plugin takes precedence
+        if !app.world().contains_resource::<ConsoleConfiguration>() {
+            app.insert_resource(self.config.clone());
+        }
+
+        if let Some(app_info) = &self.app_info {
+            app.insert_resource(app_info.clone());
+        }
+        // Like the configuration, a banner inserted before the plugin takes precedence
+        if !app.world().contains_resource::<ConsoleBanner>() {
+            app.insert_resource(self.banner.clone());
+        }
+
+        self.add_builtin::<ClearCommand, _>(app, clear_command);
+        self.add_builtin::<ExitCommand, _>(app, exit_command);
+        self.add_builtin::<HelpCommand, _>(app, help_command);
+        self.add_builtin::<ManCommand, _>(app, man_command);
+        self.add_builtin::<FpsCommand, _>(app, fps_command);
+        if !self.disabled_builtins.contains(DiagCommand::name()) {
+            app.init_resource::<DiagWatches>()
+                .add_systems(Update, update_diag_watches.in_set(ConsoleSet::PostCommands));
+            add_builtin_command::<DiagCommand, _>(app, diag_command);
+        }
+        self.add_builtin::<WatchCommand, _>(app, watch_command);
+        self.add_builtin::<UnwatchCommand, _>(app, unwatch_command);
+        self.add_builtin::<UnpinCommand, _>(app, unpin_command);
+        self.add_builtin::<VersionCommand, _>(app, version_command);
+        self.add_builtin::<AboutCommand, _>(app, about_command);
+        self.add_builtin::<ChannelCommand, _>(app, channel_command);
+        self.add_builtin::<GrepCommand, _>(app, grep_command);
+        self.add_builtin::<TailCommand, _>(app, tail_command);
+        self.add_builtin::<ScrollCommand, _>(app, scroll_command);
+        self.add_builtin::<SayCommand, _>(app, say_command);
+        self.add_builtin::<SetCommand, _>(app, set_command);
+        if !self.disabled_builtins.contains(IfCommand::name()) {
+            // run by the dispatch layer, it has no handler
+            add_builtin_info::<IfCommand>(app);
+        }
+        self.add_builtin::<RecordCommand, _>(app, record_command);
+        self.add_builtin::<PlaybackCommand, _>(app, playback_command);
+        self.add_builtin::<AfterCommand, _>(app, after_command);
+        self.add_builtin::<EveryCommand, _>(app, every_command);
+        self.add_builtin::<JobsCommand, _>(app, jobs_command);
+        self.add_builtin::<CancelCommand, _>(app, cancel_command);
+        self.add_builtin::<TimescaleCommand, _>(app, timescale_command);
+        self.add_builtin::<PauseCommand, _>(app, pause_command);
+        self.add_builtin::<ResumeCommand, _>(app, resume_command);
+        self.add_builtin::<StepCommand, _>(app, step_command);
+        self.add_builtin::<StateCommand, _>(app, state_command);
+        self.add_builtin::<ConsoleStatsCommand, _>(app, console_stats_command);
+        #[cfg(feature = "serde")]
+        self.add_builtin::<SnapshotCommand, _>(app, snapshot_command);
+        #[cfg(feature = "replicon")]
+        if self.networked_console.is_some() {
+            self.add_builtin::<ServerCommand, _>(app, server_command);
+        }
+        if !self.disabled_builtins.contains(PerfCommand::name()) {
+            instrument_schedules(app);
+            add_builtin_command::<PerfCommand, _>(app, perf_command);
+        }
+        #[cfg(feature = "audio_commands")]
+        if !self.disabled_builtins.contains(AudioCommand::name()) {
+            app.init_resource::<AudioMute>();
+            add_builtin_command::<AudioCommand, _>(app, audio_command);
+        }
+        #[cfg(feature = "render_commands")]
+        {
+            self.add_builtin::<ScreenshotCommand, _>(app, screenshot_command);
+            self.add_builtin::<RenderCommand, _>(app, render_command);
+        }
+        #[cfg(feature = "gameplay_commands")]
+        {
+            self.add_builtin::<SpawnCommand, _>(app, spawn_command);
+            self.add_builtin::<DespawnCommand, _>(app, despawn_command);
+            self.add_builtin::<TeleportCommand, _>(app, teleport_command);
+            self.add_builtin::<ParentCommand, _>(app, parent_command);
+        }
+
+        app.add_console_frontend(ConsoleState::default())
             .init_resource::<ConsoleOpen>()
+            .init_resource::<ConsoleHasFocus>()
+            .init_resource::<ConsolePrompt>()
+            .init_resource::<ConsoleStatusBar>()
+            .add_event::<ConsoleInputSubmitted>()
             .add_event::<ConsoleCommandEntered>()
             .add_event::<PrintConsoleLine>()
-            .add_console_command::<ClearCommand, _>(clear_command)
-            .add_console_command::<ExitCommand, _>(exit_command)
-            .add_console_command::<HelpCommand, _>(help_command)
+            .add_event::<CommandOutcome>()
+            .add_event::<CommandExecuted>()
+            .add_event::<ConsoleScroll>()
+            .init_resource::<ConsoleMiddlewares>()
+            .init_resource::<AppCommands>()
+            .init_resource::<ActiveStatusMarkers>()
+            .init_resource::<ConsoleLocale>()
+            .init_resource::<ConsoleAccessibility>()
+            .init_resource::<PendingSteps>()
+            .init_resource::<ConsoleMacros>()
+            .init_resource::<ConsoleScheduler>()
+            .init_resource::<ThrottledLines>()
+            .add_systems(First, gate_steps.after(bevy::time::TimeSystem))
             .add_systems(
                 Update,
                 (
-                    #[cfg(feature = "ui")]
+                    update_prompt
+                        .run_if(prompt_may_change)
+                        .before(ConsoleSet::ConsoleUI),
+                    update_status_bar
+                        .run_if(status_bar_shown)
+                        .before(ConsoleSet::ConsoleUI),
+                    #[cfg(all(feature = "ui", not(feature = "release_disabled")))]
                     console_ui.in_set(ConsoleSet::ConsoleUI),
-                    receive_console_line.in_set(ConsoleSet::PostCommands),
+                    #[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+                    toggle_by_gesture.before(ConsoleSet::ConsoleUI),
+                    #[cfg(not(feature = "release_disabled"))]
+                    parse_and_dispatch
+                        .after(ConsoleSet::ConsoleUI)
+                        .before(ConsoleSet::Commands),
+                    sync_status_markers.before(ConsoleSet::Commands),
+                    #[cfg(not(feature = "release_disabled"))]
+                    (run_jobs, play_macros, record_macros)
+                        .chain()
+                        .after(parse_and_dispatch)
+                        .before(ConsoleSet::Commands),
+                    throttle_lines
+                        .after(ConsoleSet::Commands)
+                        .before(ConsoleSet::PostCommands),
+                    // exclusive, so it only runs when the console may have been opened
+                    print_banner
+                        .run_if(resource_changed::<ConsoleOpen>)
+                        .in_set(ConsoleSet::PostCommands)
+                        .before(print_to_frontend::<ConsoleState>),
+                    register_channels.in_set(ConsoleSet::PostCommands),
+                    update_watches
+                        .run_if(watches_shown)
+                        .in_set(ConsoleSet::PostCommands),
+                    write_log_file.in_set(ConsoleSet::PostCommands),
+                    report_outcomes.in_set(ConsoleSet::PostCommands),
+                    announce_lines.in_set(ConsoleSet::PostCommands),
                 ),
             )
             .configure_sets(
@@ -87,9 +820,106 @@ impl Plugin for ConsolePlugin {
                 ),
             );
 
-        #[cfg(feature = "rustyline")]
+        #[cfg(feature = "config_asset")]
+        if let Some(path) = &self.config_asset {
+            app.init_asset::<ConsoleConfigAsset>()
+                .init_asset_loader::<ConsoleConfigAssetLoader>()
+                .add_systems(Startup, load_config_asset(path.clone()))
+                .add_systems(Update, apply_config_asset.before(ConsoleSet::ConsoleUI));
+        }
+
+        #[cfg(feature = "serde")]
+        if let Some(path) = &self.session_file {
+            app.insert_resource(SessionFile(path.clone()))
+                .add_systems(Startup, restore_session)
+                .add_systems(Last, save_session);
+
+            #[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+            if self.dedicated_window.is_some() {
+                app.init_resource::<DedicatedWindowPlacement>()
+                    .add_systems(PostStartup, restore_window_placement)
+                    .add_systems(Update, track_window_placement);
+            }
+        }
+
+        #[cfg(feature = "ui")]
+        if let Some(path) = &self.font {
+            app.init_asset::<ConsoleFont>()
+                .init_asset_loader::<ConsoleFontLoader>()
+                .add_systems(Startup, load_console_font(path.clone()))
+                .add_systems(Update, install_console_font.before(ConsoleSet::ConsoleUI));
+        }
+
+        #[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+        if let Some(window) = &self.dedicated_window {
+            app.add_systems(Startup, spawn_dedicated_window(window.clone()));
+        }
+
+        #[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+        app.add_systems(
+            PreUpdate,
+            forward_surface_input
+                .after(EguiSet::ProcessInput)
+                .before(EguiSet::BeginFrame),
+        );
+
+        #[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+        if let Some(chat) = &self.chat {
+            app.insert_resource(chat.clone())
+                .init_resource::<ChatState>()
+                .add_systems(
+                    Update,
+                    chat_ui.in_set(ConsoleSet::ConsoleUI).after(console_ui),
+                );
+        }
+
+        #[cfg(all(feature = "ui", not(feature = "release_disabled")))]
+        if let Some(toasts) = &self.toasts {
+            app.insert_resource(toasts.clone())
+                .init_resource::<ToastQueue>()
+                .add_systems(Update, toast_ui.in_set(ConsoleSet::PostCommands));
+        }
+
+        #[cfg(feature = "capture_stdio")]
+        if self.capture_stdio {
+            setup_stdio_capture(app);
+        }
+
+        #[cfg(all(
+            feature = "rustyline",
+            not(feature = "tui"),
+            not(feature = "release_disabled")
+        ))]
         setup_rustyline(app);
 
+        #[cfg(all(feature = "tui", not(feature = "release_disabled")))]
+        setup_tui(app);
+
+        #[cfg(all(feature = "remote", not(feature = "release_disabled")))]
+        if let Some(remote) = &self.remote {
+            setup_remote_console(app, remote);
+        }
+
+        #[cfg(all(feature = "http", not(feature = "release_disabled")))]
+        if let Some(endpoint) = &self.http {
+            setup_http_endpoint(app, endpoint);
+        }
+
+        #[cfg(feature = "release_disabled")]
+        {
+            let disabled = self.disabled_frontends();
+            if !disabled.is_empty() {
+                info!(
+                    "Console disabled for release, leaving out its {}",
+                    disabled.join(", ")
+                );
+            }
+        }
+
+        if self.player_consoles {
+            setup_player_consoles(app);
+        }
+
         // Don't initialize an egui plugin if one already exists.
         // This can happen if another plugin is using egui and was installed before us.
         #[cfg(feature = "ui")]
@@ -97,4 +927,18 @@ impl Plugin for ConsolePlugin {
             app.add_plugins(EguiPlugin);
         }
     }
+
+    #[cfg(any(feature = "auto_register", feature = "replicon"))]
+    fn finish(&self, app: &mut App) {
+        // commands are discovered once every plugin was built, so those added by hand are
+        // known and not registered twice
+        #[cfg(feature = "auto_register")]
+        register_auto_commands(app);
+
+        // replicon events can only be registered once its plugins were added
+        #[cfg(feature = "replicon")]
+        if let Some(console) = &self.networked_console {
+            setup_networked_console(app, console);
+        }
+    }
 }
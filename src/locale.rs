@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+/// English strings printed by the console itself, by key
+const DEFAULT_TEXTS: &[(&str, &str)] = &[
+    ("invalid-command", "error: Invalid command"),
+    (
+        "command-disabled",
+        "Command '{command}' is currently disabled",
+    ),
+    ("more-information", "For more information, try '{help}'"),
+    ("prompt-cancelled", "cancelled"),
+    (
+        "paste-confirm",
+        "Execute {count} pasted lines? Press Enter to run or Escape to cancel",
+    ),
+    ("example", "e.g. {example}"),
+    ("help-available-commands", "Available commands:"),
+    ("help-examples", "Examples:"),
+    ("help-unknown-command", "Command '{command}' does not exist"),
+    ("empty-command", "error: Empty command"),
+    ("permission-denied", "Permission denied for '{command}'"),
+    ("lines-suppressed", "suppressed {count} similar messages"),
+    ("menu-copy-line", "Copy line"),
+    ("menu-copy-all", "Copy all"),
+    ("menu-clear", "Clear"),
+    ("menu-save", "Save to file"),
+    ("menu-filter", "Show only [{channel}]"),
+    ("scrollback-saved", "Saved the scrollback to {path}"),
+    ("server-not-connected", "Not connected to a server"),
+    (
+        "missing-resource",
+        "no {resource}, add {plugin} to your app",
+    ),
+    ("missing-plugin", "{plugin} is not added to the app"),
+    ("volume-out-of-range", "volume must be between 0 and 1"),
+    ("diagnostic-unknown", "Diagnostic '{name}' does not exist"),
+    ("type-unknown", "unknown type `{type}`"),
+    (
+        "type-not-component",
+        "`{type}` is not a reflected component",
+    ),
+    ("type-no-default", "`{type}` does not reflect Default"),
+    ("entity-unknown", "entity {entity} does not exist"),
+    ("entity-no-transform", "entity {entity} has no Transform"),
+    ("entity-own-parent", "an entity can't be its own parent"),
+    ("perf-no-timings", "no schedule timings recorded yet"),
+    ("record-already-recording", "already recording `{name}`"),
+    (
+        "record-not-recording",
+        "not recording, use `record start <name>` first",
+    ),
+    ("macro-unknown", "no macro named `{name}`"),
+    ("no-primary-window", "no primary window"),
+    ("msaa-invalid-samples", "msaa samples must be 1, 2, 4 or 8"),
+    ("job-unknown", "no job {id}, see `jobs`"),
+    ("job-cancelled", "job {id} cancelled: {reason}"),
+    (
+        "timescale-invalid",
+        "timescale must be a finite, non-negative number",
+    ),
+    (
+        "step-not-paused",
+        "step only works while paused, use `pause` first",
+    ),
+    ("var-unset", "`{name}` is not set"),
+    ("pin-unknown", "Nothing is pinned by '{key}'"),
+];
+
+/// Translations of the strings printed by the console itself, e.g. `error: Invalid command`.
+///
+/// Strings are looked up by key, falling back to English for keys without a translation.
+/// Placeholders are written as `{name}`. The keys and their English strings are:
+///
+/// | Key | English |
+/// | --- | --- |
+/// | `invalid-command` | `error: Invalid command` |
+/// | `command-disabled` | `Command '{command}' is currently disabled` |
+/// | `more-information` | `For more information, try '{help}'` |
+/// | `prompt-cancelled` | `cancelled` |
+/// | `paste-confirm` | `Execute {count} pasted lines? Press Enter to run or Escape to cancel` |
+/// | `example` | `e.g. {example}` |
+/// | `help-available-commands` | `Available commands:` |
+/// | `help-examples` | `Examples:` |
+/// | `help-unknown-command` | `Command '{command}' does not exist` |
+/// | `empty-command` | `error: Empty command` |
+/// | `permission-denied` | `Permission denied for '{command}'` |
+/// | `lines-suppressed` | `suppressed {count} similar messages` |
+/// | `menu-copy-line` | `Copy line` |
+/// | `menu-copy-all` | `Copy all` |
+/// | `menu-clear` | `Clear` |
+/// | `menu-save` | `Save to file` |
+/// | `menu-filter` | `Show only [{channel}]` |
+/// | `scrollback-saved` | `Saved the scrollback to {path}` |
+/// | `server-not-connected` | `Not connected to a server` |
+/// | `missing-resource` | `no {resource}, add {plugin} to your app` |
+/// | `missing-plugin` | `{plugin} is not added to the app` |
+/// | `volume-out-of-range` | `volume must be between 0 and 1` |
+/// | `diagnostic-unknown` | `Diagnostic '{name}' does not exist` |
+/// | `type-unknown` | `` unknown type `{type}` `` |
+/// | `type-not-component` | `` `{type}` is not a reflected component `` |
+/// | `type-no-default` | `` `{type}` does not reflect Default `` |
+/// | `entity-unknown` | `entity {entity} does not exist` |
+/// | `entity-no-transform` | `entity {entity} has no Transform` |
+/// | `entity-own-parent` | `an entity can't be its own parent` |
+/// | `perf-no-timings` | `no schedule timings recorded yet` |
+/// | `record-already-recording` | `` already recording `{name}` `` |
+/// | `record-not-recording` | `` not recording, use `record start <name>` first `` |
+/// | `macro-unknown` | `` no macro named `{name}` `` |
+/// | `no-primary-window` | `no primary window` |
+/// | `msaa-invalid-samples` | `msaa samples must be 1, 2, 4 or 8` |
+/// | `job-unknown` | `` no job {id}, see `jobs` `` |
+/// | `job-cancelled` | `job {id} cancelled: {reason}` |
+/// | `timescale-invalid` | `timescale must be a finite, non-negative number` |
+/// | `step-not-paused` | `` step only works while paused, use `pause` first `` |
+/// | `var-unset` | `` `{name}` is not set `` |
+/// | `pin-unknown` | `Nothing is pinned by '{key}'` |
+///
+/// The `[ok]` and `[failed]` markers are set with
+/// [`ConsoleConfiguration::status_markers`](crate::ConsoleConfiguration::status_markers).
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_console::ConsoleLocale;
+/// let locale = ConsoleLocale::default()
+///     .with("invalid-command", "erreur : commande invalide")
+///     .with("command-disabled", "La commande '{command}' est désactivée");
+/// App::new().insert_resource(locale);
+/// ```
+#[derive(Clone, Debug, Default, Resource)]
+pub struct ConsoleLocale {
+    translations: BTreeMap<String, String>,
+}
+
+impl ConsoleLocale {
+    /// Translates the string with the given key, replacing any previous translation.
+    pub fn with(mut self, key: impl Into<String>, text: impl Into<String>) -> Self {
+        self.insert(key, text);
+        self
+    }
+
+    /// Translates the string with the given key, replacing any previous translation.
+    pub fn insert(&mut self, key: impl Into<String>, text: impl Into<String>) -> &mut Self {
+        self.translations.insert(key.into(), text.into());
+        self
+    }
+
+    /// Returns the string with the given key, the key itself if it is unknown.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.translations
+            .get(key)
+            .map(String::as_str)
+            .or_else(|| {
+                DEFAULT_TEXTS
+                    .iter()
+                    .find(|(default_key, _)| *default_key == key)
+                    .map(|(_, text)| *text)
+            })
+            .unwrap_or(key)
+    }
+
+    /// Returns the string with the given key, with its `{name}` placeholders replaced by the
+    /// given values.
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        args.iter()
+            .fold(self.get(key).to_owned(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), value)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale() {
+        let locale = ConsoleLocale::default().with("command-disabled", "'{command}' désactivée");
+        assert_eq!(locale.get("invalid-command"), "error: Invalid command");
+        assert_eq!(
+            locale.format("command-disabled", &[("command", "god")]),
+            "'god' désactivée"
+        );
+        assert_eq!(
+            locale.format("paste-confirm", &[("count", "3")]),
+            "Execute 3 pasted lines? Press Enter to run or Escape to cancel"
+        );
+        assert_eq!(
+            locale.format(
+                "missing-resource",
+                &[("resource", "AssetServer"), ("plugin", "AssetPlugin")]
+            ),
+            "no AssetServer, add AssetPlugin to your app"
+        );
+        assert_eq!(locale.get("unknown"), "unknown");
+    }
+}
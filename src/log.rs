@@ -9,6 +9,7 @@ use bevy::{
     prelude::{EventWriter, IntoSystemConfigs, ResMut, Resource},
 };
 
+use crate::throttle::throttle_lines;
 use crate::{ConsoleSet, PrintConsoleLine};
 
 /// Buffers logs written by bevy at runtime
@@ -21,23 +22,19 @@ pub struct BevyLogBufferWriter(Arc<Mutex<std::io::Cursor<Vec<u8>>>>);
 impl Write for BevyLogBufferWriter {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         // let lock = self.0.upgrade().unwrap();
-        let mut lock = self.0.lock().map_err(|e| {
-            std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Failed to lock buffer: {}", e),
-            )
-        })?;
+        let mut lock = self
+            .0
+            .lock()
+            .map_err(|e| std::io::Error::other(format!("Failed to lock buffer: {}", e)))?;
         lock.write(buf)
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
         // let lock = self.0.upgrade().unwrap();
-        let mut lock = self.0.lock().map_err(|e| {
-            std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Failed to lock buffer: {}", e),
-            )
-        })?;
+        let mut lock = self
+            .0
+            .lock()
+            .map_err(|e| std::io::Error::other(format!("Failed to lock buffer: {}", e)))?;
         lock.flush()
     }
 }
@@ -51,13 +48,27 @@ pub fn send_log_buffer_to_console(
     // read and clean buffer
     let buffer = buffer.get_mut();
     for line in buffer.lines().map_while(Result::ok) {
-        console_lines.send(PrintConsoleLine { line });
+        console_lines.send(PrintConsoleLine::new(line));
     }
     buffer.clear();
 }
 
 /// Creates a tracing layer which writes logs into a buffer resource inside the bevy world
 /// This is used by the console plugin to capture logs written by bevy
+///
+/// The `tui` terminal UI takes over the terminal, so logs written to stderr by bevy's
+/// `LogPlugin` would draw over it. Disable the plugin and only show logs in the scrollback:
+///
+/// ```no_run
+/// # use bevy::log::{tracing_subscriber::{prelude::*, Registry}, LogPlugin};
+/// # use bevy::prelude::*;
+/// # use bevy::utils::tracing::subscriber;
+/// # use bevy_console::{make_layer, ConsolePlugin};
+/// let mut app = App::new();
+/// let layer = make_layer(&mut app);
+/// subscriber::set_global_default(Registry::default().with(layer)).unwrap();
+/// app.add_plugins((DefaultPlugins.build().disable::<LogPlugin>(), ConsolePlugin::new()));
+/// ```
 pub fn make_layer(
     app: &mut App,
 ) -> Option<Box<dyn tracing_subscriber::Layer<Registry> + Send + Sync>> {
@@ -65,7 +76,9 @@ pub fn make_layer(
     app.insert_resource(BevyLogBuffer(buffer.clone()));
     app.add_systems(
         Update,
-        send_log_buffer_to_console.in_set(ConsoleSet::PostCommands),
+        send_log_buffer_to_console
+            .after(ConsoleSet::Commands)
+            .before(throttle_lines),
     );
 
     Some(Box::new(
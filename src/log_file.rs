@@ -0,0 +1,166 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+
+use crate::throttle::ThrottledLines;
+use crate::{ConsoleCommandEntered, ConsoleConfiguration, ConsoleState, PrintConsoleLine};
+
+/// The currently open log file
+#[derive(Default)]
+pub(crate) struct LogFileState {
+    path: Option<PathBuf>,
+    file: Option<File>,
+}
+
+/// Appends entered commands and printed lines to [`ConsoleConfiguration::log_file`],
+/// including the echoes and messages the console pushes straight into its scrollback
+pub(crate) fn write_log_file(
+    config: Res<ConsoleConfiguration>,
+    throttled: Res<ThrottledLines>,
+    mut console: ResMut<ConsoleState>,
+    mut commands: EventReader<ConsoleCommandEntered>,
+    mut lines: EventReader<PrintConsoleLine>,
+    mut state: Local<LogFileState>,
+) {
+    let unlogged = std::mem::take(&mut console.unlogged);
+    let Some(path) = &config.log_file else {
+        state.path = None;
+        state.file = None;
+        commands.clear();
+        lines.clear();
+        return;
+    };
+
+    // (re)open the file when the path changes, a failed open is not retried until then
+    if state.path.as_ref() != Some(path) {
+        state.path = Some(path.clone());
+        state.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| warn!("Failed to open console log file {path:?}: {err}"))
+            .ok();
+    }
+
+    let Some(file) = &mut state.file else {
+        commands.clear();
+        lines.clear();
+        return;
+    };
+
+    let timestamp = humantime::format_rfc3339_seconds(SystemTime::now());
+    let mut contents = String::new();
+    let format_line = |line: &PrintConsoleLine| {
+        let text = config.ansi.log_file.apply(&line.line);
+        let channel = line
+            .channel
+            .as_ref()
+            .map(|channel| format!("[{channel}] "))
+            .unwrap_or_default();
+        format!(
+            "{timestamp} {:<5} {channel}{text}\n",
+            line.severity.as_str()
+        )
+    };
+    for line in &unlogged {
+        contents.push_str(&format_line(line));
+    }
+    for command in commands.read() {
+        let line = shlex::try_join(
+            std::iter::once(command.command_name.as_str())
+                .chain(command.args.iter().map(String::as_str)),
+        )
+        .unwrap_or_else(|_| format!("{} {}", command.command_name, command.args.join(" ")));
+        contents.push_str(&format!("{timestamp} CMD   {line}\n"));
+    }
+    for line in throttled.read(&mut lines) {
+        contents.push_str(&format_line(line));
+    }
+
+    if !contents.is_empty() {
+        if let Err(err) = file.write_all(contents.as_bytes()) {
+            warn!("Failed to write console log file {path:?}: {err}");
+            state.file = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{CommandSource, ConsoleSeverity};
+
+    /// Lines of a log file without their timestamps
+    fn read_log(path: &PathBuf) -> Vec<String> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                line.split_once(' ')
+                    .map_or(line, |(_, line)| line)
+                    .to_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_write_log_file() {
+        let dir = std::env::temp_dir().join(format!("bevy_console_log_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (first, second) = (dir.join("first.log"), dir.join("second.log"));
+        // existing logs are appended to
+        fs::write(&first, "earlier\n").unwrap();
+
+        let mut app = App::new();
+        let config = ConsoleConfiguration {
+            log_file: Some(first.clone()),
+            ..default()
+        };
+        app.add_event::<ConsoleCommandEntered>()
+            .add_event::<PrintConsoleLine>()
+            .insert_resource(config)
+            .init_resource::<ThrottledLines>()
+            .init_resource::<ConsoleState>()
+            .add_systems(Update, write_log_file);
+
+        app.world_mut()
+            .resource_mut::<ConsoleState>()
+            .push_local(PrintConsoleLine::new("> say 'hello there'"));
+        app.world_mut().send_event(ConsoleCommandEntered {
+            command_name: "say".to_owned(),
+            args: vec!["hello there".to_owned()],
+            source: CommandSource::LocalUi,
+        });
+        app.world_mut().send_event_batch([
+            PrintConsoleLine::new("\x1b[31mred\x1b[0m"),
+            PrintConsoleLine::channel("net", "lagging").with_severity(ConsoleSeverity::Warn),
+        ]);
+        app.update();
+        assert_eq!(
+            read_log(&first),
+            [
+                "earlier",
+                "INFO  > say 'hello there'",
+                "CMD   say 'hello there'",
+                "INFO  red",
+                "WARN  [net] lagging"
+            ]
+        );
+
+        // changing the path switches to the new file
+        app.world_mut()
+            .resource_mut::<ConsoleConfiguration>()
+            .log_file = Some(second.clone());
+        app.world_mut().send_event(PrintConsoleLine::new("moved"));
+        app.update();
+        assert_eq!(read_log(&first).len(), 5);
+        assert_eq!(read_log(&second), ["INFO  moved"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
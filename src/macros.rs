@@ -48,3 +48,26 @@ macro_rules! reply_failed {
         }
     };
 }
+
+/// Creates a [`ConsoleAppInfo`](crate::ConsoleAppInfo) from the cargo environment of the calling crate.
+///
+/// The git hash is read from the `GIT_HASH` environment variable at compile time,
+/// which can be set from a build script.
+///
+/// # Example
+///
+/// ```ignore
+/// app.insert_resource(console_app_info!());
+/// ```
+#[macro_export]
+macro_rules! console_app_info {
+    () => {
+        $crate::ConsoleAppInfo::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+            .with_git_hash(option_env!("GIT_HASH"))
+            .with_build_profile(if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            })
+    };
+}
@@ -0,0 +1,129 @@
+use crate::{Colour, StyledLine, StyledSpan};
+
+/// Color of code spans and blocks
+const CODE_COLOR: Colour = Colour::from_rgb(230, 170, 90);
+
+/// Renders lightweight markdown as styled lines: `# headings`, `**bold**`, `*italic*`,
+/// `` `code` `` spans, fenced code blocks and `-`, `*` or `+` list items.
+///
+/// Anything else is kept as it is, e.g. numbered lists and links.
+pub(crate) fn markdown_lines(text: &str) -> Vec<StyledLine> {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(StyledLine::new().span(StyledSpan::new(line).color(CODE_COLOR)));
+            continue;
+        }
+
+        let indent = &line[..line.len() - trimmed.len()];
+        let heading = trimmed.trim_start_matches('#');
+        if heading.len() < trimmed.len() && (heading.is_empty() || heading.starts_with(' ')) {
+            let base = StyledSpan::default().bold().underline();
+            lines.push(inline_spans(heading.trim_start(), &base));
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            let mut line = StyledLine::new().span(format!("{indent}• "));
+            line.spans
+                .extend(inline_spans(item, &StyledSpan::default()).spans);
+            lines.push(line);
+        } else {
+            let mut styled = StyledLine::new().span(indent);
+            styled
+                .spans
+                .extend(inline_spans(trimmed, &StyledSpan::default()).spans);
+            styled.spans.retain(|span| !span.text.is_empty());
+            lines.push(styled);
+        }
+    }
+    lines
+}
+
+/// Splits the text into spans at its bold, italic and code markers, styled on top of `base`
+fn inline_spans(text: &str, base: &StyledSpan) -> StyledLine {
+    let mut line = StyledLine::new();
+    let mut current = String::new();
+    let (mut bold, mut italic) = (false, false);
+    let flush = |line: &mut StyledLine, current: &mut String, bold: bool, italic: bool| {
+        if !current.is_empty() {
+            line.spans.push(StyledSpan {
+                text: std::mem::take(current),
+                bold: base.bold || bold,
+                italic: base.italic || italic,
+                ..base.clone()
+            });
+        }
+    };
+
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                flush(&mut line, &mut current, bold, italic);
+                line.spans
+                    .push(StyledSpan::new(&rest[1..=end]).color(CODE_COLOR));
+                rest = &rest[end + 2..];
+                continue;
+            }
+        } else if let Some(after) = rest.strip_prefix("**") {
+            if bold || after.contains("**") {
+                flush(&mut line, &mut current, bold, italic);
+                bold = !bold;
+                rest = after;
+                continue;
+            }
+        } else if let Some(after) = rest.strip_prefix('*') {
+            // an opening star is followed by the emphasized text, e.g. not `a * b`
+            let opens = !after.starts_with(char::is_whitespace) && after.contains('*');
+            if italic || opens {
+                flush(&mut line, &mut current, bold, italic);
+                italic = !italic;
+                rest = after;
+                continue;
+            }
+        }
+        current.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    flush(&mut line, &mut current, bold, italic);
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_lines() {
+        let lines = markdown_lines(
+            "# Spawning\n\nSpawns **enemies** at the `cursor`, *fast*.\n  - one * two\n```\nspawn 3\n```",
+        );
+        let texts = lines.iter().map(StyledLine::text).collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            [
+                "Spawning",
+                "",
+                "Spawns enemies at the cursor, fast.",
+                "  • one * two",
+                "spawn 3"
+            ]
+        );
+
+        assert!(lines[0].spans[0].bold && lines[0].spans[0].underline);
+        let spans = &lines[2].spans;
+        assert_eq!(spans[1].text, "enemies");
+        assert!(spans[1].bold && !spans[0].bold);
+        assert_eq!(spans[3].text, "cursor");
+        assert_eq!(spans[3].color, Some(CODE_COLOR));
+        assert!(spans[5].italic);
+        assert_eq!(lines[4].spans[0].color, Some(CODE_COLOR));
+    }
+}
@@ -0,0 +1,291 @@
+#[cfg(feature = "remote")]
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bevy::ecs::event::{EventId, ManualEventReader};
+use bevy::prelude::*;
+use bevy::utils::Instant;
+
+#[cfg(feature = "remote")]
+use crate::RemotePermission;
+use crate::{CommandSource, ConsoleCommandEntered};
+
+/// Decision of a [`ConsoleMiddleware`] on whether a command may be executed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// Pass the (possibly rewritten) command on to the next middleware and its handler
+    Continue,
+    /// Don't execute the command, printing the given reason
+    Reject(String),
+}
+
+/// How the handler of a command reported its execution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandStatus {
+    /// The handler called [`ConsoleCommand::ok`](crate::ConsoleCommand::ok)
+    Ok,
+    /// The handler called [`ConsoleCommand::failed`](crate::ConsoleCommand::failed)
+    Failed,
+    /// The arguments could not be parsed
+    InvalidArguments,
+    /// The handler ran without reporting success or failure
+    Unreported,
+}
+
+/// Outcome of an executed console command.
+#[derive(Clone, Debug, Event)]
+pub struct CommandOutcome {
+    /// The executed command
+    pub command: ConsoleCommandEntered,
+    /// How the command finished
+    pub status: CommandStatus,
+}
+
+/// Sent once the handler of a command reported its outcome, e.g. for metrics or to chain commands.
+#[derive(Clone, Debug, Event)]
+pub struct CommandExecuted {
+    /// Name of the executed command
+    pub name: String,
+    /// Arguments the command was executed with
+    pub args: Vec<String>,
+    /// How the command finished
+    pub outcome: CommandStatus,
+    /// Time from dispatching the command until its outcome was reported, including deferred
+    /// replies and prompts. Zero for commands sent without going through the console.
+    pub duration: Duration,
+}
+
+/// Hooks run for every command entered into the console, regardless of where it was entered.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_console::{AddConsoleCommand, CommandOutcome, ConsoleCommandEntered, ConsoleMiddleware, Decision};
+/// struct NoCheats;
+///
+/// impl ConsoleMiddleware for NoCheats {
+///     fn before_execute(&mut self, command: &mut ConsoleCommandEntered) -> Decision {
+///         if command.command_name == "god" {
+///             Decision::Reject("Cheats are disabled".to_owned())
+///         } else {
+///             Decision::Continue
+///         }
+///     }
+///
+///     fn after_execute(&mut self, outcome: &CommandOutcome) {
+///         info!("{} finished: {:?}", outcome.command.command_name, outcome.status);
+///     }
+/// }
+///
+/// App::new().add_console_middleware(NoCheats);
+/// ```
+pub trait ConsoleMiddleware: Send + Sync + 'static {
+    /// Called before a command is sent to its handler, the command may be rewritten.
+    fn before_execute(&mut self, _command: &mut ConsoleCommandEntered) -> Decision {
+        Decision::Continue
+    }
+
+    /// Called after the handler of a command ran.
+    fn after_execute(&mut self, _outcome: &CommandOutcome) {}
+}
+
+/// Registered middleware, in order of registration
+#[derive(Default, Resource)]
+pub(crate) struct ConsoleMiddlewares(
+    pub(crate) Vec<Box<dyn ConsoleMiddleware>>,
+    /// Dispatched commands waiting for their outcome, with the time they were dispatched
+    pub(crate) Vec<(ConsoleCommandEntered, Instant)>,
+    /// Permissions of the connected remote clients and of the players of a networked console
+    #[cfg(feature = "remote")]
+    pub(crate) HashMap<CommandSource, RemotePermission>,
+);
+
+impl ConsoleMiddlewares {
+    /// Returns whether `source` may run the command.
+    ///
+    /// Checked for every dispatched line, so commands running other commands later, like
+    /// `after` and `playback`, can't run anything their source isn't allowed to.
+    #[cfg(feature = "remote")]
+    pub(crate) fn allows(&self, source: &CommandSource, command_name: &str) -> bool {
+        match self.2.get(source) {
+            Some(permission) => permission.allows(command_name),
+            // remote clients only run commands while they're connected
+            None => !matches!(source, CommandSource::Remote(_)),
+        }
+    }
+
+    /// Returns whether `source` may run the command, any source may without remote sources.
+    #[cfg(not(feature = "remote"))]
+    pub(crate) fn allows(&self, _source: &CommandSource, _command_name: &str) -> bool {
+        true
+    }
+
+    /// Restricts the commands `source` may run to those the permission allows
+    #[cfg(feature = "remote")]
+    #[cfg_attr(feature = "release_disabled", allow(dead_code))]
+    pub(crate) fn grant(&mut self, source: CommandSource, permission: RemotePermission) {
+        self.2.insert(source, permission);
+    }
+
+    /// Removes the permission of a remote client which disconnected
+    #[cfg(feature = "remote")]
+    #[cfg_attr(feature = "release_disabled", allow(dead_code))]
+    pub(crate) fn revoke(&mut self, source: &CommandSource) {
+        self.2.remove(source);
+    }
+
+    /// Runs all `before_execute` hooks and sends the command if none rejected it
+    pub(crate) fn dispatch(
+        &mut self,
+        mut command: ConsoleCommandEntered,
+        command_entered: &mut EventWriter<ConsoleCommandEntered>,
+    ) -> Result<EventId<ConsoleCommandEntered>, String> {
+        for middleware in &mut self.0 {
+            if let Decision::Reject(reason) = middleware.before_execute(&mut command) {
+                debug!("Command `{}` rejected: {reason}", command.command_name);
+                return Err(reason);
+            }
+        }
+        self.1.push((command.clone(), Instant::now()));
+        Ok(command_entered.send(command))
+    }
+}
+
+/// Reports commands which ran without an outcome, runs all `after_execute` hooks and sends
+/// [`CommandExecuted`]
+pub(crate) fn report_outcomes(
+    mut middlewares: ResMut<ConsoleMiddlewares>,
+    mut commands: EventReader<ConsoleCommandEntered>,
+    mut outcomes: ResMut<Events<CommandOutcome>>,
+    mut outcome_reader: Local<ManualEventReader<CommandOutcome>>,
+    mut executed: EventWriter<CommandExecuted>,
+) {
+    let mut unreported = commands.read().cloned().collect::<Vec<_>>();
+    let mut reported = Vec::new();
+    for outcome in outcome_reader.read(&outcomes) {
+        if let Some(index) = unreported.iter().position(|c| *c == outcome.command) {
+            unreported.remove(index);
+        }
+        reported.push(outcome.clone());
+    }
+
+    for command in unreported {
+        let outcome = CommandOutcome {
+            command,
+            status: CommandStatus::Unreported,
+        };
+        reported.push(outcome.clone());
+        outcomes.send(outcome);
+    }
+    // skip the outcomes sent above next frame
+    outcome_reader.clear(&outcomes);
+
+    for outcome in &reported {
+        for middleware in &mut middlewares.0 {
+            middleware.after_execute(outcome);
+        }
+        let started = middlewares
+            .1
+            .iter()
+            .position(|(command, _)| *command == outcome.command)
+            .map(|index| middlewares.1.remove(index).1);
+        executed.send(CommandExecuted {
+            name: outcome.command.command_name.clone(),
+            args: outcome.command.args.clone(),
+            outcome: outcome.status,
+            duration: started.map_or(Duration::ZERO, |started| started.elapsed()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use bevy::ecs::system::SystemState;
+
+    use super::*;
+    use crate::AddConsoleCommand;
+
+    #[derive(Default, Clone)]
+    struct Recorder(Arc<Mutex<Vec<(String, CommandStatus)>>>);
+
+    impl ConsoleMiddleware for Recorder {
+        fn before_execute(&mut self, command: &mut ConsoleCommandEntered) -> Decision {
+            if command.command_name == "forbidden" {
+                Decision::Reject("nope".to_owned())
+            } else {
+                Decision::Continue
+            }
+        }
+
+        fn after_execute(&mut self, outcome: &CommandOutcome) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((outcome.command.command_name.clone(), outcome.status));
+        }
+    }
+
+    fn command(name: &str) -> ConsoleCommandEntered {
+        ConsoleCommandEntered {
+            command_name: name.to_owned(),
+            args: vec![],
+            source: CommandSource::LocalUi,
+        }
+    }
+
+    #[test]
+    fn test_middleware_rejects_and_reports() {
+        let recorder = Recorder::default();
+        let mut app = App::new();
+        app.add_event::<ConsoleCommandEntered>()
+            .add_event::<CommandOutcome>()
+            .add_event::<CommandExecuted>()
+            .add_console_middleware(recorder.clone())
+            .add_systems(Update, report_outcomes);
+
+        let world = app.world_mut();
+        let mut system_state = SystemState::<(
+            ResMut<ConsoleMiddlewares>,
+            EventWriter<ConsoleCommandEntered>,
+        )>::new(world);
+        let (mut middlewares, mut writer) = system_state.get_mut(world);
+        assert_eq!(
+            middlewares.dispatch(command("forbidden"), &mut writer),
+            Err("nope".to_owned())
+        );
+        assert!(middlewares
+            .dispatch(command("allowed"), &mut writer)
+            .is_ok());
+        world.send_event(CommandOutcome {
+            command: command("reported"),
+            status: CommandStatus::Ok,
+        });
+
+        app.update();
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![
+                ("reported".to_owned(), CommandStatus::Ok),
+                ("allowed".to_owned(), CommandStatus::Unreported),
+            ]
+        );
+
+        let executed = app.world().resource::<Events<CommandExecuted>>();
+        let executed = executed
+            .iter_current_update_events()
+            .map(|executed| (executed.name.as_str(), executed.outcome))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            executed,
+            [
+                ("reported", CommandStatus::Ok),
+                ("allowed", CommandStatus::Unreported)
+            ]
+        );
+        assert!(app.world().resource::<ConsoleMiddlewares>().1.is_empty());
+    }
+}
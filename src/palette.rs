@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+
+#[cfg(feature = "ui")]
+use bevy_egui::egui::{self, Color32, FontId, TextEdit};
+
+#[cfg(feature = "ui")]
+use crate::ConsoleConfiguration;
+
+/// Number of matches listed by the command palette
+#[cfg(feature = "ui")]
+const PALETTE_MATCHES: usize = 12;
+
+/// Scores how well `pattern` matches `text` as a case insensitive subsequence,
+/// favouring consecutive characters and word starts. Returns `None` if it doesn't match.
+pub(crate) fn fuzzy_score(pattern: &str, text: &str) -> Option<i32> {
+    fuzzy_match(pattern, text).map(|(score, _)| score)
+}
+
+/// Like [`fuzzy_score`], also returning the byte offsets of the matched characters of `text`
+pub(crate) fn fuzzy_match(pattern: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let mut score = 0;
+    let mut matched = Vec::new();
+    let mut text_chars = text.char_indices();
+    let mut previous_match: Option<usize> = None;
+    for pattern_char in pattern.chars().filter(|c| !c.is_whitespace()) {
+        let pattern_char = pattern_char.to_ascii_lowercase();
+        loop {
+            let (index, text_char) = text_chars.next()?;
+            if text_char.to_ascii_lowercase() != pattern_char {
+                continue;
+            }
+            score += 1;
+            let word_start = text[..index]
+                .chars()
+                .next_back()
+                .map_or(true, |before| !before.is_alphanumeric());
+            if word_start {
+                score += 8;
+            }
+            match previous_match {
+                Some(previous)
+                    if previous + text[previous..].chars().next()?.len_utf8() == index =>
+                {
+                    score += 5;
+                }
+                Some(_) => score -= 1,
+                None => {}
+            }
+            previous_match = Some(index);
+            matched.push(index);
+            break;
+        }
+    }
+    Some((score, matched))
+}
+
+/// Command names matching the query by name or description, best matches first
+pub(crate) fn palette_matches(
+    commands: &BTreeMap<&'static str, clap::Command>,
+    query: &str,
+) -> Vec<&'static str> {
+    let mut matches = commands
+        .iter()
+        .filter_map(|(name, command)| {
+            let about = command
+                .get_about()
+                .map(|about| about.to_string())
+                .unwrap_or_default();
+            // a match in the name counts more than one in the description
+            let score = [
+                fuzzy_score(query, name).map(|score| score * 2),
+                fuzzy_score(query, &about),
+            ]
+            .into_iter()
+            .flatten()
+            .max()?;
+            Some((score, *name))
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|(a_score, a_name), (b_score, b_name)| {
+        b_score.cmp(a_score).then(a_name.cmp(b_name))
+    });
+    matches.into_iter().map(|(_, name)| name).collect()
+}
+
+/// State of the command palette opened with `Ctrl+P`
+#[derive(Default)]
+pub(crate) struct CommandPalette {
+    query: String,
+    selected: usize,
+}
+
+/// What happened to the command palette this frame
+#[cfg(feature = "ui")]
+pub(crate) enum PaletteResponse {
+    Open,
+    Closed,
+    Selected(&'static str),
+}
+
+/// Shows the command palette at the top of the screen
+#[cfg(feature = "ui")]
+pub(crate) fn palette_ui(
+    ctx: &egui::Context,
+    palette: &mut CommandPalette,
+    config: &ConsoleConfiguration,
+    font: &FontId,
+) -> PaletteResponse {
+    let matches = palette_matches(&config.commands, &palette.query);
+    let (up, down, enter, escape) = ctx.input_mut(|i| {
+        (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            i.key_pressed(egui::Key::Enter),
+            i.key_pressed(egui::Key::Escape),
+        )
+    });
+    if up {
+        palette.selected = palette.selected.saturating_sub(1);
+    } else if down {
+        palette.selected += 1;
+    }
+    palette.selected = palette
+        .selected
+        .min(matches.len().min(PALETTE_MATCHES).saturating_sub(1));
+
+    if escape {
+        return PaletteResponse::Closed;
+    }
+    if enter {
+        return match matches.get(palette.selected) {
+            Some(name) => PaletteResponse::Selected(name),
+            None => PaletteResponse::Closed,
+        };
+    }
+
+    let mut response = PaletteResponse::Open;
+    egui::Area::new(egui::Id::new("console_palette"))
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 40.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style())
+                .fill(config.theme.background_color.into())
+                .show(ui, |ui| {
+                    ui.set_width(ctx.screen_rect().width() / 2.0);
+                    let query = ui.add(
+                        TextEdit::singleline(&mut palette.query)
+                            .hint_text("Search commands")
+                            .font(font.clone())
+                            .desired_width(f32::INFINITY),
+                    );
+                    query.request_focus();
+                    if query.changed() {
+                        palette.selected = 0;
+                    }
+
+                    for (index, name) in matches.iter().take(PALETTE_MATCHES).enumerate() {
+                        let about = config.commands[name]
+                            .get_about()
+                            .map(|about| about.to_string())
+                            .unwrap_or_default();
+                        let label = ui.selectable_label(
+                            index == palette.selected,
+                            egui::RichText::new(format!("{name} - {about}")).font(font.clone()),
+                        );
+                        if label.clicked() {
+                            response = PaletteResponse::Selected(name);
+                        }
+                    }
+                    if matches.len() > PALETTE_MATCHES {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{} more",
+                                matches.len() - PALETTE_MATCHES
+                            ))
+                            .color(Color32::GRAY),
+                        );
+                    }
+
+                    // preview the usage of the selected command
+                    if let Some(name) = matches.get(palette.selected) {
+                        ui.separator();
+                        let usage = config.commands[name].clone().render_usage().to_string();
+                        ui.label(
+                            egui::RichText::new(usage)
+                                .font(font.clone())
+                                .color(Color32::GRAY),
+                        );
+                    }
+                });
+        });
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("xyz", "timescale"), None);
+        assert_eq!(fuzzy_score("", "timescale"), Some(0));
+        assert!(fuzzy_score("ts", "timescale").is_some());
+        assert!(fuzzy_score("TIME", "timescale").is_some());
+        // consecutive and word start matches rank higher
+        assert!(fuzzy_score("time", "timescale") > fuzzy_score("tmsc", "timescale"));
+        assert!(fuzzy_score("rw", "render.wireframe") > fuzzy_score("re", "render.wireframe"));
+    }
+
+    #[test]
+    fn test_palette_matches() {
+        let commands = BTreeMap::from([
+            (
+                "pause",
+                clap::Command::new("pause").about("Pauses virtual time"),
+            ),
+            (
+                "timescale",
+                clap::Command::new("timescale").about("Sets the speed of time"),
+            ),
+            (
+                "fps",
+                clap::Command::new("fps").about("Prints the frame rate"),
+            ),
+        ]);
+        assert_eq!(palette_matches(&commands, "time"), ["timescale", "pause"]);
+        assert_eq!(palette_matches(&commands, "frame"), ["fps"]);
+        assert_eq!(palette_matches(&commands, "").len(), 3);
+    }
+}
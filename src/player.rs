@@ -0,0 +1,228 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::console::ConsoleState;
+use crate::frontend::MAX_LINES_PER_FRAME;
+use crate::throttle::ThrottledLines;
+use crate::{
+    CommandSource, ConsoleInputSubmitted, ConsoleMacros, ConsoleScheduler, ConsoleSet,
+    PrintConsoleLine,
+};
+
+/// Identifies a player of a networked game, e.g. by the `ClientId` of bevy_replicon or renet
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PlayerId(pub u64);
+
+/// Consoles of the players connected to a game server, see
+/// [`ConsolePlugin::with_player_consoles`](crate::ConsolePlugin::with_player_consoles).
+///
+/// The networking layer [`submit`](Self::submit)s the lines players typed into their chat or
+/// console, which are executed as commands from [`CommandSource::Player`], and sends the lines
+/// taken with [`take_lines`](Self::take_lines) back to them. Only replies to a player's commands
+/// and lines targeted at the player are sent, not the lines printed to everyone like logs.
+///
+/// Players can enter every command, reject the ones they aren't allowed to with a
+/// [`ConsoleMiddleware`](crate::ConsoleMiddleware) or check [`CommandSource::player`] in the
+/// handlers.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_console::{
+/// #     AddConsoleCommand, ConsoleCommandEntered, ConsoleMiddleware, Decision, PlayerConsoles,
+/// #     PlayerId,
+/// # };
+/// /// Only lets admins run commands
+/// struct AdminsOnly(Vec<PlayerId>);
+///
+/// impl ConsoleMiddleware for AdminsOnly {
+///     fn before_execute(&mut self, command: &mut ConsoleCommandEntered) -> Decision {
+///         match command.source.player() {
+///             Some(player) if !self.0.contains(&player) => {
+///                 Decision::Reject("Only admins can run commands".to_owned())
+///             }
+///             _ => Decision::Continue,
+///         }
+///     }
+/// }
+///
+/// fn send_replies(mut consoles: ResMut<PlayerConsoles>) {
+///     for (_player, _line) in consoles.take_lines() {
+///         // send `line` to `player` over the network
+///     }
+/// }
+///
+/// App::new()
+///     .add_console_middleware(AdminsOnly(vec![PlayerId(1)]))
+///     .add_systems(Update, send_replies);
+/// ```
+#[derive(Debug, Default, Resource)]
+pub struct PlayerConsoles {
+    received: VecDeque<(PlayerId, String)>,
+    lines: Vec<(PlayerId, PrintConsoleLine)>,
+    disconnected: Vec<PlayerId>,
+}
+
+impl PlayerConsoles {
+    /// Executes a line entered by the player, in the next frame at the latest.
+    pub fn submit(&mut self, player: PlayerId, line: impl Into<String>) {
+        self.received.push_back((player, line.into()));
+    }
+
+    /// Takes the lines printed to players since the last call, in the order they were printed.
+    pub fn take_lines(&mut self) -> impl Iterator<Item = (PlayerId, PrintConsoleLine)> + '_ {
+        self.lines.drain(..)
+    }
+
+    /// Drops the lines entered by and printed to a player who disconnected, and cancels the
+    /// commands the player scheduled to run later or is playing back.
+    pub fn disconnect(&mut self, player: PlayerId) {
+        self.received.retain(|(from, _)| *from != player);
+        self.lines.retain(|(to, _)| *to != player);
+        self.disconnected.push(player);
+    }
+}
+
+pub(crate) fn setup_player_consoles(app: &mut App) {
+    app.init_resource::<PlayerConsoles>();
+
+    #[cfg(not(feature = "release_disabled"))]
+    app.add_systems(
+        Update,
+        (
+            read_players.in_set(ConsoleSet::ConsoleUI),
+            write_players.in_set(ConsoleSet::PostCommands),
+        ),
+    );
+
+    // the resource stays for the networking layer, but what players enter is never executed
+    #[cfg(feature = "release_disabled")]
+    app.add_systems(Update, drop_player_lines.in_set(ConsoleSet::ConsoleUI));
+}
+
+/// Drops the lines entered by players with `release_disabled`
+#[cfg(feature = "release_disabled")]
+fn drop_player_lines(mut consoles: ResMut<PlayerConsoles>) {
+    consoles.received.clear();
+    consoles.disconnected.clear();
+}
+
+/// Submits the lines entered by players, which are dispatched like the lines of the console
+/// window, and cancels the commands of players who disconnected
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+fn read_players(
+    mut consoles: ResMut<PlayerConsoles>,
+    mut state: ResMut<ConsoleState>,
+    mut scheduler: ResMut<ConsoleScheduler>,
+    mut macros: ResMut<ConsoleMacros>,
+    mut submitted: EventWriter<ConsoleInputSubmitted>,
+) {
+    for player in std::mem::take(&mut consoles.disconnected) {
+        let source = CommandSource::Player(player);
+        scheduler.cancel_from(&source);
+        macros.stop_from(&source);
+        state.forget_prompt(&source);
+    }
+    for _ in 0..MAX_LINES_PER_FRAME {
+        let Some((player, line)) = consoles.received.pop_front() else {
+            return;
+        };
+        submitted.send(ConsoleInputSubmitted::new(
+            line,
+            CommandSource::Player(player),
+        ));
+    }
+}
+
+/// Keeps the lines targeted at players until they're taken
+#[cfg_attr(feature = "release_disabled", allow(dead_code))]
+fn write_players(
+    mut consoles: ResMut<PlayerConsoles>,
+    throttled: Res<ThrottledLines>,
+    mut lines: EventReader<PrintConsoleLine>,
+) {
+    for line in throttled.read(&mut lines) {
+        if let Some(CommandSource::Player(player)) = &line.target {
+            consoles.lines.push((*player, line.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(feature = "release_disabled", allow(unused_imports))]
+mod tests {
+    use super::*;
+    use crate::console::parse_and_dispatch;
+    use crate::testing::test_app;
+    use crate::ConsoleCommandEntered;
+
+    #[test]
+    #[cfg(not(feature = "release_disabled"))]
+    fn test_player_consoles() {
+        let mut app = test_app();
+        app.configure_sets(
+            Update,
+            ConsoleSet::PostCommands.after(ConsoleSet::ConsoleUI),
+        )
+        .add_systems(
+            Update,
+            parse_and_dispatch
+                .after(ConsoleSet::ConsoleUI)
+                .before(ConsoleSet::PostCommands),
+        );
+        setup_player_consoles(&mut app);
+
+        let mut consoles = app.world_mut().resource_mut::<PlayerConsoles>();
+        consoles.submit(PlayerId(1), "say hi");
+        consoles.submit(PlayerId(2), "shout hi");
+        consoles.submit(PlayerId(3), "say bye");
+        consoles.disconnect(PlayerId(3));
+        app.world_mut().send_event_batch([
+            PrintConsoleLine::new("everyone"),
+            PrintConsoleLine::new("reply").with_target(CommandSource::Player(PlayerId(1))),
+        ]);
+        app.update();
+
+        let entered = app.world().resource::<Events<ConsoleCommandEntered>>();
+        let entered = entered.iter_current_update_events().collect::<Vec<_>>();
+        assert_eq!(entered.len(), 1);
+        assert_eq!(entered[0].source.player(), Some(PlayerId(1)));
+
+        let mut consoles = app.world_mut().resource_mut::<PlayerConsoles>();
+        let lines = consoles
+            .take_lines()
+            .map(|(player, line)| (player, line.line.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                (PlayerId(1), "reply".to_owned()),
+                (PlayerId(2), "error: Invalid command".to_owned())
+            ]
+        );
+        assert_eq!(consoles.take_lines().count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "release_disabled")]
+    fn test_release_disabled_drops_player_lines() {
+        let mut app = App::new();
+        app.add_event::<ConsoleCommandEntered>();
+        setup_player_consoles(&mut app);
+
+        let mut consoles = app.world_mut().resource_mut::<PlayerConsoles>();
+        consoles.submit(PlayerId(1), "exit");
+        consoles.disconnect(PlayerId(2));
+        app.update();
+
+        let consoles = app.world().resource::<PlayerConsoles>();
+        assert!(consoles.received.is_empty());
+        assert!(consoles.disconnected.is_empty());
+        assert!(app
+            .world()
+            .resource::<Events<ConsoleCommandEntered>>()
+            .is_empty());
+    }
+}
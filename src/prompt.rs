@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+
+use crate::console::ConsoleState;
+use crate::ConsoleConfiguration;
+
+/// Function producing the value of a prompt placeholder
+pub type PromptFn = Box<dyn Fn(&World) -> String + Send + Sync>;
+
+/// Placeholders available in the [`ConsoleConfiguration::symbol`] prompt template.
+///
+/// Placeholders are written as `{name}`, the built-in ones are:
+/// - `{time}`: current time of day (UTC)
+/// - `{fps}`: smoothed frame rate, requires the `FrameTimeDiagnosticsPlugin`
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_console::ConsolePrompt;
+/// # #[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
+/// # enum GameState { #[default] Menu }
+/// let mut prompt = ConsolePrompt::default();
+/// prompt
+///     .with_state::<GameState>()
+///     .placeholder("entities", |world| world.entities().len().to_string());
+/// ```
+#[derive(Resource)]
+pub struct ConsolePrompt {
+    placeholders: BTreeMap<String, PromptFn>,
+    prompt_fn: Option<PromptFn>,
+}
+
+impl Default for ConsolePrompt {
+    fn default() -> Self {
+        let mut prompt = Self {
+            placeholders: BTreeMap::new(),
+            prompt_fn: None,
+        };
+        prompt
+            .placeholder("time", |_| time_of_day())
+            .placeholder("fps", |world| {
+                world
+                    .get_resource::<DiagnosticsStore>()
+                    .and_then(|store| store.get(&FrameTimeDiagnosticsPlugin::FPS))
+                    .and_then(|fps| fps.smoothed())
+                    .map(|fps| format!("{fps:.0}"))
+                    .unwrap_or_else(|| "-".to_owned())
+            });
+        prompt
+    }
+}
+
+impl ConsolePrompt {
+    /// Registers a `{name}` placeholder, replacing any existing placeholder with the same name.
+    pub fn placeholder(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Fn(&World) -> String + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.placeholders.insert(name.into(), Box::new(value));
+        self
+    }
+
+    /// Registers a `{state}` placeholder showing the current value of the `S` state.
+    pub fn with_state<S: States>(&mut self) -> &mut Self {
+        self.placeholder("state", |world| {
+            world
+                .get_resource::<State<S>>()
+                .map(|state| format!("{:?}", state.get()))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Builds the whole prompt with the given function instead of the [`ConsoleConfiguration::symbol`] template.
+    pub fn set_fn(&mut self, prompt: impl Fn(&World) -> String + Send + Sync + 'static) {
+        self.prompt_fn = Some(Box::new(prompt));
+    }
+}
+
+/// Whether the prompt may render differently than last frame: it has placeholders or a function
+/// rendering it, or the template changed
+pub(crate) fn prompt_may_change(
+    config: Res<ConsoleConfiguration>,
+    prompt: Res<ConsolePrompt>,
+) -> bool {
+    config.is_changed()
+        || prompt.is_changed()
+        || prompt.prompt_fn.is_some()
+        || config.symbol.contains('{')
+}
+
+/// Renders the prompt template into [`ConsoleState`], every frame while it has placeholders
+pub(crate) fn update_prompt(world: &mut World) {
+    world.resource_scope(|world, prompt: Mut<ConsolePrompt>| {
+        let rendered = match &prompt.prompt_fn {
+            Some(prompt_fn) => prompt_fn(world),
+            None => render_template(&world.resource::<ConsoleConfiguration>().symbol, |name| {
+                prompt.placeholders.get(name).map(|value| value(world))
+            }),
+        };
+
+        let mut state = world.resource_mut::<ConsoleState>();
+        if state.prompt != rendered {
+            state.prompt = rendered;
+        }
+    });
+}
+
+/// Replaces every `{name}` in the template, unknown placeholders are kept as they are
+fn render_template(template: &str, resolve: impl Fn(&str) -> Option<String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start..start + len + 1];
+        match resolve(&placeholder[1..placeholder.len() - 1]) {
+            Some(value) => rendered.push_str(&value),
+            None => rendered.push_str(placeholder),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn time_of_day() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+        % 86400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(name: &str) -> Option<String> {
+        match name {
+            "fps" => Some("60".to_owned()),
+            "state" => Some("InGame".to_owned()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        assert_eq!(
+            render_template("[{state} {fps}]$ ", resolve),
+            "[InGame 60]$ "
+        );
+    }
+
+    #[test]
+    fn test_update_prompt() {
+        let mut app = App::new();
+        app.init_resource::<ConsoleState>()
+            .init_resource::<ConsoleConfiguration>()
+            .init_resource::<ConsolePrompt>()
+            .add_systems(Update, update_prompt.run_if(prompt_may_change));
+        app.world_mut()
+            .resource_mut::<ConsolePrompt>()
+            .placeholder("level", |world| {
+                world
+                    .resource::<ConsoleState>()
+                    .scrollback
+                    .len()
+                    .to_string()
+            });
+        app.update();
+        let symbol = app
+            .world()
+            .resource::<ConsoleConfiguration>()
+            .symbol
+            .clone();
+        assert_eq!(app.world().resource::<ConsoleState>().prompt, symbol);
+
+        // a static prompt isn't rendered again until the template changes
+        app.world_mut().resource_mut::<ConsoleState>().prompt = String::new();
+        app.update();
+        assert_eq!(app.world().resource::<ConsoleState>().prompt, "");
+
+        app.world_mut()
+            .resource_mut::<ConsoleConfiguration>()
+            .symbol = "{level}> ".to_owned();
+        app.update();
+        assert_eq!(app.world().resource::<ConsoleState>().prompt, "0> ");
+        app.world_mut()
+            .resource_mut::<ConsoleState>()
+            .scrollback
+            .push(crate::PrintConsoleLine::new("line".to_owned()));
+        app.update();
+        assert_eq!(app.world().resource::<ConsoleState>().prompt, "1> ");
+    }
+
+    #[test]
+    fn test_render_unknown_and_unclosed_placeholders() {
+        assert_eq!(render_template("{nope} $ ", resolve), "{nope} $ ");
+        assert_eq!(render_template("{fps} {$ ", resolve), "60 {$ ");
+    }
+}
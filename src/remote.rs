@@ -0,0 +1,777 @@
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream};
+#[cfg(feature = "tls")]
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+
+use crate::color::strip_ansi;
+use crate::console::ConsoleState;
+use crate::middleware::ConsoleMiddlewares;
+use crate::throttle::ThrottledLines;
+#[cfg(feature = "tls")]
+use crate::tls::{server_config, TlsStream};
+use crate::{
+    CommandSource, ConsoleAppInfo, ConsoleInputSubmitted, ConsoleMacros, ConsoleScheduler,
+    ConsoleSet, PrintConsoleLine,
+};
+
+/// Time a client has to send its token after connecting
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time writing to a client may block before it is disconnected
+const WRITE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Longest line a client may send in bytes, including its line ending, before it is disconnected
+pub(crate) const MAX_LINE_LEN: u64 = 4096;
+
+/// Most connections handled at once, including those still authenticating
+const MAX_CONNECTIONS: usize = 32;
+
+/// Time an address is refused after failing to authenticate, doubled with every further failure
+const AUTH_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest time an address is refused after failing to authenticate
+const MAX_AUTH_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Settings of the remote console, see [`ConsolePlugin::with_remote_console`](crate::ConsolePlugin::with_remote_console).
+///
+/// Clients connect over TCP and send one command per line, the first line has to be one of
+/// the [`tokens`](Self::tokens), which decides what the client is allowed to do, including the
+/// commands run later for the client, e.g. with `after` or `playback`. Those are cancelled once it
+/// disconnects.
+/// Clients receive the replies to their commands and every line printed to all sources, like
+/// log messages. Once authenticated they are sent the [`ConsoleAppInfo`] summary of the app, if
+/// it has one.
+///
+/// No client is needed besides `telnet` or `nc`, see [`RemoteConsole::telnet`] for the settings
+/// matching a terminal.
+///
+/// At most 32 clients are connected at once, and an address which sent an invalid token is
+/// refused for a second, doubling with every further failure up to a minute.
+///
+/// Connections are encrypted with [`with_tls`](Self::with_tls) and the `tls` feature, e.g. for
+/// `openssl s_client` or `socat`. Otherwise tunnel them through SSH or a VPN when exposing the
+/// console beyond a trusted network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteConsole {
+    /// Address to listen on, e.g. `127.0.0.1:7777`
+    pub address: SocketAddr,
+    /// Tokens accepted in the handshake and the permission they grant
+    pub tokens: Vec<(String, RemotePermission)>,
+    /// End lines sent to clients with `\r\n` instead of `\n`
+    pub crlf: bool,
+    /// Send every line received from a client back to it
+    pub echo: bool,
+    /// Keep ANSI escape codes in lines sent to clients instead of removing them
+    pub ansi: bool,
+    /// Encrypt the connections with this certificate
+    #[cfg(feature = "tls")]
+    pub tls: Option<RemoteTls>,
+}
+
+/// Certificate of the remote console, see [`RemoteConsole::with_tls`]
+#[cfg(feature = "tls")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteTls {
+    /// PEM file with the certificate chain, starting with the certificate of the server
+    pub cert_chain: PathBuf,
+    /// PEM file with the private key of the certificate
+    pub private_key: PathBuf,
+}
+
+impl RemoteConsole {
+    /// Listens on `address`, accepting `token` with [`RemotePermission::Full`].
+    pub fn new(address: SocketAddr, token: impl Into<String>) -> Self {
+        Self {
+            address,
+            tokens: vec![(token.into(), RemotePermission::Full)],
+            crlf: false,
+            echo: false,
+            ansi: false,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+
+    /// Sends `\r\n` line endings and keeps ANSI colors, for clients like `telnet` running in a terminal.
+    pub fn telnet(mut self) -> Self {
+        self.crlf = true;
+        self.ansi = true;
+        self
+    }
+
+    /// Sends every line received from a client back to it, for clients not echoing input themselves.
+    pub fn with_echo(mut self) -> Self {
+        self.echo = true;
+        self
+    }
+
+    /// Also accepts `token`, granting the given permission.
+    pub fn with_token(mut self, token: impl Into<String>, permission: RemotePermission) -> Self {
+        self.tokens.push((token.into(), permission));
+        self
+    }
+
+    /// Encrypts the connections with TLS, using the certificate chain and private key in the
+    /// given PEM files. The console isn't started if they can't be loaded.
+    #[cfg(feature = "tls")]
+    pub fn with_tls(
+        mut self,
+        cert_chain: impl Into<PathBuf>,
+        private_key: impl Into<PathBuf>,
+    ) -> Self {
+        self.tls = Some(RemoteTls {
+            cert_chain: cert_chain.into(),
+            private_key: private_key.into(),
+        });
+        self
+    }
+}
+
+/// What a remote client is allowed to do
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RemotePermission {
+    /// Only receive console output
+    ReadOnly,
+    /// Run the commands with the given names
+    Commands(BTreeSet<String>),
+    /// Run every command
+    Full,
+}
+
+impl RemotePermission {
+    /// Returns whether the command with the given name may be run.
+    pub fn allows(&self, command_name: &str) -> bool {
+        match self {
+            RemotePermission::ReadOnly => false,
+            RemotePermission::Commands(commands) => commands.contains(command_name),
+            RemotePermission::Full => true,
+        }
+    }
+}
+
+/// A remote console client sent an invalid token, or none in time, and was disconnected
+#[derive(Clone, Debug, Event, PartialEq, Eq)]
+pub struct AuthFailed {
+    /// Address of the client
+    pub address: SocketAddr,
+}
+
+enum RemoteEvent {
+    Connected(SocketAddr, RemotePermission, ClientStream),
+    AuthFailed(SocketAddr),
+    Line(SocketAddr, String),
+    Disconnected(SocketAddr),
+}
+
+/// Authenticated clients, and events from their connection threads
+#[derive(Resource)]
+pub(crate) struct RemoteClients {
+    rx: Mutex<Receiver<RemoteEvent>>,
+    clients: HashMap<SocketAddr, ClientStream>,
+    format: LineFormat,
+}
+
+impl RemoteClients {
+    /// Number of authenticated clients currently connected
+    pub(crate) fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+}
+
+/// How lines are written to clients
+#[derive(Clone, Copy)]
+struct LineFormat {
+    crlf: bool,
+    ansi: bool,
+}
+
+impl LineFormat {
+    fn format(self, line: &str) -> String {
+        let line = if self.ansi {
+            line.to_owned()
+        } else {
+            strip_ansi(line)
+        };
+        let ending = if self.crlf { "\r\n" } else { "\n" };
+        let mut formatted = line.lines().collect::<Vec<_>>().join(ending);
+        formatted.push_str(ending);
+        formatted
+    }
+}
+
+/// Connection of a client, encrypted if the console has a certificate
+enum ClientStream {
+    Tcp(TcpStream),
+    #[cfg(feature = "tls")]
+    Tls(TlsStream),
+}
+
+impl ClientStream {
+    /// The underlying socket, e.g. to set timeouts or shut it down
+    fn tcp(&self) -> &TcpStream {
+        match self {
+            Self::Tcp(stream) => stream,
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => stream.tcp(),
+        }
+    }
+
+    fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Self::Tcp(stream) => stream.try_clone().map(Self::Tcp),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => stream.try_clone().map(Self::Tls),
+        }
+    }
+}
+
+impl Read for ClientStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.read(buf),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for ClientStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.write(buf),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.flush(),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Caps the connections handled at once, and refuses addresses which failed to authenticate for
+/// a while, so tokens can't be guessed quickly. Shared with the HTTP endpoint.
+#[derive(Clone, Default)]
+pub(crate) struct ConnectionLimiter(Arc<Mutex<LimiterState>>);
+
+#[derive(Default)]
+struct LimiterState {
+    connections: usize,
+    /// Failed authentications in a row and the time until which the address is refused
+    failures: HashMap<IpAddr, (u32, Instant)>,
+}
+
+impl ConnectionLimiter {
+    /// Returns a permit for a new connection from `ip`, `None` if it should be refused
+    pub(crate) fn admit(&self, ip: IpAddr, now: Instant) -> Option<ConnectionPermit> {
+        let mut state = self.0.lock().ok()?;
+        // the count is kept for a while after the backoff ended, so repeated failures still grow it
+        state
+            .failures
+            .retain(|_, (_, refused_until)| now < *refused_until + MAX_AUTH_BACKOFF);
+        if state
+            .failures
+            .get(&ip)
+            .is_some_and(|(_, refused_until)| now < *refused_until)
+            || state.connections >= MAX_CONNECTIONS
+        {
+            return None;
+        }
+        state.connections += 1;
+        Some(ConnectionPermit {
+            limiter: self.clone(),
+            ip,
+        })
+    }
+}
+
+/// A connection counted by a [`ConnectionLimiter`], until it is dropped
+pub(crate) struct ConnectionPermit {
+    limiter: ConnectionLimiter,
+    ip: IpAddr,
+}
+
+impl ConnectionPermit {
+    /// Records whether the client authenticated, refusing its address for a while if it didn't
+    pub(crate) fn authenticated(&self, authenticated: bool, now: Instant) {
+        let Ok(mut state) = self.limiter.0.lock() else {
+            return;
+        };
+        if authenticated {
+            state.failures.remove(&self.ip);
+            return;
+        }
+        let (failures, refused_until) = state.failures.entry(self.ip).or_insert((0, now));
+        *failures += 1;
+        let backoff = AUTH_BACKOFF
+            .saturating_mul(2u32.saturating_pow(*failures - 1))
+            .min(MAX_AUTH_BACKOFF);
+        *refused_until = now + backoff;
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.limiter.0.lock() {
+            state.connections -= 1;
+        }
+    }
+}
+
+/// Listens for remote console clients on a background thread
+pub(crate) fn setup_remote_console(app: &mut App, remote: &RemoteConsole) {
+    let (tx, rx) = mpsc::channel();
+    let format = LineFormat {
+        crlf: remote.crlf,
+        ansi: remote.ansi,
+    };
+    let echo = remote.echo;
+    let tokens = Arc::new(remote.tokens.clone());
+    let app_info: Option<Arc<str>> = app
+        .world()
+        .get_resource::<ConsoleAppInfo>()
+        .map(|info| info.summary().into());
+    #[cfg(feature = "tls")]
+    let tls = match &remote.tls {
+        Some(tls) => match server_config(&tls.cert_chain, &tls.private_key) {
+            Ok(config) => Some(config),
+            Err(err) => {
+                warn!(
+                    "Failed to load the certificate of the remote console from {}: {err}",
+                    tls.cert_chain.display()
+                );
+                return;
+            }
+        },
+        None => None,
+    };
+    match TcpListener::bind(remote.address) {
+        Ok(listener) => {
+            info!("Remote console listening on {}", remote.address);
+            let limiter = ConnectionLimiter::default();
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let Some(permit) = stream
+                        .peer_addr()
+                        .ok()
+                        .and_then(|address| limiter.admit(address.ip(), Instant::now()))
+                    else {
+                        continue;
+                    };
+                    #[cfg(feature = "tls")]
+                    let stream = match &tls {
+                        Some(config) => match TlsStream::new(config.clone(), stream) {
+                            Ok(stream) => ClientStream::Tls(stream),
+                            Err(_) => continue,
+                        },
+                        None => ClientStream::Tcp(stream),
+                    };
+                    #[cfg(not(feature = "tls"))]
+                    let stream = ClientStream::Tcp(stream);
+                    let (tokens, app_info, tx) = (tokens.clone(), app_info.clone(), tx.clone());
+                    thread::spawn(move || {
+                        let app_info = app_info.as_deref();
+                        handle_client(stream, &permit, &tokens, app_info, format, echo, &tx);
+                    });
+                }
+            });
+        }
+        Err(err) => warn!(
+            "Failed to start the remote console on {}: {err}",
+            remote.address
+        ),
+    }
+
+    app.add_event::<AuthFailed>()
+        .insert_resource(RemoteClients {
+            rx: Mutex::new(rx),
+            clients: HashMap::new(),
+            format,
+        })
+        .add_systems(
+            Update,
+            (
+                read_remote.in_set(ConsoleSet::ConsoleUI),
+                write_remote.in_set(ConsoleSet::PostCommands),
+            ),
+        );
+}
+
+/// Authenticates a client, then forwards its lines until it disconnects
+fn handle_client(
+    stream: ClientStream,
+    permit: &ConnectionPermit,
+    tokens: &[(String, RemotePermission)],
+    app_info: Option<&str>,
+    format: LineFormat,
+    echo: bool,
+    tx: &Sender<RemoteEvent>,
+) {
+    let Ok(address) = stream.tcp().peer_addr() else {
+        return;
+    };
+    let connection = stream
+        .tcp()
+        .set_read_timeout(Some(AUTH_TIMEOUT))
+        .and_then(|_| stream.try_clone());
+    let Ok(reader) = connection else {
+        return;
+    };
+    let mut reader = BufReader::new(reader);
+    let mut writer = stream;
+
+    let permission =
+        authenticate(&mut reader, &mut writer, tokens, app_info, format).unwrap_or(None);
+    permit.authenticated(permission.is_some(), Instant::now());
+    let connected = permission.and_then(|permission| {
+        writer.tcp().set_read_timeout(None).ok()?;
+        writer.tcp().set_write_timeout(Some(WRITE_TIMEOUT)).ok()?;
+        Some(RemoteEvent::Connected(
+            address,
+            permission,
+            writer.try_clone().ok()?,
+        ))
+    });
+    let Some(connected) = connected else {
+        let _ = writer.tcp().shutdown(Shutdown::Both);
+        let _ = tx.send(RemoteEvent::AuthFailed(address));
+        return;
+    };
+    if tx.send(connected).is_err() {
+        return;
+    }
+
+    while let Ok(Some(line)) = read_line(&mut reader) {
+        if echo && writer.write_all(format.format(&line).as_bytes()).is_err() {
+            break;
+        }
+        if tx.send(RemoteEvent::Line(address, line)).is_err() {
+            return;
+        }
+    }
+    let _ = tx.send(RemoteEvent::Disconnected(address));
+}
+
+/// Asks for a token and returns the permission it grants, `None` if it is invalid.
+///
+/// Authenticated clients are also told the summary of the app from its [`ConsoleAppInfo`].
+fn authenticate(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    tokens: &[(String, RemotePermission)],
+    app_info: Option<&str>,
+    format: LineFormat,
+) -> io::Result<Option<RemotePermission>> {
+    write!(writer, "token: ")?;
+    writer.flush()?;
+    let token = read_line(reader)?.unwrap_or_default();
+
+    let permission = token_permission(tokens, &token);
+    let reply = match permission {
+        Some(_) => "authenticated",
+        None => "authentication failed",
+    };
+    writer.write_all(format.format(reply).as_bytes())?;
+    if let Some(app_info) = app_info.filter(|_| permission.is_some()) {
+        writer.write_all(format.format(app_info).as_bytes())?;
+    }
+    Ok(permission)
+}
+
+/// Reads a line without its line ending, `None` once the client disconnected.
+///
+/// Telnet commands are skipped and invalid UTF-8 is replaced, so any client can connect
+/// without negotiating options first. Lines longer than [`MAX_LINE_LEN`] are an error, so
+/// clients can't exhaust the memory, even before authenticating.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    /// Telnet "interpret as command" byte
+    const IAC: u8 = 255;
+    /// Telnet subnegotiation start and end
+    const SB: u8 = 250;
+    const SE: u8 = 240;
+
+    let mut bytes = Vec::new();
+    let read = reader
+        .by_ref()
+        .take(MAX_LINE_LEN)
+        .read_until(b'\n', &mut bytes)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if read as u64 == MAX_LINE_LEN && bytes.last() != Some(&b'\n') {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "line too long"));
+    }
+
+    let mut line = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter().copied();
+    while let Some(byte) = iter.next() {
+        if byte != IAC {
+            line.push(byte);
+            continue;
+        }
+        match iter.next() {
+            // an escaped 255 data byte
+            Some(IAC) => line.push(IAC),
+            Some(SB) => {
+                while let Some(byte) = iter.next() {
+                    if byte == IAC && iter.next() == Some(SE) {
+                        break;
+                    }
+                }
+            }
+            // WILL, WONT, DO and DONT are followed by the option
+            Some(251..=254) => {
+                iter.next();
+            }
+            _ => {}
+        }
+    }
+    let line = String::from_utf8_lossy(&line);
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_owned()))
+}
+
+/// Returns the permission granted by `token`, `None` if it isn't one of `tokens`
+pub(crate) fn token_permission(
+    tokens: &[(String, RemotePermission)],
+    token: &str,
+) -> Option<RemotePermission> {
+    tokens
+        .iter()
+        .find(|(expected, _)| constant_time_eq(expected.as_bytes(), token.as_bytes()))
+        .map(|(_, permission)| permission.clone())
+}
+
+/// Compares the tokens without returning early, so their contents can't be guessed from timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Submits the lines of connected clients, which are dispatched like the lines of the console
+/// window
+fn read_remote(
+    mut remote: ResMut<RemoteClients>,
+    mut state: ResMut<ConsoleState>,
+    mut middlewares: ResMut<ConsoleMiddlewares>,
+    mut scheduler: ResMut<ConsoleScheduler>,
+    mut macros: ResMut<ConsoleMacros>,
+    mut submitted: EventWriter<ConsoleInputSubmitted>,
+    mut auth_failed: EventWriter<AuthFailed>,
+) {
+    let events = match remote.rx.lock() {
+        Ok(rx) => rx.try_iter().collect::<Vec<_>>(),
+        Err(_) => return,
+    };
+    for event in events {
+        match event {
+            RemoteEvent::Connected(address, permission, stream) => {
+                info!("Remote console client {address} connected");
+                remote.clients.insert(address, stream);
+                middlewares.grant(CommandSource::Remote(address), permission);
+            }
+            RemoteEvent::AuthFailed(address) => {
+                warn!("Remote console client {address} failed to authenticate");
+                auth_failed.send(AuthFailed { address });
+            }
+            RemoteEvent::Disconnected(address) => {
+                info!("Remote console client {address} disconnected");
+                remote.clients.remove(&address);
+                let source = CommandSource::Remote(address);
+                middlewares.revoke(&source);
+                scheduler.cancel_from(&source);
+                macros.stop_from(&source);
+                state.forget_prompt(&source);
+            }
+            RemoteEvent::Line(address, line) => {
+                if remote.clients.contains_key(&address) {
+                    submitted.send(ConsoleInputSubmitted::new(
+                        line,
+                        CommandSource::Remote(address),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Sends clients the replies to their commands and lines printed to everyone
+fn write_remote(
+    mut remote: ResMut<RemoteClients>,
+    throttled: Res<ThrottledLines>,
+    mut lines: EventReader<PrintConsoleLine>,
+) {
+    if remote.clients.is_empty() {
+        lines.clear();
+        return;
+    }
+
+    let format = remote.format;
+    let mut disconnected = Vec::new();
+    for line in throttled.read(&mut lines) {
+        for (address, stream) in remote.clients.iter_mut() {
+            let recipient = match &line.target {
+                None => true,
+                Some(CommandSource::Remote(target)) => target == address,
+                Some(_) => false,
+            };
+            if recipient
+                && stream
+                    .write_all(
+                        format
+                            .format(line.json.as_deref().unwrap_or(&line.line))
+                            .as_bytes(),
+                    )
+                    .is_err()
+            {
+                disconnected.push(*address);
+            }
+        }
+    }
+    for address in disconnected {
+        if let Some(stream) = remote.clients.remove(&address) {
+            let _ = stream.tcp().shutdown(Shutdown::Both);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_authenticate() {
+        let tokens = [
+            ("secret".to_owned(), RemotePermission::Full),
+            ("guest".to_owned(), RemotePermission::ReadOnly),
+        ];
+        let format = LineFormat {
+            crlf: false,
+            ansi: false,
+        };
+        let mut output = Vec::new();
+        let permission = authenticate(
+            &mut Cursor::new("guest\r\n"),
+            &mut output,
+            &tokens,
+            None,
+            format,
+        );
+        assert_eq!(permission.unwrap(), Some(RemotePermission::ReadOnly));
+        assert_eq!(output, b"token: authenticated\n");
+
+        let app_info = ConsoleAppInfo::new("my_game", "1.0.0").with_build_profile("release");
+        let mut output = Vec::new();
+        let permission = authenticate(
+            &mut Cursor::new("secret\n"),
+            &mut output,
+            &tokens,
+            Some(&app_info.summary()),
+            format,
+        );
+        assert_eq!(permission.unwrap(), Some(RemotePermission::Full));
+        assert_eq!(output, b"token: authenticated\nmy_game 1.0.0 (release)\n");
+
+        // the app isn't revealed to clients without a valid token
+        let mut output = Vec::new();
+        let permission = authenticate(
+            &mut Cursor::new("secre\n"),
+            &mut output,
+            &tokens,
+            Some(&app_info.summary()),
+            format,
+        );
+        assert_eq!(permission.unwrap(), None);
+        assert_eq!(output, b"token: authentication failed\n");
+    }
+
+    #[test]
+    fn test_connection_limiter() {
+        let limiter = ConnectionLimiter::default();
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let now = Instant::now();
+        let permits = (0..MAX_CONNECTIONS)
+            .map(|_| limiter.admit(ip, now).unwrap())
+            .collect::<Vec<_>>();
+        assert!(limiter.admit(ip, now).is_none());
+        drop(permits);
+
+        let permit = limiter.admit(ip, now).unwrap();
+        permit.authenticated(false, now);
+        assert!(limiter.admit(ip, now).is_none());
+        // other addresses aren't affected
+        assert!(limiter.admit(IpAddr::from([127, 0, 0, 2]), now).is_some());
+        assert!(limiter.admit(ip, now + AUTH_BACKOFF).is_some());
+
+        // the backoff doubles with every failure in a row
+        permit.authenticated(false, now);
+        assert!(limiter.admit(ip, now + AUTH_BACKOFF).is_none());
+        assert!(limiter.admit(ip, now + AUTH_BACKOFF * 2).is_some());
+        for _ in 0..10 {
+            permit.authenticated(false, now);
+        }
+        assert!(limiter.admit(ip, now + MAX_AUTH_BACKOFF).is_some());
+
+        permit.authenticated(true, now);
+        assert!(limiter.admit(ip, now).is_some());
+    }
+
+    #[test]
+    fn test_read_line_skips_telnet_commands() {
+        let mut input =
+            Cursor::new(b"\xff\xfd\x03fps\xff\xfa\x18\x01\xff\xf0 -h\r\nnext\n".to_vec());
+        assert_eq!(read_line(&mut input).unwrap().as_deref(), Some("fps -h"));
+        assert_eq!(read_line(&mut input).unwrap().as_deref(), Some("next"));
+        assert_eq!(read_line(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_line_rejects_long_lines() {
+        let mut line = vec![b'a'; MAX_LINE_LEN as usize - 1];
+        line.push(b'\n');
+        assert!(read_line(&mut Cursor::new(line)).unwrap().is_some());
+
+        let mut input = Cursor::new(vec![b'a'; MAX_LINE_LEN as usize * 2]);
+        let error = read_line(&mut input).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        // the rest isn't buffered
+        assert_eq!(input.position(), MAX_LINE_LEN);
+    }
+
+    #[test]
+    fn test_line_format() {
+        let telnet = LineFormat {
+            crlf: true,
+            ansi: true,
+        };
+        assert_eq!(
+            telnet.format("\x1b[31ma\x1b[0m\nb"),
+            "\x1b[31ma\x1b[0m\r\nb\r\n"
+        );
+        let plain = LineFormat {
+            crlf: false,
+            ansi: false,
+        };
+        assert_eq!(plain.format("\x1b[31ma\x1b[0m"), "a\n");
+    }
+
+    #[test]
+    fn test_permission_allows() {
+        let permission = RemotePermission::Commands(BTreeSet::from(["fps".to_owned()]));
+        assert!(permission.allows("fps"));
+        assert!(!permission.allows("exit"));
+        assert!(!RemotePermission::ReadOnly.allows("fps"));
+        assert!(RemotePermission::Full.allows("exit"));
+    }
+}
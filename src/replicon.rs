@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::middleware::ConsoleMiddlewares;
+use crate::player::PlayerConsoles;
+use crate::{
+    CommandSource, ConsoleSet, ConsoleSeverity, PlayerId, PrintConsoleLine, RemotePermission,
+};
+
+/// Settings of the console commands bevy_replicon clients send to the server, see
+/// [`ConsolePlugin::with_networked_console`](crate::ConsolePlugin::with_networked_console).
+///
+/// Clients send a [`NetworkedConsoleCommand`], e.g. with the `server` command, which the server
+/// executes as a command of the player if their permission allows it. The replies are sent back
+/// as [`NetworkedConsoleReply`] and printed in the client's console. Works with any messaging
+/// library of bevy_replicon, like renet with bevy_replicon_renet.
+///
+/// The server and its clients need the same settings, the permissions are only checked by the
+/// server.
+#[derive(Clone, Debug, PartialEq, Eq, Resource)]
+pub struct NetworkedConsole {
+    /// Permission of the players without one of their own
+    pub default_permission: RemotePermission,
+    /// Permissions of given players, e.g. admins
+    pub permissions: HashMap<PlayerId, RemotePermission>,
+}
+
+impl NetworkedConsole {
+    /// Grants every player the given permission.
+    pub fn new(default_permission: RemotePermission) -> Self {
+        Self {
+            default_permission,
+            permissions: HashMap::new(),
+        }
+    }
+
+    /// Grants a player the given permission instead of the default one.
+    pub fn with_permission(mut self, player: PlayerId, permission: RemotePermission) -> Self {
+        self.permissions.insert(player, permission);
+        self
+    }
+
+    /// Returns the permission of a player.
+    pub fn permission(&self, player: PlayerId) -> &RemotePermission {
+        self.permissions
+            .get(&player)
+            .unwrap_or(&self.default_permission)
+    }
+}
+
+/// A console line a client sends to be executed on the server
+#[derive(Clone, Debug, Deserialize, Event, PartialEq, Eq, Serialize)]
+pub struct NetworkedConsoleCommand {
+    /// The command and its arguments, as typed into a console
+    pub line: String,
+}
+
+/// A line the server printed in reply to a [`NetworkedConsoleCommand`] of the client
+#[derive(Clone, Debug, Deserialize, Event, PartialEq, Eq, Serialize)]
+pub struct NetworkedConsoleReply {
+    /// Text of the line, including its ANSI escape codes
+    pub line: String,
+    /// Severity of the line
+    pub severity: ConsoleSeverity,
+    /// Channel the line was printed to
+    pub channel: Option<String>,
+}
+
+impl From<&PrintConsoleLine> for NetworkedConsoleReply {
+    fn from(line: &PrintConsoleLine) -> Self {
+        Self {
+            line: line.line.to_string(),
+            severity: line.severity,
+            channel: line.channel.clone(),
+        }
+    }
+}
+
+impl From<NetworkedConsoleReply> for PrintConsoleLine {
+    fn from(reply: NetworkedConsoleReply) -> Self {
+        Self {
+            channel: reply.channel,
+            ..PrintConsoleLine::new(reply.line).with_severity(reply.severity)
+        }
+    }
+}
+
+/// Registers the events once the replicon plugins were added
+pub(crate) fn setup_networked_console(app: &mut App, settings: &NetworkedConsole) {
+    app.insert_resource(settings.clone())
+        .add_client_event::<NetworkedConsoleCommand>(ChannelKind::Ordered)
+        .add_server_event::<NetworkedConsoleReply>(ChannelKind::Ordered)
+        // replies don't refer to entities, so they don't wait for the replication of their tick
+        .make_independent::<NetworkedConsoleReply>()
+        .add_systems(
+            Update,
+            print_replies
+                .run_if(client_connected)
+                .before(ConsoleSet::PostCommands),
+        );
+
+    // the events stay registered so the protocol matches the clients, but the server never
+    // executes their commands
+    #[cfg(not(feature = "release_disabled"))]
+    app.add_systems(
+        Update,
+        (
+            receive_commands.before(ConsoleSet::ConsoleUI),
+            send_replies.after(ConsoleSet::PostCommands),
+            disconnect_players,
+        )
+            .run_if(server_running),
+    );
+}
+
+/// Submits the commands of clients to their player console, which only runs the commands their
+/// permission allows, including the ones they schedule to run later
+fn receive_commands(
+    mut commands: EventReader<FromClient<NetworkedConsoleCommand>>,
+    settings: Res<NetworkedConsole>,
+    mut middlewares: ResMut<ConsoleMiddlewares>,
+    mut consoles: ResMut<PlayerConsoles>,
+) {
+    for FromClient { client_id, event } in commands.read() {
+        let player = PlayerId(client_id.get());
+        middlewares.grant(
+            CommandSource::Player(player),
+            settings.permission(player).clone(),
+        );
+        consoles.submit(player, event.line.clone());
+    }
+}
+
+/// Sends the lines printed to players to their clients
+fn send_replies(
+    mut consoles: ResMut<PlayerConsoles>,
+    mut replies: EventWriter<ToClients<NetworkedConsoleReply>>,
+) {
+    for (player, line) in consoles.take_lines() {
+        replies.send(ToClients {
+            mode: SendMode::Direct(ClientId::new(player.0)),
+            event: NetworkedConsoleReply::from(&line),
+        });
+    }
+}
+
+fn disconnect_players(
+    mut events: EventReader<ServerEvent>,
+    mut consoles: ResMut<PlayerConsoles>,
+    mut middlewares: ResMut<ConsoleMiddlewares>,
+) {
+    for event in events.read() {
+        if let ServerEvent::ClientDisconnected { client_id, .. } = event {
+            let player = PlayerId(client_id.get());
+            consoles.disconnect(player);
+            middlewares.revoke(&CommandSource::Player(player));
+        }
+    }
+}
+
+/// Prints the replies of the server in the client's console
+fn print_replies(
+    mut replies: EventReader<NetworkedConsoleReply>,
+    mut console_line: EventWriter<PrintConsoleLine>,
+) {
+    console_line.send_batch(
+        replies
+            .read()
+            .map(|reply| PrintConsoleLine::from(reply.clone())),
+    );
+}
+
+#[cfg(all(test, not(feature = "release_disabled")))]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use bevy_replicon::test_app::ServerTestAppExt;
+
+    use super::*;
+    use crate::console::parse_and_dispatch;
+    use crate::player::setup_player_consoles;
+    use crate::testing::test_app;
+    use crate::ConsoleCommandEntered;
+
+    #[test]
+    fn test_networked_console() {
+        let settings = NetworkedConsole::new(RemotePermission::Commands(BTreeSet::from([
+            "say".to_owned()
+        ])));
+        let mut server_app = test_app();
+        let mut client_app = App::new();
+        for app in [&mut server_app, &mut client_app] {
+            app.add_plugins((MinimalPlugins, RepliconPlugins))
+                .add_event::<PrintConsoleLine>()
+                .configure_sets(
+                    Update,
+                    ConsoleSet::PostCommands.after(ConsoleSet::ConsoleUI),
+                );
+            setup_networked_console(app, &settings);
+        }
+        server_app.add_systems(
+            Update,
+            parse_and_dispatch
+                .after(ConsoleSet::ConsoleUI)
+                .before(ConsoleSet::PostCommands),
+        );
+        setup_player_consoles(&mut server_app);
+        server_app.connect_client(&mut client_app);
+
+        client_app.world_mut().send_event_batch([
+            NetworkedConsoleCommand {
+                line: "say hi".to_owned(),
+            },
+            NetworkedConsoleCommand {
+                line: "exit".to_owned(),
+            },
+        ]);
+        client_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        server_app.update();
+
+        let entered = server_app
+            .world()
+            .resource::<Events<ConsoleCommandEntered>>();
+        let entered = entered.iter_current_update_events().collect::<Vec<_>>();
+        assert_eq!(entered.len(), 1);
+        assert_eq!(entered[0].command_name, "say");
+        assert!(entered[0].source.player().is_some());
+
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+        let lines = client_app.world().resource::<Events<PrintConsoleLine>>();
+        let lines = lines
+            .iter_current_update_events()
+            .map(|line| line.line.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, ["Permission denied for 'exit'"]);
+    }
+}
@@ -1,54 +1,199 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, IsTerminal};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::thread;
 
 use bevy::prelude::*;
+use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
-use rustyline::{DefaultEditor, Result};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{config::Configurer, ColorMode, Context, Editor, Helper, Result};
 
-use crate::ConsoleCommandEntered;
+use crate::completion::argument_completions;
+use crate::console::{ConsoleOpen, ConsoleState};
+use crate::frontend::read_frontend;
+use crate::prompt::update_prompt;
+use crate::{
+    AddConsoleFrontend, CommandSource, ConsoleConfiguration, ConsoleEof, ConsoleFrontend,
+    ConsoleInterrupted, ConsoleSeverity, EditMode, EofAction, PrintConsoleLine,
+};
+
+/// The terminal the app was started from, read with rustyline on a separate thread
 #[derive(Resource)]
 pub struct ConsoleLineReceiver {
     rx: Mutex<Receiver<Result<String>>>,
+    /// Interrupt or end of input read after the lines submitted this frame
+    signal: Option<TerminalSignal>,
+    /// Whether a command is waiting for the next line, which then isn't stored in history
+    prompting: Arc<AtomicBool>,
+    /// Whether the line a command is waiting for is secret, so it is masked while typed
+    secret: Arc<AtomicBool>,
+    /// Rendered [`ConsoleConfiguration::symbol`], shown from the next line read on
+    prompt: Arc<Mutex<String>>,
+    /// Registered commands and the [`ConsoleConfiguration::completion_root`], for completion
+    completion: Arc<Mutex<CompletionSource>>,
+}
+
+#[derive(Default)]
+struct CompletionSource {
+    commands: BTreeMap<&'static str, clap::Command>,
+    root: PathBuf,
+}
+
+/// Completes argument values and file system paths on tab, and masks secret answers
+struct ArgumentCompleter {
+    source: Arc<Mutex<CompletionSource>>,
+    secret: Arc<AtomicBool>,
+}
+
+impl ArgumentCompleter {
+    fn is_secret(&self) -> bool {
+        self.secret.load(Ordering::Relaxed)
+    }
+}
+
+impl Completer for ArgumentCompleter {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Result<(usize, Vec<String>)> {
+        if self.is_secret() {
+            return Ok((pos, Vec::new()));
+        }
+        let completions =
+            self.source.lock().ok().and_then(|source| {
+                argument_completions(&source.commands, &line[..pos], &source.root)
+            });
+        Ok(completions.unwrap_or((pos, Vec::new())))
+    }
+}
+
+impl Hinter for ArgumentCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for ArgumentCompleter {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        if self.is_secret() {
+            Cow::Owned("*".repeat(line.chars().count()))
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+
+    // the line is highlighted again on every key while it is masked
+    fn highlight_char(&self, _line: &str, _pos: usize, _forced: bool) -> bool {
+        self.is_secret()
+    }
 }
 
-/// The user inputted a console interrupt
-#[derive(Event)]
-pub struct ConsoleInterrupted;
+impl Validator for ArgumentCompleter {}
 
-fn str_to_command(str: &str) -> Option<ConsoleCommandEntered> {
-    let mut iter = str.split_whitespace();
-    let command_name = iter.next()?.to_owned();
-    let args = iter.map(|s| s.to_owned()).collect();
+impl Helper for ArgumentCompleter {}
 
-    Some(ConsoleCommandEntered { command_name, args })
+enum TerminalSignal {
+    Interrupted,
+    Eof,
 }
 
-fn read_rustyline(
-    clr: Res<ConsoleLineReceiver>,
-    mut evw_consolecommand: EventWriter<ConsoleCommandEntered>,
+impl ConsoleFrontend for ConsoleLineReceiver {
+    fn source(&self) -> CommandSource {
+        CommandSource::Terminal
+    }
+
+    fn next_line(&mut self) -> Option<String> {
+        let rx = self.rx.get_mut().ok()?;
+        loop {
+            // lines after a signal are read once it was handled
+            match rx.try_recv().ok()? {
+                Ok(line) => return Some(line),
+                Err(ReadlineError::Interrupted) => {
+                    self.signal = Some(TerminalSignal::Interrupted);
+                    return None;
+                }
+                Err(ReadlineError::Eof) => {
+                    self.signal = Some(TerminalSignal::Eof);
+                    return None;
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    /// Only replies to commands entered in the terminal are printed,
+    /// everything else is already logged there
+    fn accepts(&self, line: &PrintConsoleLine, _config: &ConsoleConfiguration) -> bool {
+        line.target == Some(CommandSource::Terminal)
+    }
+
+    fn print_line(&mut self, line: &PrintConsoleLine, config: &ConsoleConfiguration) {
+        let text = config.ansi.terminal.apply(&line.line);
+        match line.severity {
+            ConsoleSeverity::Error | ConsoleSeverity::Warn => eprintln!("{text}"),
+            _ => println!("{text}"),
+        }
+    }
+}
+
+fn handle_terminal_signal(
+    mut clr: ResMut<ConsoleLineReceiver>,
+    config: Res<ConsoleConfiguration>,
     mut evw_interrupt: EventWriter<ConsoleInterrupted>,
+    mut evw_eof: EventWriter<ConsoleEof>,
+    mut evw_exit: EventWriter<AppExit>,
+    mut console_open: ResMut<ConsoleOpen>,
 ) {
-    if let Ok(r) = clr.rx.lock() {
-        if let Ok(res) = r.try_recv() {
-            match res {
-                Ok(str) => {
-                    evw_consolecommand.send_batch(str_to_command(&str));
-                }
-                Err(ReadlineError::Interrupted) => {
-                    evw_interrupt.send(ConsoleInterrupted);
+    match clr.signal.take() {
+        Some(TerminalSignal::Interrupted) => {
+            evw_interrupt.send(ConsoleInterrupted);
+        }
+        Some(TerminalSignal::Eof) => {
+            evw_eof.send(ConsoleEof);
+            match config.eof_action {
+                EofAction::Ignore => {}
+                EofAction::CloseConsole => console_open.open = false,
+                EofAction::Exit => {
+                    evw_exit.send(AppExit::Success);
                 }
-                _ => (),
             }
         }
+        None => {}
     }
 }
 
 pub(super) fn setup_rustyline(app: &mut App) {
     let (tx, rx): (Sender<Result<String>>, Receiver<Result<String>>) = mpsc::channel();
+    let config = app.world().resource::<ConsoleConfiguration>();
+    let history_policy = config.history_policy;
+    let history_size = config.history_size;
+    let edit_mode = match config.edit_mode {
+        EditMode::Emacs => rustyline::EditMode::Emacs,
+        EditMode::Vi => rustyline::EditMode::Vi,
+    };
+    let prompting = Arc::new(AtomicBool::new(false));
+    let thread_prompting = prompting.clone();
+    let secret = Arc::new(AtomicBool::new(false));
+    let thread_secret = secret.clone();
+    // placeholders are only rendered from the first frame on
+    let prompt = Arc::new(Mutex::new(config.symbol.clone()));
+    let thread_prompt = prompt.clone();
+    let completion = Arc::new(Mutex::new(CompletionSource::default()));
+    let thread_completion = completion.clone();
 
     thread::spawn(move || {
-        let mut rl = match DefaultEditor::new() {
+        // rustyline can't edit without a terminal, e.g. in a container without a TTY
+        if !io::stdin().is_terminal() {
+            read_plain_stdin(&tx);
+            return;
+        }
+
+        let mut rl = match Editor::<ArgumentCompleter, DefaultHistory>::new() {
             Err(e) => {
                 error!(
                     "Error: {e:?}. Failed to create rustyline editor. Reading input from attached console will not be available."
@@ -57,14 +202,175 @@ pub(super) fn setup_rustyline(app: &mut App) {
             }
             Ok(rl) => rl,
         };
+        rl.set_helper(Some(ArgumentCompleter {
+            source: thread_completion,
+            secret: thread_secret,
+        }));
+        rl.set_edit_mode(edit_mode);
+        // secret answers are masked by the highlighter, which is skipped without colors otherwise
+        rl.set_color_mode(ColorMode::Forced);
+        // Duplicates are filtered by the history policy instead
+        if let Err(e) = rl
+            .set_max_history_size(history_size)
+            .and_then(|_| rl.set_history_ignore_dups(false))
+        {
+            warn!("Failed to configure rustyline history: {e:?}");
+        }
 
+        let mut previous: Option<String> = None;
         loop {
-            let input = rl.readline("");
+            // rustyline skips ANSI escape codes when measuring the prompt, so colors are kept
+            let prompt = thread_prompt.lock().map(|p| p.clone()).unwrap_or_default();
+            let input = rl.readline(&prompt);
+            if let Ok(line) = &input {
+                if !thread_prompting.load(Ordering::Relaxed)
+                    && history_policy.should_store(line, previous.as_deref())
+                {
+                    let _ = rl.add_history_entry(line.as_str());
+                    previous = Some(line.clone());
+                }
+            }
             let _ = tx.send(input);
         }
     });
 
-    app.insert_resource(ConsoleLineReceiver { rx: Mutex::new(rx) })
-        .add_event::<ConsoleInterrupted>()
-        .add_systems(Update, read_rustyline);
+    app.add_console_frontend(ConsoleLineReceiver {
+        rx: Mutex::new(rx),
+        signal: None,
+        prompting,
+        secret,
+        prompt,
+        completion,
+    })
+    .add_event::<ConsoleInterrupted>()
+    .add_event::<ConsoleEof>()
+    .add_systems(
+        Update,
+        (
+            update_terminal_prompt.after(update_prompt),
+            update_terminal_completion,
+            handle_terminal_signal.after(read_frontend::<ConsoleLineReceiver>),
+        ),
+    );
+}
+
+/// Shares the prompt rendered by `update_prompt` with the reader thread
+fn update_terminal_prompt(clr: Res<ConsoleLineReceiver>, state: Res<ConsoleState>) {
+    if !state.is_changed() {
+        return;
+    }
+    clr.prompting
+        .store(state.input_prompt.is_some(), Ordering::Relaxed);
+    clr.secret.store(
+        state
+            .input_prompt
+            .as_ref()
+            .is_some_and(|pending| pending.prompt.secret),
+        Ordering::Relaxed,
+    );
+    if let Ok(mut prompt) = clr.prompt.lock() {
+        if *prompt != state.prompt {
+            prompt.clone_from(&state.prompt);
+        }
+    }
+}
+
+/// Shares the commands registered so far with the reader thread
+fn update_terminal_completion(clr: Res<ConsoleLineReceiver>, config: Res<ConsoleConfiguration>) {
+    if !config.is_changed() {
+        return;
+    }
+    if let Ok(mut completion) = clr.completion.lock() {
+        completion.commands.clone_from(&config.commands);
+        completion.root.clone_from(&config.completion_root);
+    }
+}
+
+/// Reads lines from a stdin that isn't a terminal, without any editing or history
+fn read_plain_stdin(tx: &Sender<Result<String>>) {
+    let mut stdin = io::stdin().lock();
+    loop {
+        let mut line = String::new();
+        let input = match stdin.read_line(&mut line) {
+            Ok(0) => Err(ReadlineError::Eof),
+            Ok(_) => Ok(line.trim_end_matches(['\r', '\n']).to_owned()),
+            Err(e) => Err(ReadlineError::Io(e)),
+        };
+        let done = input.is_err();
+        if tx.send(input).is_err() || done {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::event::ManualEventReader;
+
+    use super::*;
+    use crate::ConsoleInputSubmitted;
+
+    #[test]
+    fn test_mask_secret_answers() {
+        let completer = ArgumentCompleter {
+            source: Arc::default(),
+            secret: Arc::default(),
+        };
+        assert_eq!(completer.highlight("hunter2", 7), "hunter2");
+        assert!(!completer.highlight_char("hunter2", 7, false));
+
+        completer.secret.store(true, Ordering::Relaxed);
+        assert_eq!(completer.highlight("hunter2", 7), "*******");
+        assert!(completer.highlight_char("hunter2", 7, false));
+    }
+
+    #[test]
+    fn test_terminal_signals() {
+        let (tx, rx) = mpsc::channel();
+        let mut app = App::new();
+        app.add_event::<ConsoleInputSubmitted>()
+            .add_event::<PrintConsoleLine>()
+            .add_event::<ConsoleInterrupted>()
+            .add_event::<ConsoleEof>()
+            .add_event::<AppExit>()
+            .insert_resource(ConsoleConfiguration {
+                eof_action: EofAction::CloseConsole,
+                ..default()
+            })
+            .insert_resource(ConsoleOpen { open: true })
+            .add_console_frontend(ConsoleLineReceiver {
+                rx: Mutex::new(rx),
+                signal: None,
+                prompting: Arc::default(),
+                secret: Arc::default(),
+                prompt: Arc::default(),
+                completion: Arc::default(),
+            })
+            .add_systems(
+                Update,
+                handle_terminal_signal.after(read_frontend::<ConsoleLineReceiver>),
+            );
+        tx.send(Ok("help".to_owned())).unwrap();
+        tx.send(Err(ReadlineError::Interrupted)).unwrap();
+        tx.send(Ok("fps".to_owned())).unwrap();
+        tx.send(Err(ReadlineError::Eof)).unwrap();
+
+        let mut reader = ManualEventReader::<ConsoleInputSubmitted>::default();
+        let mut submitted_lines = |app: &App| {
+            let events = app.world().resource::<Events<ConsoleInputSubmitted>>();
+            reader
+                .read(events)
+                .map(|input| input.line.clone())
+                .collect::<Vec<_>>()
+        };
+        app.update();
+        assert_eq!(submitted_lines(&app), ["help"]);
+        let interrupts = app.world().resource::<Events<ConsoleInterrupted>>();
+        assert_eq!(interrupts.len(), 1);
+        assert!(app.world().resource::<ConsoleOpen>().open);
+
+        app.update();
+        assert_eq!(submitted_lines(&app), ["fps"]);
+        assert!(!app.world().resource::<ConsoleOpen>().open);
+    }
 }
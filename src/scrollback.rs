@@ -0,0 +1,314 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use bevy_egui::egui::{
+    self,
+    text::{LayoutJob, TextWrapping},
+    Align, FontId, ScrollArea,
+};
+
+use crate::console::{style_ansi_text, submit_line, ConsoleState, ScrollTarget};
+use crate::{
+    strip_ansi, ConsoleConfiguration, ConsoleLocale, ConsoleSeverity, PrintConsoleLine, WrapMode,
+};
+
+/// Width reserved at the right of wrapped rows for the continuation indicator
+const CONTINUATION_WIDTH: f32 = 14.0;
+
+/// Shows a single scrollback line according to [`ConsoleConfiguration::wrap_mode`],
+/// opening its hyperlinks when clicked.
+///
+/// Returns whether the line was clicked outside of a hyperlink, and the response of its label.
+fn scrollback_line(
+    ui: &mut egui::Ui,
+    (mut job, links): (LayoutJob, Vec<(u32, String)>),
+    clickable: bool,
+    config: &ConsoleConfiguration,
+    font: &FontId,
+) -> (bool, egui::Response) {
+    let available_width = ui.available_width();
+    match config.wrap_mode {
+        WrapMode::Truncate => job.wrap = TextWrapping::truncate_at_width(available_width),
+        WrapMode::HorizontalScroll => job.wrap.max_width = f32::INFINITY,
+        WrapMode::Wrap if !config.continuation_indicator => job.wrap.max_width = available_width,
+        WrapMode::Wrap => job.wrap.max_width = (available_width - CONTINUATION_WIDTH).max(0.0),
+    }
+    // every line is clicked with the secondary button to open its context menu
+    let galley = ui.fonts(|fonts| fonts.layout_job(job));
+    let response = ui.add(egui::Label::new(galley.clone()).sense(egui::Sense::click()));
+
+    if config.wrap_mode == WrapMode::Wrap && config.continuation_indicator {
+        let color = config.theme.foreground_color.into();
+        let font = FontId::new(font.size * 0.85, font.family.clone());
+        let last = galley.rows.len().saturating_sub(1);
+        for row in galley.rows.iter().take(last) {
+            if row.ends_with_newline {
+                continue;
+            }
+            let y = response.rect.min.y + row.rect.center().y;
+            if config.right_to_left {
+                let pos = egui::pos2(response.rect.min.x, y);
+                ui.painter()
+                    .text(pos, egui::Align2::RIGHT_CENTER, "↪", font.clone(), color);
+            } else {
+                let pos = egui::pos2(response.rect.min.x + galley.rect.right(), y);
+                ui.painter()
+                    .text(pos, egui::Align2::LEFT_CENTER, "↩", font.clone(), color);
+            }
+        }
+    }
+
+    let hovered_link = response.hover_pos().and_then(|pos| {
+        let section = hovered_section(&galley, (pos - response.rect.min).to_pos2())?;
+        links
+            .iter()
+            .find(|(link_section, _)| *link_section == section)
+            .map(|(_, uri)| uri)
+    });
+    let clicked = response.clicked();
+    match hovered_link {
+        Some(uri) => {
+            if clicked {
+                ui.ctx().open_url(egui::OpenUrl::new_tab(uri));
+            }
+            let response = response
+                .on_hover_cursor(egui::CursorIcon::PointingHand)
+                .on_hover_text_at_pointer(uri);
+            (false, response)
+        }
+        None if clickable => (
+            clicked,
+            response.on_hover_cursor(egui::CursorIcon::PointingHand),
+        ),
+        None => (false, response),
+    }
+}
+
+/// Entry picked from the context menu of a scrollback line, which isn't handled by the menu
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum LineMenuAction {
+    CopyAll,
+    Clear,
+    Save,
+    /// Follows the channel of the line in a tab
+    Filter(String),
+}
+
+/// Shows the entries of the context menu of a scrollback line
+fn line_menu(
+    ui: &mut egui::Ui,
+    line: &PrintConsoleLine,
+    locale: &ConsoleLocale,
+) -> Option<LineMenuAction> {
+    let mut action = None;
+    if ui.button(locale.get("menu-copy-line")).clicked() {
+        ui.ctx().copy_text(strip_ansi(&line.line));
+        ui.close_menu();
+    }
+    if ui.button(locale.get("menu-copy-all")).clicked() {
+        action = Some(LineMenuAction::CopyAll);
+    }
+    if ui.button(locale.get("menu-clear")).clicked() {
+        action = Some(LineMenuAction::Clear);
+    }
+    // there's no file system to save to on the web
+    #[cfg(not(target_arch = "wasm32"))]
+    if ui.button(locale.get("menu-save")).clicked() {
+        action = Some(LineMenuAction::Save);
+    }
+    if let Some(channel) = &line.channel {
+        let label = locale.format("menu-filter", &[("channel", channel)]);
+        if ui.button(label).clicked() {
+            action = Some(LineMenuAction::Filter(channel.clone()));
+        }
+    }
+    if action.is_some() {
+        ui.close_menu();
+    }
+    action
+}
+
+/// Saves the text to a new file of the directory, named after the current time
+#[cfg(not(target_arch = "wasm32"))]
+fn save_scrollback(text: &str, dir: &std::path::Path) -> std::io::Result<PathBuf> {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let path = dir.join(format!("console-{secs}.txt"));
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// Index of the layout job section of the glyph at the position, relative to the galley
+fn hovered_section(galley: &egui::Galley, pos: egui::Pos2) -> Option<u32> {
+    let row = galley
+        .rows
+        .iter()
+        .find(|row| row.rect.y_range().contains(pos.y))?;
+    row.glyphs
+        .iter()
+        .find(|glyph| (glyph.pos.x..glyph.max_x()).contains(&pos.x))
+        .map(|glyph| glyph.section_index)
+}
+
+/// Shows the scrollback of the visible tab, or the open manual page, scrolled by the keys or
+/// [`ConsoleScroll`](crate::ConsoleScroll) events, and runs the actions picked from its lines.
+///
+/// `opened` scrolls to the bottom when the console was just opened.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn scrollback_ui(
+    ui: &mut egui::Ui,
+    state: &mut ConsoleState,
+    config: &ConsoleConfiguration,
+    locale: &ConsoleLocale,
+    font: &FontId,
+    align: Align,
+    scroll: Option<ScrollTarget>,
+    focused: bool,
+    opened: bool,
+) {
+    let active_tab = config.tabs.get(state.active_tab);
+
+    let mut scroll_height = ui.available_height() - 30.0;
+    if !state.status.is_empty() {
+        scroll_height -= font.size + 2.0 * ui.spacing().item_spacing.y;
+    }
+
+    // Scroll area
+    let row_height = font.size + config.theme.line_spacing;
+    let page = (scroll_height / row_height).floor().max(1.0) as i32;
+    let scroll = scroll.or_else(|| {
+        // Home and End move the cursor of the input unless it is empty
+        let home_end = state.buf.is_empty();
+        ui.input(|input| {
+            let ctrl = input.modifiers.command;
+            if input.key_pressed(egui::Key::PageUp) {
+                Some(ScrollTarget::Lines(-page))
+            } else if input.key_pressed(egui::Key::PageDown) {
+                Some(ScrollTarget::Lines(page))
+            } else if input.key_pressed(egui::Key::Home) && (ctrl || home_end) {
+                Some(ScrollTarget::Top)
+            } else if input.key_pressed(egui::Key::End) && (ctrl || home_end) {
+                Some(ScrollTarget::Bottom)
+            } else {
+                None
+            }
+        })
+        .filter(|_| focused)
+    });
+    let mut scroll_area = match config.wrap_mode {
+        WrapMode::HorizontalScroll => ScrollArea::both(),
+        WrapMode::Wrap | WrapMode::Truncate => ScrollArea::vertical(),
+    };
+    if scroll == Some(ScrollTarget::Top) {
+        scroll_area = scroll_area.vertical_scroll_offset(0.0);
+    }
+    // sticking to the bottom would undo scrolling up
+    let scrolls_up = match scroll {
+        Some(ScrollTarget::Top) => true,
+        Some(ScrollTarget::Lines(n)) => n < 0,
+        _ => false,
+    };
+    let mut clicked_action = None;
+    let mut menu_action = None;
+    let visible = |line: &&PrintConsoleLine| {
+        line.channel
+            .as_ref()
+            .map_or(true, |channel| config.is_channel_enabled(channel))
+            && active_tab.map_or(true, |tab| tab.matches(line))
+    };
+    scroll_area
+        .auto_shrink([false, false])
+        .stick_to_bottom(!scrolls_up && state.man_page.is_none())
+        .max_height(scroll_height)
+        .id_source((
+            state.active_tab,
+            state.man_page.as_ref().map(|page| &page.command),
+        ))
+        .show(ui, |ui| {
+            ui.with_layout(egui::Layout::top_down(align), |ui| {
+                ui.spacing_mut().item_spacing.y = config.theme.line_spacing;
+                if let Some(page) = &state.man_page {
+                    for line in &page.lines {
+                        let job = style_ansi_text(line, config, font);
+                        scrollback_line(ui, job, false, config, font);
+                    }
+                    return;
+                }
+                for line in state.scrollback.iter().filter(visible) {
+                    let (clicked, response) = scrollback_line(
+                        ui,
+                        style_ansi_text(line, config, font),
+                        line.action.is_some(),
+                        config,
+                        font,
+                    );
+                    if clicked {
+                        clicked_action.clone_from(&line.action);
+                    }
+                    response.context_menu(|ui| {
+                        if let Some(action) = line_menu(ui, line, locale) {
+                            menu_action = Some(action);
+                        }
+                    });
+                }
+            });
+
+            // Scroll to bottom if console just opened
+            if opened || scroll == Some(ScrollTarget::Bottom) {
+                ui.scroll_to_cursor(Some(Align::BOTTOM));
+            }
+            if let Some(ScrollTarget::Lines(lines)) = scroll {
+                ui.scroll_with_delta(egui::vec2(0.0, -(lines as f32) * row_height));
+            }
+        });
+    if let Some(action) = clicked_action {
+        submit_line(&action, state);
+    }
+    let visible_text = || {
+        state
+            .scrollback
+            .iter()
+            .filter(visible)
+            .map(|line| strip_ansi(&line.line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    match menu_action {
+        Some(LineMenuAction::CopyAll) => ui.ctx().copy_text(visible_text()),
+        Some(LineMenuAction::Clear) => state.scrollback.clear(),
+        #[cfg(not(target_arch = "wasm32"))]
+        Some(LineMenuAction::Save) => {
+            let line = match save_scrollback(&visible_text(), &config.save_dir) {
+                Ok(path) => PrintConsoleLine::new(
+                    locale.format("scrollback-saved", &[("path", &path.display().to_string())]),
+                ),
+                Err(err) => {
+                    PrintConsoleLine::new(err.to_string()).with_severity(ConsoleSeverity::Error)
+                }
+            };
+            state.push_local(line);
+        }
+        Some(LineMenuAction::Filter(channel)) => {
+            let channel = shlex::try_quote(&channel).unwrap_or_default();
+            submit_line(&format!("tail {channel}"), state);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_save_scrollback() {
+        let dir = std::env::temp_dir();
+        let path = save_scrollback("spawn 3\n[ok]", &dir).unwrap();
+        assert_eq!(path.parent(), Some(dir.as_path()));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "spawn 3\n[ok]");
+        std::fs::remove_file(path).unwrap();
+    }
+}
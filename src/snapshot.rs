@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::console::ConsoleState;
+#[cfg(feature = "ui")]
+use crate::dock::DedicatedConsoleWindow;
+use crate::{ConsoleSeverity, PrintConsoleLine};
+
+/// A console session, saved to disk and restored to carry it over app restarts or attach it to
+/// bug reports.
+///
+/// Holds the scrollback, the command history, the watched expressions and the pinned lines.
+/// The session file also holds where the window of
+/// [`ConsolePlugin::with_dedicated_window`](crate::ConsolePlugin::with_dedicated_window) was.
+/// Saved as JSON by [`ConsoleSnapshot::save`], the `snapshot` command and
+/// [`ConsolePlugin::with_session_file`](crate::ConsolePlugin::with_session_file).
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_console::ConsoleSnapshot;
+/// fn save_session(world: &World) {
+///     if let Some(snapshot) = ConsoleSnapshot::capture(world) {
+///         snapshot.save("console_session.json").unwrap();
+///     }
+/// }
+///
+/// fn restore_session(world: &mut World) {
+///     if let Ok(snapshot) = ConsoleSnapshot::load("console_session.json") {
+///         snapshot.restore(world);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsoleSnapshot {
+    /// Lines of the scrollback, oldest first
+    pub scrollback: Vec<SnapshotLine>,
+    /// Entered commands, most recent first
+    pub history: Vec<String>,
+    /// Watched expressions, see the `watch` command
+    pub watches: Vec<String>,
+    /// Lines pinned to the header, by key
+    pub pinned: BTreeMap<String, SnapshotLine>,
+    /// Placement of the dedicated console window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowPlacement>,
+}
+
+/// Position and size of the dedicated console window in a [`ConsoleSnapshot`]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowPlacement {
+    /// Physical position on the desktop spanning all monitors, which selects the monitor the
+    /// window opens on
+    pub position: [i32; 2],
+    /// Logical width and height
+    pub size: [f32; 2],
+}
+
+/// A scrollback line of a [`ConsoleSnapshot`], without its per frame routing
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotLine {
+    /// Text of the line, including its ANSI escape codes
+    pub line: String,
+    /// Severity of the line
+    #[serde(default)]
+    pub severity: ConsoleSeverity,
+    /// Whether the line echoes a command entered into the console
+    #[serde(default)]
+    pub is_command: bool,
+    /// Channel the line was printed to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Command entered when the line is clicked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+}
+
+impl From<&PrintConsoleLine> for SnapshotLine {
+    fn from(line: &PrintConsoleLine) -> Self {
+        Self {
+            line: line.line.to_string(),
+            severity: line.severity,
+            is_command: line.is_command,
+            channel: line.channel.clone(),
+            action: line.action.clone(),
+        }
+    }
+}
+
+impl From<SnapshotLine> for PrintConsoleLine {
+    fn from(line: SnapshotLine) -> Self {
+        Self {
+            is_command: line.is_command,
+            channel: line.channel,
+            action: line.action,
+            ..PrintConsoleLine::new(line.line).with_severity(line.severity)
+        }
+    }
+}
+
+impl ConsoleSnapshot {
+    /// Captures the session of the console, `None` without a [`ConsolePlugin`](crate::ConsolePlugin).
+    pub fn capture(world: &World) -> Option<Self> {
+        world.get_resource::<ConsoleState>().map(Self::of)
+    }
+
+    /// Replaces the session of the console with the snapshot.
+    pub fn restore(self, world: &mut World) {
+        if let Some(mut state) = world.get_resource_mut::<ConsoleState>() {
+            self.restore_into(&mut state);
+        }
+    }
+
+    /// Saves the snapshot as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Loads a snapshot saved with [`ConsoleSnapshot::save`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub(crate) fn of(state: &ConsoleState) -> Self {
+        Self {
+            scrollback: state.scrollback.iter().map(SnapshotLine::from).collect(),
+            // the first history entry is the input being edited
+            history: state.history.iter().skip(1).cloned().collect(),
+            watches: state.watches.keys().cloned().collect(),
+            pinned: state
+                .pinned
+                .iter()
+                .map(|(key, line)| (key.clone(), line.into()))
+                .collect(),
+            window: None,
+        }
+    }
+
+    pub(crate) fn restore_into(self, state: &mut ConsoleState) {
+        state.scrollback = self.scrollback.into_iter().map(Into::into).collect();
+        state.history.truncate(1);
+        state.history.extend(self.history);
+        state.history_index = 0;
+        // evaluated again by the next watch update
+        state.watches = self
+            .watches
+            .into_iter()
+            .map(|expr| (expr, "...".to_owned()))
+            .collect();
+        state.pinned = self
+            .pinned
+            .into_iter()
+            .map(|(key, line)| {
+                let line = PrintConsoleLine::from(line).with_pin(key.clone());
+                (key, line)
+            })
+            .collect();
+        state.active_tab = 0;
+    }
+}
+
+/// Session file set with [`ConsolePlugin::with_session_file`](crate::ConsolePlugin::with_session_file)
+#[derive(Resource)]
+pub(crate) struct SessionFile(pub(crate) PathBuf);
+
+/// Placement of the dedicated console window, restored from and saved to the session file
+#[derive(Default, Resource)]
+pub(crate) struct DedicatedWindowPlacement(pub(crate) Option<WindowPlacement>);
+
+/// Restores the session saved by the previous run, if there is one
+pub(crate) fn restore_session(
+    file: Res<SessionFile>,
+    mut state: ResMut<ConsoleState>,
+    placement: Option<ResMut<DedicatedWindowPlacement>>,
+) {
+    match ConsoleSnapshot::load(&file.0) {
+        Ok(snapshot) => {
+            if let Some(mut placement) = placement {
+                placement.0 = snapshot.window;
+            }
+            snapshot.restore_into(&mut state);
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => warn!("Could not restore console session {:?}: {err}", file.0),
+    }
+}
+
+/// Saves the session when the app exits
+pub(crate) fn save_session(
+    mut exit: EventReader<AppExit>,
+    file: Res<SessionFile>,
+    state: Res<ConsoleState>,
+    placement: Option<Res<DedicatedWindowPlacement>>,
+) {
+    if exit.read().last().is_none() {
+        return;
+    }
+    let snapshot = ConsoleSnapshot {
+        window: placement.and_then(|placement| placement.0),
+        ..ConsoleSnapshot::of(&state)
+    };
+    if let Err(err) = snapshot.save(&file.0) {
+        warn!("Could not save console session {:?}: {err}", file.0);
+    }
+}
+
+/// Moves the dedicated console window where it was when the app last exited, before it is
+/// created
+#[cfg(feature = "ui")]
+pub(crate) fn restore_window_placement(
+    placement: Res<DedicatedWindowPlacement>,
+    mut windows: Query<&mut Window, With<DedicatedConsoleWindow>>,
+) {
+    let Some(WindowPlacement { position, size }) = placement.0 else {
+        return;
+    };
+    for mut window in &mut windows {
+        window.position = WindowPosition::At(position.into());
+        window.resolution.set(size[0], size[1]);
+    }
+}
+
+/// Keeps track of where the dedicated console window is moved or resized to
+#[cfg(feature = "ui")]
+pub(crate) fn track_window_placement(
+    windows: Query<&Window, (With<DedicatedConsoleWindow>, Changed<Window>)>,
+    mut placement: ResMut<DedicatedWindowPlacement>,
+) {
+    for window in &windows {
+        if let WindowPosition::At(position) = window.position {
+            placement.0 = Some(WindowPlacement {
+                position: position.into(),
+                size: [window.width(), window.height()],
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut state = ConsoleState {
+            scrollback: vec![
+                PrintConsoleLine::new("spawn 3").with_action("help spawn"),
+                PrintConsoleLine::channel("net", "\x1b[31mtimed out\x1b[0m")
+                    .with_severity(ConsoleSeverity::Error),
+            ],
+            ..default()
+        };
+        state.scrollback[0].is_command = true;
+        state
+            .history
+            .extend(["spawn 3".to_owned(), "help".to_owned()]);
+        state
+            .watches
+            .insert("Time.elapsed".to_owned(), "1.5s".to_owned());
+        state.pinned.insert(
+            "fps".to_owned(),
+            PrintConsoleLine::new("60 fps").with_pin("fps"),
+        );
+
+        let path = std::env::temp_dir().join("bevy_console_snapshot_round_trip.json");
+        ConsoleSnapshot::of(&state).save(&path).unwrap();
+        let snapshot = ConsoleSnapshot::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(snapshot, ConsoleSnapshot::of(&state));
+
+        let mut restored = ConsoleState {
+            buf: "sp".to_owned(),
+            ..default()
+        };
+        snapshot.restore_into(&mut restored);
+        assert_eq!(restored.scrollback, state.scrollback);
+        assert_eq!(restored.history, state.history);
+        assert_eq!(restored.watches["Time.elapsed"], "...");
+        assert_eq!(restored.pinned, state.pinned);
+        // the input being edited is kept
+        assert_eq!(restored.buf, "sp");
+    }
+
+    #[cfg(feature = "ui")]
+    #[test]
+    fn test_window_placement() {
+        let mut app = App::new();
+        app.insert_resource(DedicatedWindowPlacement(Some(WindowPlacement {
+            position: [1920, 40],
+            size: [800.0, 600.0],
+        })))
+        .add_systems(PostStartup, restore_window_placement)
+        .add_systems(Update, track_window_placement);
+        let window = app
+            .world_mut()
+            .spawn((Window::default(), DedicatedConsoleWindow))
+            .id();
+        app.update();
+
+        let placed = app.world().get::<Window>(window).unwrap();
+        assert_eq!(placed.position, WindowPosition::At(IVec2::new(1920, 40)));
+        assert_eq!((placed.width(), placed.height()), (800.0, 600.0));
+
+        app.world_mut().get_mut::<Window>(window).unwrap().position =
+            WindowPosition::At(IVec2::new(0, 0));
+        app.update();
+        assert_eq!(
+            app.world().resource::<DedicatedWindowPlacement>().0,
+            Some(WindowPlacement {
+                position: [0, 0],
+                size: [800.0, 600.0],
+            })
+        );
+    }
+}
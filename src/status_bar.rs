@@ -0,0 +1,250 @@
+use bevy::prelude::*;
+
+use crate::console::ConsoleState;
+use crate::prompt::PromptFn;
+use crate::{ConsoleConfiguration, ConsoleOpen};
+
+/// Segments of the status bar shown at the bottom of the console window with
+/// [`ConsoleConfiguration::status_bar`].
+///
+/// Every segment is a function rendering its text each frame while the console is open,
+/// segments rendering an empty string are hidden. The built-in ones are:
+/// - `lines`: number of scrollback lines
+/// - `filter`: active tab and disabled channels
+/// - `remote`: number of connected remote console clients, with the `remote` feature
+/// - `permission`: commands the console window may run, with the `remote` feature. Apps with
+///   their own permission levels replace it, as in the example below
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_console::ConsoleStatusBar;
+/// # #[derive(Resource)]
+/// # struct Permission(&'static str);
+/// let mut status_bar = ConsoleStatusBar::default();
+/// status_bar
+///     .without_segment("lines")
+///     .segment("permission", |world| {
+///         world
+///             .get_resource::<Permission>()
+///             .map(|permission| format!("permission: {}", permission.0))
+///             .unwrap_or_default()
+///     });
+/// ```
+#[derive(Resource)]
+pub struct ConsoleStatusBar {
+    segments: Vec<(String, PromptFn)>,
+}
+
+impl Default for ConsoleStatusBar {
+    fn default() -> Self {
+        let mut status_bar = Self {
+            segments: Vec::new(),
+        };
+        status_bar
+            .segment("lines", |world| {
+                let lines = world.resource::<ConsoleState>().scrollback.len();
+                format!("{lines} lines")
+            })
+            .segment("filter", |world| {
+                let config = world.resource::<ConsoleConfiguration>();
+                let state = world.resource::<ConsoleState>();
+                let mut filters = Vec::new();
+                if let Some(tab) = config.tabs.get(state.active_tab) {
+                    filters.push(format!("tab: {}", tab.name));
+                }
+                let disabled = config
+                    .channels
+                    .iter()
+                    .filter(|(_, channel)| !channel.enabled)
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>();
+                if !disabled.is_empty() {
+                    filters.push(format!("hidden: {}", disabled.join(", ")));
+                }
+                filters.join(", ")
+            });
+        #[cfg(feature = "remote")]
+        status_bar.segment("remote", |world| {
+            world
+                .get_resource::<crate::remote::RemoteClients>()
+                .map(|remote| match remote.client_count() {
+                    1 => "1 remote client".to_owned(),
+                    count => format!("{count} remote clients"),
+                })
+                .unwrap_or_default()
+        });
+        #[cfg(feature = "remote")]
+        status_bar.segment("permission", |world| {
+            use crate::{CommandSource, RemotePermission};
+
+            let Some(middlewares) = world.get_resource::<crate::middleware::ConsoleMiddlewares>()
+            else {
+                return String::new();
+            };
+            match middlewares.2.get(&CommandSource::LocalUi) {
+                None | Some(RemotePermission::Full) => "permission: full".to_owned(),
+                Some(RemotePermission::ReadOnly) => "permission: read-only".to_owned(),
+                Some(RemotePermission::Commands(commands)) => format!(
+                    "permission: {}",
+                    commands.iter().cloned().collect::<Vec<_>>().join(", ")
+                ),
+            }
+        });
+        status_bar
+    }
+}
+
+impl ConsoleStatusBar {
+    /// Adds a segment after the existing ones, or replaces the segment with the same name in place.
+    pub fn segment(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Fn(&World) -> String + Send + Sync + 'static,
+    ) -> &mut Self {
+        let name = name.into();
+        let value: PromptFn = Box::new(value);
+        match self
+            .segments
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            Some((_, existing)) => *existing = value,
+            None => self.segments.push((name, value)),
+        }
+        self
+    }
+
+    /// Removes the segment with the given name.
+    pub fn without_segment(&mut self, name: &str) -> &mut Self {
+        self.segments.retain(|(existing, _)| existing != name);
+        self
+    }
+}
+
+/// Whether the status bar is shown, or still has to be cleared after it was hidden
+pub(crate) fn status_bar_shown(
+    config: Res<ConsoleConfiguration>,
+    console_open: Res<ConsoleOpen>,
+    state: Res<ConsoleState>,
+) -> bool {
+    config.status_bar && console_open.open || !state.status.is_empty()
+}
+
+/// Renders the status bar segments into [`ConsoleState`] while the console is open
+pub(crate) fn update_status_bar(world: &mut World) {
+    let shown =
+        world.resource::<ConsoleConfiguration>().status_bar && world.resource::<ConsoleOpen>().open;
+    if !shown {
+        if !world.resource::<ConsoleState>().status.is_empty() {
+            world.resource_mut::<ConsoleState>().status.clear();
+        }
+        return;
+    }
+
+    world.resource_scope(|world, status_bar: Mut<ConsoleStatusBar>| {
+        let rendered = status_bar
+            .segments
+            .iter()
+            .map(|(_, value)| value(world))
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut state = world.resource_mut::<ConsoleState>();
+        if state.status != rendered {
+            state.status = rendered;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConsoleChannel, PrintConsoleLine};
+
+    #[test]
+    fn test_update_status_bar() {
+        let mut app = App::new();
+        app.init_resource::<ConsoleState>()
+            .init_resource::<ConsoleStatusBar>()
+            .insert_resource(ConsoleOpen { open: true })
+            .insert_resource(ConsoleConfiguration {
+                status_bar: true,
+                ..default()
+            })
+            .add_systems(Update, update_status_bar.run_if(status_bar_shown));
+        app.world_mut()
+            .resource_mut::<ConsoleStatusBar>()
+            .segment("custom", |_| "custom".to_owned())
+            .segment("lines", |world| {
+                let lines = world.resource::<ConsoleState>().scrollback.len();
+                format!("{lines} rows")
+            });
+        app.world_mut()
+            .resource_mut::<ConsoleState>()
+            .scrollback
+            .push(PrintConsoleLine::new("line".to_owned()));
+        app.update();
+        assert_eq!(
+            app.world().resource::<ConsoleState>().status,
+            ["1 rows", "custom"]
+        );
+
+        app.world_mut()
+            .resource_mut::<ConsoleConfiguration>()
+            .channels
+            .insert(
+                "net".to_owned(),
+                ConsoleChannel {
+                    enabled: false,
+                    ..default()
+                },
+            );
+        app.update();
+        assert_eq!(
+            app.world().resource::<ConsoleState>().status,
+            ["1 rows", "hidden: net", "custom"]
+        );
+
+        app.world_mut().resource_mut::<ConsoleOpen>().open = false;
+        app.update();
+        assert!(app.world().resource::<ConsoleState>().status.is_empty());
+    }
+
+    #[cfg(feature = "remote")]
+    #[test]
+    fn test_permission_segment() {
+        use crate::middleware::ConsoleMiddlewares;
+        use crate::{CommandSource, RemotePermission};
+
+        let mut app = App::new();
+        app.init_resource::<ConsoleState>()
+            .init_resource::<ConsoleMiddlewares>()
+            .insert_resource(ConsoleOpen { open: true })
+            .insert_resource(ConsoleConfiguration {
+                status_bar: true,
+                ..default()
+            })
+            .add_systems(Update, update_status_bar);
+        let mut status_bar = ConsoleStatusBar::default();
+        status_bar
+            .without_segment("lines")
+            .without_segment("filter");
+        app.insert_resource(status_bar);
+        app.update();
+        assert_eq!(
+            app.world().resource::<ConsoleState>().status,
+            ["permission: full"]
+        );
+
+        app.world_mut()
+            .resource_mut::<ConsoleMiddlewares>()
+            .grant(CommandSource::LocalUi, RemotePermission::ReadOnly);
+        app.update();
+        assert_eq!(
+            app.world().resource::<ConsoleState>().status,
+            ["permission: read-only"]
+        );
+    }
+}
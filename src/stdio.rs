@@ -0,0 +1,249 @@
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+
+use crate::throttle::throttle_lines;
+use crate::PrintConsoleLine;
+
+/// Lines read from the captured standard streams, with the channel they are printed to
+#[derive(Resource)]
+pub(crate) struct CapturedStdio {
+    rx: Mutex<Receiver<(&'static str, String)>>,
+}
+
+/// Redirects stdout and stderr into pipes, mirroring every line to the original stream and the console.
+///
+/// Stdout is left alone with a terminal frontend, which prints the console to it: capturing it
+/// would feed every printed line back into the console.
+pub(crate) fn setup_stdio_capture(app: &mut App) {
+    let (tx, rx) = mpsc::channel();
+    let capture_stdout = !cfg!(all(
+        any(feature = "rustyline", feature = "tui"),
+        not(feature = "release_disabled")
+    ));
+
+    #[cfg(unix)]
+    for (fd, channel) in [
+        (libc::STDOUT_FILENO, "stdout"),
+        (libc::STDERR_FILENO, "stderr"),
+    ] {
+        if fd == libc::STDOUT_FILENO && !capture_stdout {
+            info!("Not capturing stdout, the terminal frontend of the console prints to it");
+            continue;
+        }
+        if let Err(err) = unix::capture(fd, channel, tx.clone()) {
+            warn!("Failed to capture {channel}: {err}");
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = capture_stdout;
+        drop(tx);
+        warn!("Capturing stdout and stderr is not supported on this platform");
+    }
+
+    app.insert_resource(CapturedStdio { rx: Mutex::new(rx) })
+        .add_systems(Update, print_captured_stdio.before(throttle_lines));
+}
+
+fn print_captured_stdio(
+    captured: Res<CapturedStdio>,
+    mut console_line: EventWriter<PrintConsoleLine>,
+) {
+    if let Ok(rx) = captured.rx.lock() {
+        // stderr is where logs and other diagnostics go, not only warnings
+        for (channel, line) in rx.try_iter() {
+            console_line.send(PrintConsoleLine::channel(channel, line));
+        }
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::fs::File;
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::os::fd::{AsRawFd, FromRawFd, RawFd};
+    use std::sync::mpsc::Sender;
+    use std::thread;
+
+    pub(super) fn capture(
+        fd: RawFd,
+        channel: &'static str,
+        tx: Sender<(&'static str, String)>,
+    ) -> io::Result<()> {
+        let mut pipe = [0; 2];
+        // SAFETY: `pipe` points to two writable file descriptors
+        if unsafe { libc::pipe(pipe.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let [read_end, write_end] = pipe;
+
+        // SAFETY: `fd` is a standard stream, the duplicate is owned by `original` below
+        let original = unsafe { libc::dup(fd) };
+        // SAFETY: both descriptors are valid, `fd` now refers to the write end of the pipe
+        if original < 0 || unsafe { libc::dup2(write_end, fd) } < 0 {
+            let err = io::Error::last_os_error();
+            // SAFETY: the descriptors were created above and aren't used anywhere else
+            unsafe {
+                libc::close(read_end);
+                libc::close(write_end);
+                if original >= 0 {
+                    libc::close(original);
+                }
+            }
+            return Err(err);
+        }
+        // SAFETY: `write_end` was duplicated onto `fd` and isn't used anymore
+        unsafe { libc::close(write_end) };
+
+        // SAFETY: both descriptors are owned exclusively by the files from here on
+        let (reader, mut original) =
+            unsafe { (File::from_raw_fd(read_end), File::from_raw_fd(original)) };
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            let mut splitter = LineSplitter::default();
+            let mut forwarding = true;
+            loop {
+                let buf = match reader.fill_buf() {
+                    Ok([]) => break,
+                    Ok(buf) => buf,
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                };
+                let _ = original.write_all(buf);
+                let len = buf.len();
+                if forwarding {
+                    let lines = splitter.split(buf);
+                    if lines
+                        .into_iter()
+                        .any(|line| tx.send((channel, line)).is_err())
+                    {
+                        // the app is gone, e.g. after `App::run` returned, so later output
+                        // like panic messages goes to the original stream directly again.
+                        // The pipe is drained until its write end is closed by this.
+                        restore(fd, &original);
+                        forwarding = false;
+                    }
+                }
+                reader.consume(len);
+            }
+            if let Some(line) = splitter.finish().filter(|_| forwarding) {
+                let _ = tx.send((channel, line));
+            }
+            // closing the pipe would leave `fd` without a reader, failing every write to it
+            restore(fd, &original);
+        });
+        Ok(())
+    }
+
+    /// Points `fd` at the original stream again
+    fn restore(fd: RawFd, original: &File) {
+        // SAFETY: both descriptors are valid, `original` stays open as it is only duplicated
+        unsafe { libc::dup2(original.as_raw_fd(), fd) };
+    }
+
+    /// Splits a stream into lines as soon as they end with a line break or carriage return,
+    /// so progress output overwriting a line shows up before its line break.
+    ///
+    /// Lines continuing a row after a carriage return start with one, replacing the row printed
+    /// so far in the console.
+    #[derive(Default)]
+    pub(super) struct LineSplitter {
+        line: Vec<u8>,
+        /// Whether part of the current row was split off at a carriage return
+        returned: bool,
+    }
+
+    impl LineSplitter {
+        /// Returns the lines completed by the bytes
+        pub(super) fn split(&mut self, bytes: &[u8]) -> Vec<String> {
+            let mut lines = Vec::new();
+            for &byte in bytes {
+                match byte {
+                    b'\n' => {
+                        // the line break of a CRLF ends a row already split off
+                        if !self.line.is_empty() || !self.returned {
+                            lines.push(self.take_line());
+                        }
+                        self.returned = false;
+                    }
+                    b'\r' => {
+                        if !self.line.is_empty() {
+                            lines.push(self.take_line());
+                            self.returned = true;
+                        }
+                    }
+                    byte => self.line.push(byte),
+                }
+            }
+            lines
+        }
+
+        /// Returns the unterminated line left at the end of the stream
+        pub(super) fn finish(&mut self) -> Option<String> {
+            (!self.line.is_empty()).then(|| self.take_line())
+        }
+
+        fn take_line(&mut self) -> String {
+            let prefix = if self.returned { "\r" } else { "" };
+            let line = String::from_utf8_lossy(&self.line);
+            let line = format!("{prefix}{line}");
+            self.line.clear();
+            line
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::unix::{capture, LineSplitter};
+
+    #[test]
+    fn test_capture_outlives_app() {
+        let path = std::env::temp_dir().join(format!("bevy_console_stdio_{}", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        let (tx, rx) = mpsc::channel();
+        capture(file.as_raw_fd(), "stdout", tx).unwrap();
+        file.write_all(b"captured\n").unwrap();
+        assert_eq!(rx.recv().unwrap(), ("stdout", "captured".to_owned()));
+
+        // writing still works once nothing receives the lines anymore
+        drop(rx);
+        file.write_all(b"dropped\n").unwrap();
+        for _ in 0..100 {
+            if fs::read_to_string(&path).unwrap() == "captured\ndropped\n" {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        file.write_all(b"after\n").unwrap();
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "captured\ndropped\nafter\n"
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_line_splitter() {
+        let mut splitter = LineSplitter::default();
+        assert_eq!(splitter.split(b"building\n\r0%"), ["building"]);
+        assert_eq!(
+            splitter.split(b"\r50%\r100%\r\n"),
+            ["0%", "\r50%", "\r100%"]
+        );
+        assert_eq!(splitter.split(b"done\r\n\n"), ["done", ""]);
+        assert_eq!(splitter.finish(), None);
+        assert_eq!(splitter.split(b"no line break"), Vec::<String>::new());
+        assert_eq!(splitter.finish(), Some("no line break".to_owned()));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::Colour;
+
+/// Text printed with a single style, see [`StyledLine`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StyledSpan {
+    /// Text of the span
+    pub text: String,
+    /// Text color, the color of the line's severity if `None`
+    pub color: Option<Colour>,
+    /// Bold text
+    pub bold: bool,
+    /// Italic text
+    pub italic: bool,
+    /// Underlined text
+    pub underline: bool,
+}
+
+impl StyledSpan {
+    /// Creates an unstyled span.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the text color.
+    pub fn color(mut self, color: Colour) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Makes the text bold.
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Makes the text italic.
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    /// Underlines the text.
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Appends the text wrapped in the ANSI escape codes of its style.
+    fn write_ansi(&self, ansi: &mut String) {
+        let mut modes = Vec::new();
+        if self.bold {
+            modes.push("1".to_owned());
+        }
+        if self.italic {
+            modes.push("3".to_owned());
+        }
+        if self.underline {
+            modes.push("4".to_owned());
+        }
+        if let Some(Colour { r, g, b, .. }) = self.color {
+            modes.push(format!("38;2;{r};{g};{b}"));
+        }
+        if modes.is_empty() {
+            ansi.push_str(&self.text);
+        } else {
+            ansi.push_str(&format!("\x1b[{}m{}\x1b[0m", modes.join(";"), self.text));
+        }
+    }
+}
+
+impl From<&str> for StyledSpan {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for StyledSpan {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+/// A line made of styled spans, printed with
+/// [`PrintConsoleLine::from_styled`](crate::PrintConsoleLine::from_styled) or
+/// [`ConsoleCommand::reply_styled`](crate::ConsoleCommand::reply_styled).
+///
+/// The console window shows the spans as they are, without formatting them as ANSI escape
+/// codes and parsing them back. Other outputs like the terminal and the log file get the
+/// line as ANSI text.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_console::{Colour, PrintConsoleLine, StyledLine, StyledSpan};
+/// let line = StyledLine::new()
+///     .span("health: ")
+///     .span(StyledSpan::new("100").color(Colour::from_rgb(57, 181, 74)).bold());
+/// assert_eq!(line.text(), "health: 100");
+/// let line = PrintConsoleLine::from_styled(line);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StyledLine {
+    /// Spans of the line, in order
+    pub spans: Vec<StyledSpan>,
+}
+
+impl StyledLine {
+    /// Creates an empty line.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a span.
+    pub fn span(mut self, span: impl Into<StyledSpan>) -> Self {
+        self.spans.push(span.into());
+        self
+    }
+
+    /// The text of the line without styling.
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    /// The text of the line with its styling as ANSI escape codes.
+    pub fn to_ansi(&self) -> String {
+        let mut ansi = String::new();
+        for span in &self.spans {
+            span.write_ansi(&mut ansi);
+        }
+        ansi
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_styled_line_to_ansi() {
+        let line = StyledLine::new()
+            .span("hp: ")
+            .span(
+                StyledSpan::new("5")
+                    .color(Colour::from_rgb(222, 56, 43))
+                    .bold(),
+            )
+            .span(StyledSpan::new("!").underline());
+        assert_eq!(line.text(), "hp: 5!");
+        assert_eq!(
+            line.to_ansi(),
+            "hp: \x1b[1;38;2;222;56;43m5\x1b[0m\x1b[4m!\x1b[0m"
+        );
+    }
+}
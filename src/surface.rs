@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use bevy_egui::egui;
+use bevy_egui::{EguiInput, EguiRenderToTextureHandle};
+
+use crate::ConsoleOpen;
+
+/// Renders the console to an image instead of a window, e.g. for a diegetic terminal on a quad
+/// in the world or a VR panel.
+///
+/// While the console is open, the keyboard input of the windows is forwarded to the surface, so
+/// commands are typed and executed as in the console window. Pointer input isn't forwarded.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_console::ConsoleSurface;
+/// fn spawn_terminal(
+///     mut commands: Commands,
+///     mut images: ResMut<Assets<Image>>,
+///     mut materials: ResMut<Assets<StandardMaterial>>,
+///     mut meshes: ResMut<Assets<Mesh>>,
+/// ) {
+///     let image = images.add(ConsoleSurface::image(512, 512));
+///     commands.spawn(ConsoleSurface::bundle(image.clone()));
+///     commands.spawn(PbrBundle {
+///         mesh: meshes.add(Plane3d::default().mesh().size(1.0, 1.0)),
+///         material: materials.add(StandardMaterial {
+///             base_color_texture: Some(image),
+///             unlit: true,
+///             ..default()
+///         }),
+///         ..default()
+///     });
+/// }
+/// ```
+#[derive(Clone, Component, Debug, Default)]
+pub struct ConsoleSurface;
+
+impl ConsoleSurface {
+    /// Creates a transparent image of the given size which the console can be rendered to
+    pub fn image(width: u32, height: u32) -> Image {
+        let size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let mut image = Image::new_fill(
+            size,
+            TextureDimension::D2,
+            &[0; 4],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+        image
+    }
+
+    /// Components of an entity rendering the console to `image`
+    pub fn bundle(image: Handle<Image>) -> (Self, EguiRenderToTextureHandle) {
+        (Self, EguiRenderToTextureHandle(image))
+    }
+}
+
+/// Whether an egui event is keyboard input typed into the console
+fn is_keyboard_event(event: &egui::Event) -> bool {
+    matches!(
+        event,
+        egui::Event::Key { .. }
+            | egui::Event::Text(_)
+            | egui::Event::Paste(_)
+            | egui::Event::Copy
+            | egui::Event::Cut
+    )
+}
+
+/// Moves the keyboard input of the windows to the console surfaces while the console is open,
+/// as egui only reads input for windows
+pub(crate) fn forward_surface_input(
+    console_open: Res<ConsoleOpen>,
+    mut windows: Query<&mut EguiInput, With<Window>>,
+    mut surfaces: Query<&mut EguiInput, (With<ConsoleSurface>, Without<Window>)>,
+) {
+    if !console_open.open || surfaces.is_empty() {
+        return;
+    }
+    let mut events = Vec::new();
+    let mut modifiers = egui::Modifiers::NONE;
+    for mut input in &mut windows {
+        let input = &mut input.0;
+        modifiers = modifiers.plus(input.modifiers);
+        events.extend(
+            input
+                .events
+                .iter()
+                .filter(|e| is_keyboard_event(e))
+                .cloned(),
+        );
+        input.events.retain(|event| !is_keyboard_event(event));
+    }
+    for mut surface in &mut surfaces {
+        surface.0.modifiers = modifiers;
+        surface.0.events.extend(events.iter().cloned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_surface_input() {
+        let mut app = App::new();
+        app.insert_resource(ConsoleOpen { open: true })
+            .add_systems(Update, forward_surface_input);
+        let pointer = egui::Event::PointerMoved(egui::pos2(1.0, 2.0));
+        let input = egui::RawInput {
+            events: vec![egui::Event::Text("fps".to_owned()), pointer.clone()],
+            ..default()
+        };
+        let window = app
+            .world_mut()
+            .spawn((Window::default(), EguiInput(input)))
+            .id();
+        let surface = app
+            .world_mut()
+            .spawn((ConsoleSurface, EguiInput::default()))
+            .id();
+        app.update();
+
+        let events = |entity| {
+            app.world()
+                .get::<EguiInput>(entity)
+                .unwrap()
+                .0
+                .events
+                .clone()
+        };
+        assert_eq!(events(window), [pointer]);
+        assert_eq!(events(surface), [egui::Event::Text("fps".to_owned())]);
+    }
+}
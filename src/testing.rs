@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use crate::commands::record::ConsoleMacros;
+use crate::commands::schedule::ConsoleScheduler;
+use crate::console::{AppCommands, ConsoleState};
+use crate::middleware::ConsoleMiddlewares;
+use crate::throttle::ThrottledLines;
+use crate::{
+    CommandOutcome, ConsoleCommandEntered, ConsoleConfiguration, ConsoleInputSubmitted,
+    ConsoleLocale, PrintConsoleLine,
+};
+
+/// A `say <msg>` and an `exit` command, for tests dispatching lines
+pub(crate) fn test_commands() -> BTreeMap<&'static str, clap::Command> {
+    BTreeMap::from([
+        ("say", clap::Command::new("say").arg(clap::Arg::new("msg"))),
+        ("exit", clap::Command::new("exit")),
+    ])
+}
+
+/// App with the [`test_commands`] and the events and resources lines are dispatched with,
+/// without any systems
+pub(crate) fn test_app() -> App {
+    let mut app = App::new();
+    app.add_event::<ConsoleInputSubmitted>()
+        .add_event::<ConsoleCommandEntered>()
+        .add_event::<PrintConsoleLine>()
+        .add_event::<CommandOutcome>()
+        .insert_resource(ConsoleConfiguration {
+            commands: test_commands(),
+            ..default()
+        })
+        .init_resource::<ConsoleState>()
+        .init_resource::<ConsoleMiddlewares>()
+        .init_resource::<AppCommands>()
+        .init_resource::<ConsoleLocale>()
+        .init_resource::<ConsoleMacros>()
+        .init_resource::<ConsoleScheduler>()
+        .init_resource::<ThrottledLines>();
+    app
+}
@@ -0,0 +1,154 @@
+use std::borrow::{Borrow, Cow};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Text of a [`PrintConsoleLine`](crate::PrintConsoleLine), either borrowed for the whole
+/// program, owned, or shared.
+///
+/// Lines are cloned into the scrollback of every frontend, so systems printing every frame can
+/// print static or shared text, which is cloned without allocating.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use bevy_console::{ConsoleText, PrintConsoleLine};
+/// let status: Arc<str> = Arc::from("connected");
+/// let line = PrintConsoleLine::new(status.clone());
+/// assert_eq!(line.line, "connected");
+/// let line = PrintConsoleLine::new("ready");
+/// assert!(matches!(line.line, ConsoleText::Static("ready")));
+/// ```
+#[derive(Clone)]
+pub enum ConsoleText {
+    /// Text borrowed for the whole program, e.g. a string literal
+    Static(&'static str),
+    /// Owned text
+    Owned(String),
+    /// Text shared between lines
+    Shared(Arc<str>),
+}
+
+impl ConsoleText {
+    /// The text as a string slice.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Static(text) => text,
+            Self::Owned(text) => text,
+            Self::Shared(text) => text,
+        }
+    }
+
+    /// The text as an owned string, only allocating if it isn't owned already.
+    pub fn into_string(self) -> String {
+        match self {
+            Self::Owned(text) => text,
+            text => text.as_str().to_owned(),
+        }
+    }
+}
+
+impl Default for ConsoleText {
+    fn default() -> Self {
+        Self::Static("")
+    }
+}
+
+impl Deref for ConsoleText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for ConsoleText {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for ConsoleText {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for ConsoleText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for ConsoleText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq for ConsoleText {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for ConsoleText {}
+
+impl Hash for ConsoleText {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl PartialEq<str> for ConsoleText {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ConsoleText {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for ConsoleText {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl From<&'static str> for ConsoleText {
+    fn from(text: &'static str) -> Self {
+        Self::Static(text)
+    }
+}
+
+impl From<String> for ConsoleText {
+    fn from(text: String) -> Self {
+        Self::Owned(text)
+    }
+}
+
+impl From<Arc<str>> for ConsoleText {
+    fn from(text: Arc<str>) -> Self {
+        Self::Shared(text)
+    }
+}
+
+impl From<Cow<'static, str>> for ConsoleText {
+    fn from(text: Cow<'static, str>) -> Self {
+        match text {
+            Cow::Borrowed(text) => Self::Static(text),
+            Cow::Owned(text) => Self::Owned(text),
+        }
+    }
+}
+
+impl From<ConsoleText> for String {
+    fn from(text: ConsoleText) -> Self {
+        text.into_string()
+    }
+}
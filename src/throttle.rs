@@ -0,0 +1,270 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::*;
+
+use crate::text::ConsoleText;
+use crate::{ConsoleConfiguration, ConsoleLocale, ConsoleSeverity, PrintConsoleLine};
+
+/// Length of the window lines are counted in
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Limits how many similar lines are printed per second, so log storms don't bring the console
+/// to a crawl.
+///
+/// Lines over the limit are suppressed, and once the second is over a note tells how many were.
+/// Set for all lines with [`ConsoleConfiguration::throttle`] or per channel with
+/// [`ConsoleChannel::throttle`](crate::ConsoleChannel::throttle).
+///
+/// Command echoes, pinned lines, progress updates and replies targeted at a single source are
+/// never suppressed.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_console::{ConsoleConfiguration, ConsoleSeverity, Throttle};
+/// let config = ConsoleConfiguration {
+///     // at most 10 identical info or debug lines per second
+///     throttle: Some(Throttle::new(10)),
+///     ..Default::default()
+/// };
+/// let net = Throttle::new(50).by_channel().with_max_severity(ConsoleSeverity::Warn);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Throttle {
+    /// Most similar lines printed per second, the rest are suppressed
+    pub per_second: u32,
+    /// Which lines count as similar
+    pub group: ThrottleGroup,
+    /// Most severe lines which are throttled, more severe lines are always printed
+    pub max_severity: ConsoleSeverity,
+}
+
+impl Default for Throttle {
+    fn default() -> Self {
+        Self {
+            per_second: 20,
+            group: ThrottleGroup::Message,
+            max_severity: ConsoleSeverity::Info,
+        }
+    }
+}
+
+impl Throttle {
+    /// Limits identical info and debug lines to the given number per second.
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            per_second,
+            ..default()
+        }
+    }
+
+    /// Counts all lines of a channel together, instead of identical lines.
+    pub fn by_channel(mut self) -> Self {
+        self.group = ThrottleGroup::Channel;
+        self
+    }
+
+    /// Sets the most severe lines which are throttled.
+    pub fn with_max_severity(mut self, severity: ConsoleSeverity) -> Self {
+        self.max_severity = severity;
+        self
+    }
+}
+
+/// Which lines count as similar for a [`Throttle`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThrottleGroup {
+    /// Identical lines printed to the same channel
+    #[default]
+    Message,
+    /// All lines printed to the same channel, or without a channel
+    Channel,
+}
+
+/// Lines counted in the current second for a group of similar lines
+struct ThrottleWindow {
+    /// When the first line of the window was printed
+    start: Duration,
+    /// Lines printed in the window, including the suppressed ones
+    count: u32,
+    /// Lines suppressed in the window
+    suppressed: u32,
+    /// Most severe suppressed line
+    severity: ConsoleSeverity,
+}
+
+impl ThrottleWindow {
+    fn new(start: Duration) -> Self {
+        Self {
+            start,
+            count: 0,
+            suppressed: 0,
+            severity: ConsoleSeverity::Debug,
+        }
+    }
+
+    /// The note telling how many lines were suppressed, if any were
+    fn note(&self, channel: &Option<String>, locale: &ConsoleLocale) -> Option<PrintConsoleLine> {
+        if self.suppressed == 0 {
+            return None;
+        }
+        let text = locale.format(
+            "lines-suppressed",
+            &[("count", &self.suppressed.to_string())],
+        );
+        let note = PrintConsoleLine {
+            channel: channel.clone(),
+            ..PrintConsoleLine::new(text)
+        };
+        Some(note.with_severity(self.severity))
+    }
+}
+
+/// Lines suppressed by the [`Throttle`]s, which the outputs skip
+#[derive(Default, Resource)]
+pub(crate) struct ThrottledLines {
+    /// Event ids of the lines suppressed this frame
+    suppressed: HashSet<usize>,
+    /// Windows of the groups printed to in the last second, by channel and text
+    windows: HashMap<(Option<String>, Option<ConsoleText>), ThrottleWindow>,
+}
+
+impl ThrottledLines {
+    /// Reads the printed lines which weren't suppressed
+    pub(crate) fn read<'a>(
+        &'a self,
+        lines: &'a mut EventReader<'_, '_, PrintConsoleLine>,
+    ) -> impl Iterator<Item = &'a PrintConsoleLine> + 'a {
+        lines
+            .read_with_id()
+            .filter(|(_, id)| !self.suppressed.contains(&id.id))
+            .map(|(line, _)| line)
+    }
+}
+
+/// Counts the lines printed since the last frame, suppressing those over their throttle's limit
+pub(crate) fn throttle_lines(
+    config: Res<ConsoleConfiguration>,
+    locale: Option<Res<ConsoleLocale>>,
+    time: Res<Time<Real>>,
+    mut throttled: ResMut<ThrottledLines>,
+    mut events: ResMut<Events<PrintConsoleLine>>,
+    mut reader: Local<ManualEventReader<PrintConsoleLine>>,
+) {
+    let default_locale = ConsoleLocale::default();
+    let locale = locale.as_deref().unwrap_or(&default_locale);
+    let now = time.elapsed();
+    let ThrottledLines {
+        suppressed,
+        windows,
+    } = &mut *throttled;
+    suppressed.clear();
+
+    let mut notes = Vec::new();
+    for (line, id) in reader.read_with_id(&events) {
+        if line.target.is_some()
+            || line.is_command
+            || line.pin.is_some()
+            || line.line.starts_with('\r')
+        {
+            continue;
+        }
+        let Some(throttle) = config.throttle_for(line.channel.as_deref()) else {
+            continue;
+        };
+        if line.severity > throttle.max_severity {
+            continue;
+        }
+
+        let text = match throttle.group {
+            ThrottleGroup::Message => Some(line.line.clone()),
+            ThrottleGroup::Channel => None,
+        };
+        let window = windows
+            .entry((line.channel.clone(), text))
+            .or_insert_with(|| ThrottleWindow::new(now));
+        if now - window.start >= WINDOW {
+            notes.extend(window.note(&line.channel, locale));
+            *window = ThrottleWindow::new(now);
+        }
+        window.count += 1;
+        if window.count > throttle.per_second {
+            window.suppressed += 1;
+            window.severity = window.severity.max(line.severity);
+            suppressed.insert(id.id);
+        }
+    }
+
+    windows.retain(|(channel, _), window| {
+        if now - window.start < WINDOW {
+            return true;
+        }
+        notes.extend(window.note(channel, locale));
+        false
+    });
+
+    events.send_batch(notes);
+    // the notes aren't throttled themselves
+    reader.clear(&events);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ConsoleState;
+    use crate::frontend::print_to_frontend;
+
+    #[test]
+    fn test_throttle_lines() {
+        let mut app = App::new();
+        app.add_event::<PrintConsoleLine>()
+            .insert_resource(ConsoleConfiguration {
+                throttle: Some(Throttle::new(2)),
+                ..default()
+            })
+            .init_resource::<ConsoleState>()
+            .init_resource::<ThrottledLines>()
+            .init_resource::<Time<Real>>()
+            .add_systems(
+                Update,
+                (throttle_lines, print_to_frontend::<ConsoleState>).chain(),
+            );
+        fn advance(app: &mut App, secs: f32) {
+            app.world_mut()
+                .resource_mut::<Time<Real>>()
+                .update_with_duration(Duration::from_secs_f32(secs));
+            app.update();
+        }
+        let scrollback = |app: &App| {
+            app.world()
+                .resource::<ConsoleState>()
+                .scrollback
+                .iter()
+                .map(|line| line.line.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        app.world_mut().send_event_batch([
+            PrintConsoleLine::new("spam"),
+            PrintConsoleLine::new("spam"),
+            PrintConsoleLine::new("other"),
+            PrintConsoleLine::new("spam"),
+            PrintConsoleLine::new("spam").with_severity(ConsoleSeverity::Warn),
+            PrintConsoleLine::new("spam"),
+        ]);
+        // the first update only starts the clock
+        advance(&mut app, 0.0);
+        assert_eq!(scrollback(&app), ["spam", "spam", "other", "spam"]);
+
+        advance(&mut app, 0.5);
+        assert_eq!(scrollback(&app).len(), 4);
+        advance(&mut app, 0.6);
+        assert_eq!(
+            scrollback(&app)[4..],
+            ["suppressed 2 similar messages".to_owned()]
+        );
+        assert!(app.world().resource::<ThrottledLines>().windows.is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use rustls::crypto::ring::default_provider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::{ServerConfig, ServerConnection};
+
+/// Size of the encrypted records read from the socket at once
+const RECORD_BUFFER_LEN: usize = 16 * 1024;
+
+/// Builds the TLS configuration of a server from a PEM certificate chain and private key
+pub(crate) fn server_config(
+    cert_chain: &Path,
+    private_key: &Path,
+) -> io::Result<Arc<ServerConfig>> {
+    let cert_chain = CertificateDer::pem_file_iter(cert_chain)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(invalid_data)?;
+    let private_key = PrivateKeyDer::from_pem_file(private_key).map_err(invalid_data)?;
+    let config = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| {
+            builder
+                .with_no_client_auth()
+                .with_single_cert(cert_chain, private_key)
+        })
+        .map_err(invalid_data)?;
+    Ok(Arc::new(config))
+}
+
+fn invalid_data(err: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+/// A TLS connection of a client, read on its connection thread while the app writes to it.
+///
+/// Reading the socket doesn't hold the lock of the connection, so writes don't wait for the
+/// client to send something.
+pub(crate) struct TlsStream {
+    connection: Arc<Mutex<ServerConnection>>,
+    tcp: TcpStream,
+}
+
+impl TlsStream {
+    /// Starts the handshake with a client, which completes while reading from it
+    pub(crate) fn new(config: Arc<ServerConfig>, tcp: TcpStream) -> io::Result<Self> {
+        let connection = ServerConnection::new(config).map_err(invalid_data)?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+            tcp,
+        })
+    }
+
+    /// Returns another handle to the same connection
+    pub(crate) fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self {
+            connection: self.connection.clone(),
+            tcp: self.tcp.try_clone()?,
+        })
+    }
+
+    /// The underlying socket, e.g. to set timeouts
+    pub(crate) fn tcp(&self) -> &TcpStream {
+        &self.tcp
+    }
+
+    fn lock(&self) -> io::Result<MutexGuard<'_, ServerConnection>> {
+        self.connection
+            .lock()
+            .map_err(|_| io::Error::other("TLS connection poisoned"))
+    }
+}
+
+/// Sends the records queued by rustls, like handshake messages, written data or alerts
+fn send_records(connection: &mut ServerConnection, mut tcp: &TcpStream) -> io::Result<()> {
+    while connection.wants_write() {
+        connection.write_tls(&mut tcp)?;
+    }
+    Ok(())
+}
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut records = [0; RECORD_BUFFER_LEN];
+        loop {
+            match self.lock()?.reader().read(buf) {
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+                // a client closing the socket without a `close_notify` is just disconnected
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(0),
+                result => return result,
+            }
+
+            let read = (&self.tcp).read(&mut records)?;
+            if read == 0 {
+                return Ok(0);
+            }
+            let mut connection = self.lock()?;
+            let mut records = &records[..read];
+            while !records.is_empty() {
+                connection.read_tls(&mut records)?;
+                if let Err(err) = connection.process_new_packets() {
+                    // tells the client what went wrong
+                    let _ = send_records(&mut connection, &self.tcp);
+                    return Err(invalid_data(err));
+                }
+            }
+            send_records(&mut connection, &self.tcp)?;
+        }
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut connection = self.lock()?;
+        let written = connection.writer().write(buf)?;
+        send_records(&mut connection, &self.tcp)?;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut connection = self.lock()?;
+        connection.writer().flush()?;
+        send_records(&mut connection, &self.tcp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+
+    use super::*;
+
+    const CERT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tls/cert.pem");
+    const KEY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tls/key.pem");
+
+    #[test]
+    fn test_tls_stream() {
+        let config = server_config(Path::new(CERT), Path::new(KEY)).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let client = thread::spawn(move || {
+            let mut roots = RootCertStore::empty();
+            for cert in CertificateDer::pem_file_iter(CERT).unwrap() {
+                roots.add(cert.unwrap()).unwrap();
+            }
+            let config = ClientConfig::builder_with_provider(Arc::new(default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+            let connection =
+                ClientConnection::new(Arc::new(config), "localhost".try_into().unwrap()).unwrap();
+            let mut stream = StreamOwned::new(connection, TcpStream::connect(address).unwrap());
+            stream.write_all(b"ping\n").unwrap();
+            let mut reply = [0; 5];
+            stream.read_exact(&mut reply).unwrap();
+            reply
+        });
+
+        let (tcp, _) = listener.accept().unwrap();
+        let mut reader = TlsStream::new(config, tcp).unwrap();
+        let mut writer = reader.try_clone().unwrap();
+        let mut line = [0; 5];
+        reader.read_exact(&mut line).unwrap();
+        assert_eq!(&line, b"ping\n");
+        writer.write_all(b"pong\n").unwrap();
+        assert_eq!(&client.join().unwrap(), b"pong\n");
+    }
+
+    #[test]
+    fn test_server_config_rejects_missing_files() {
+        let error = server_config(Path::new(CERT), Path::new("missing.pem")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
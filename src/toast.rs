@@ -0,0 +1,182 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::egui::{self, Color32};
+use bevy_egui::EguiContexts;
+
+use crate::console::console_font;
+use crate::throttle::ThrottledLines;
+use crate::{
+    strip_ansi, CommandSource, ConsoleConfiguration, ConsoleOpen, ConsoleSeverity, PrintConsoleLine,
+};
+
+/// Time it takes a toast to fade out at the end of its duration
+const FADE_OUT: Duration = Duration::from_millis(500);
+
+/// Settings of on-screen toasts, see [`ConsolePlugin::with_toasts`](crate::ConsolePlugin::with_toasts).
+///
+/// Printed lines of at least [`min_severity`](Self::min_severity) are shown in the top right
+/// corner of the window while the console is closed, styled like in the scrollback.
+#[derive(Clone, Debug, Resource)]
+pub struct ConsoleToasts {
+    /// Lowest severity shown as a toast, [`ConsoleSeverity::Warn`] by default
+    pub min_severity: ConsoleSeverity,
+    /// Time a toast stays on screen, 5 seconds by default
+    pub duration: Duration,
+    /// Most toasts shown at once, the oldest are dropped first
+    pub max_toasts: usize,
+}
+
+impl Default for ConsoleToasts {
+    fn default() -> Self {
+        Self {
+            min_severity: ConsoleSeverity::Warn,
+            duration: Duration::from_secs(5),
+            max_toasts: 5,
+        }
+    }
+}
+
+struct Toast {
+    line: String,
+    severity: ConsoleSeverity,
+    expires: Duration,
+}
+
+/// Toasts currently on screen, oldest first
+#[derive(Default, Resource)]
+pub(crate) struct ToastQueue(VecDeque<Toast>);
+
+impl ToastQueue {
+    fn push(&mut self, settings: &ConsoleToasts, line: &PrintConsoleLine, now: Duration) {
+        // replies to terminal, remote and player commands are shown there
+        let elsewhere = matches!(
+            line.target,
+            Some(CommandSource::Terminal | CommandSource::Remote(_) | CommandSource::Player(_))
+        );
+        if line.is_command || elsewhere || line.severity < settings.min_severity {
+            return;
+        }
+        self.0.push_back(Toast {
+            line: strip_ansi(&line.line),
+            severity: line.severity,
+            expires: now + settings.duration,
+        });
+        while self.0.len() > settings.max_toasts {
+            self.0.pop_front();
+        }
+    }
+
+    fn expire(&mut self, now: Duration) {
+        self.0.retain(|toast| toast.expires > now);
+    }
+}
+
+/// Opacity of a toast with `remaining` time left
+fn toast_alpha(remaining: Duration) -> f32 {
+    (remaining.as_secs_f32() / FADE_OUT.as_secs_f32()).min(1.0)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn toast_ui(
+    mut egui_context: EguiContexts,
+    settings: Res<ConsoleToasts>,
+    config: Res<ConsoleConfiguration>,
+    console_open: Res<ConsoleOpen>,
+    time: Res<Time<Real>>,
+    mut toasts: ResMut<ToastQueue>,
+    throttled: Res<ThrottledLines>,
+    mut lines: EventReader<PrintConsoleLine>,
+    windows: Query<(), With<Window>>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+) {
+    let now = time.elapsed();
+    for line in throttled.read(&mut lines) {
+        toasts.push(&settings, line, now);
+    }
+    toasts.expire(now);
+    if toasts.0.is_empty() || console_open.open {
+        return;
+    }
+
+    let Some(target) = config
+        .window
+        .filter(|window| windows.contains(*window))
+        .or_else(|| primary_window.get_single().ok())
+    else {
+        return;
+    };
+    let Some(ctx) = egui_context.try_ctx_for_entity_mut(target) else {
+        return;
+    };
+
+    let font = console_font(ctx, &config, 1.0);
+    let background: Color32 = config.theme.background_color.into();
+    egui::Area::new(egui::Id::new("console_toasts"))
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            ui.set_max_width(ctx.screen_rect().width() / 3.0);
+            for toast in &toasts.0 {
+                let alpha = toast_alpha(toast.expires - now);
+                let style = config.theme.severity_style(toast.severity);
+                let color: Color32 = style.color.unwrap_or(config.theme.foreground_color).into();
+                let text = format!("{}{}", style.prefix.unwrap_or_default(), toast.line);
+                egui::Frame::popup(ui.style())
+                    .fill(background.gamma_multiply(alpha))
+                    .stroke(egui::Stroke::NONE)
+                    .show(ui, |ui| {
+                        ui.label(
+                            egui::RichText::new(text)
+                                .font(font.clone())
+                                .color(color.gamma_multiply(alpha)),
+                        );
+                    });
+            }
+        });
+    // keep repainting while the toasts fade out
+    ctx.request_repaint();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toast_queue() {
+        let settings = ConsoleToasts {
+            max_toasts: 2,
+            ..default()
+        };
+        let mut toasts = ToastQueue::default();
+        let now = Duration::ZERO;
+        toasts.push(&settings, &PrintConsoleLine::new("info"), now);
+        toasts.push(
+            &settings,
+            &PrintConsoleLine::new("terminal")
+                .with_severity(ConsoleSeverity::Error)
+                .with_target(CommandSource::Terminal),
+            now,
+        );
+        assert!(toasts.0.is_empty());
+
+        for line in ["first", "\x1b[31msecond\x1b[0m", "third"] {
+            let line = PrintConsoleLine::new(line).with_severity(ConsoleSeverity::Error);
+            toasts.push(&settings, &line, now);
+        }
+        let lines = toasts.0.iter().map(|toast| toast.line.as_str());
+        assert_eq!(lines.collect::<Vec<_>>(), ["second", "third"]);
+
+        toasts.expire(settings.duration);
+        assert!(toasts.0.is_empty());
+    }
+
+    #[test]
+    fn test_toast_alpha() {
+        assert_eq!(toast_alpha(Duration::from_secs(3)), 1.0);
+        assert_eq!(toast_alpha(FADE_OUT / 2), 0.5);
+        assert_eq!(toast_alpha(Duration::ZERO), 0.0);
+    }
+}
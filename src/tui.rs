@@ -0,0 +1,602 @@
+use std::collections::VecDeque;
+use std::io::{self, IsTerminal, Stdout};
+use std::mem;
+use std::panic;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{
+    self, Event, KeyCode as Key, KeyEvent, KeyEventKind, KeyModifiers,
+};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::layout::{Constraint, Layout, Position};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Terminal;
+
+use crate::color::strip_ansi;
+use crate::completion::{argument_completions, common_prefix};
+use crate::console::{argument_hint, command_suggestions, ConsoleState};
+use crate::frontend::print_to_frontend;
+use crate::input_edit::{KillRing, LineEdit};
+use crate::{
+    AddConsoleFrontend, CommandSource, ConsoleConfiguration, ConsoleEof, ConsoleFrontend,
+    ConsoleInterrupted, ConsoleOpen, ConsoleSet, EofAction, PrintConsoleLine,
+};
+
+/// Most lines kept in the scrollback of the terminal UI
+const MAX_SCROLLBACK: usize = 10_000;
+
+/// Rows scrolled by `PageUp` and `PageDown`
+const PAGE_ROWS: usize = 10;
+
+/// What a key pressed in the terminal UI asks of the app
+#[derive(Debug, PartialEq, Eq)]
+enum KeyOutcome {
+    Handled,
+    /// `Ctrl+C` on an empty line
+    Interrupt,
+    /// `Ctrl+D` on an empty line
+    Eof,
+}
+
+/// Full-screen terminal frontend of the `tui` feature: a scrollback pane,
+/// the input line and a bar suggesting completions of the input.
+#[derive(Resource)]
+pub(crate) struct TerminalUi {
+    /// `None` in tests, which don't draw
+    terminal: Option<Terminal<CrosstermBackend<Stdout>>>,
+    scrollback: VecDeque<PrintConsoleLine>,
+    input: String,
+    /// Cursor position in the input, in characters
+    cursor: usize,
+    kill_ring: KillRing,
+    /// Entered lines, newest first
+    history: VecDeque<String>,
+    /// Browsed history entry, 0 being the line typed before browsing
+    history_index: usize,
+    draft: String,
+    /// Rows scrolled up from the bottom of the scrollback
+    scroll: usize,
+    submitted: VecDeque<String>,
+    /// Whether the screen needs to be drawn again
+    dirty: bool,
+}
+
+impl TerminalUi {
+    fn new(terminal: Option<Terminal<CrosstermBackend<Stdout>>>) -> Self {
+        Self {
+            terminal,
+            scrollback: VecDeque::new(),
+            input: String::new(),
+            cursor: 0,
+            kill_ring: KillRing::default(),
+            history: VecDeque::new(),
+            history_index: 0,
+            draft: String::new(),
+            scroll: 0,
+            submitted: VecDeque::new(),
+            dirty: true,
+        }
+    }
+
+    fn cursor_byte(&self) -> usize {
+        self.input
+            .char_indices()
+            .nth(self.cursor)
+            .map_or(self.input.len(), |(index, _)| index)
+    }
+
+    fn set_input(&mut self, input: String) {
+        self.cursor = input.chars().count();
+        self.input = input;
+    }
+
+    /// Completions of the input before the cursor, with the byte offset they replace from
+    fn completions(&self, config: &ConsoleConfiguration) -> Option<(usize, Vec<String>)> {
+        let typed = &self.input[..self.cursor_byte()];
+        argument_completions(&config.commands, typed, &config.completion_root).or_else(|| {
+            (!typed.contains(char::is_whitespace)).then(|| {
+                let names = config.commands.keys().copied();
+                (0, command_suggestions(names, typed))
+            })
+        })
+    }
+
+    /// Extends the typed word to the longest prefix shared by its completions
+    fn complete(&mut self, config: &ConsoleConfiguration) {
+        let Some((start, completions)) = self.completions(config) else {
+            return;
+        };
+        let mut completed = common_prefix(&completions).to_owned();
+        let end = self.cursor_byte();
+        if completed.len() <= end - start {
+            return;
+        }
+        // a single complete value is followed by the next argument
+        if let [completion] = completions.as_slice() {
+            if !completion.ends_with(['/', '.']) {
+                completed.push(' ');
+            }
+        }
+        self.input.replace_range(start..end, &completed);
+        self.cursor = self.input[..start + completed.len()].chars().count();
+    }
+
+    fn browse_history(&mut self, index: usize) {
+        if index > self.history.len() || index == self.history_index {
+            return;
+        }
+        if self.history_index == 0 {
+            self.draft = mem::take(&mut self.input);
+        }
+        self.history_index = index;
+        let input = match index {
+            0 => mem::take(&mut self.draft),
+            _ => self.history[index - 1].clone(),
+        };
+        self.set_input(input);
+    }
+
+    /// Submits the input, echoing it unless it answers a secret prompt
+    fn submit(&mut self, config: &ConsoleConfiguration, state: &ConsoleState) {
+        let line = mem::take(&mut self.input);
+        self.cursor = 0;
+        self.history_index = 0;
+        self.scroll = 0;
+        match &state.input_prompt {
+            Some(pending) if pending.prompt.secret => {}
+            Some(pending) => self.push_line(PrintConsoleLine {
+                is_command: true,
+                ..PrintConsoleLine::new(format!("{} {line}", pending.prompt.message))
+            }),
+            None => {
+                self.push_line(PrintConsoleLine {
+                    is_command: true,
+                    ..PrintConsoleLine::new(format!("{}{line}", strip_ansi(&state.prompt)))
+                });
+                let previous = self.history.front().map(String::as_str);
+                if config.history_policy.should_store(&line, previous) {
+                    self.history.push_front(line.clone());
+                    self.history.truncate(config.history_size);
+                }
+            }
+        }
+        self.submitted.push_back(line);
+    }
+
+    fn push_line(&mut self, line: PrintConsoleLine) {
+        self.scrollback.push_back(line);
+        if self.scrollback.len() > MAX_SCROLLBACK {
+            self.scrollback.pop_front();
+        }
+        self.dirty = true;
+    }
+
+    fn key(
+        &mut self,
+        key: KeyEvent,
+        config: &ConsoleConfiguration,
+        state: &ConsoleState,
+    ) -> KeyOutcome {
+        self.dirty = true;
+        let line_edit = match (key.modifiers, key.code) {
+            (KeyModifiers::CONTROL, Key::Char('c')) if self.input.is_empty() => {
+                return KeyOutcome::Interrupt;
+            }
+            (KeyModifiers::CONTROL, Key::Char('c')) => {
+                self.set_input(String::new());
+                None
+            }
+            (KeyModifiers::CONTROL, Key::Char('d')) if self.input.is_empty() => {
+                return KeyOutcome::Eof;
+            }
+            (KeyModifiers::CONTROL, Key::Char('w')) => Some(LineEdit::KillWordBackward),
+            (KeyModifiers::CONTROL, Key::Char('u')) => Some(LineEdit::KillToStart),
+            (KeyModifiers::CONTROL, Key::Char('k')) => Some(LineEdit::KillToEnd),
+            (KeyModifiers::CONTROL, Key::Char('y')) => Some(LineEdit::Yank),
+            (KeyModifiers::CONTROL, Key::Char('a')) | (_, Key::Home) => Some(LineEdit::Home),
+            (KeyModifiers::CONTROL, Key::Char('e')) | (_, Key::End) => Some(LineEdit::End),
+            (KeyModifiers::ALT, Key::Char('b')) => Some(LineEdit::WordBackward),
+            (KeyModifiers::ALT, Key::Char('f')) => Some(LineEdit::WordForward),
+            (modifiers, Key::Char(c))
+                if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                let index = self.cursor_byte();
+                self.input.insert(index, c);
+                self.cursor += 1;
+                None
+            }
+            (_, Key::Backspace) if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.input.remove(self.cursor_byte());
+                None
+            }
+            (_, Key::Delete) if self.cursor < self.input.chars().count() => {
+                self.input.remove(self.cursor_byte());
+                None
+            }
+            (_, Key::Left) => {
+                self.cursor = self.cursor.saturating_sub(1);
+                None
+            }
+            (_, Key::Right) => {
+                self.cursor = (self.cursor + 1).min(self.input.chars().count());
+                None
+            }
+            (_, Key::Up) => {
+                self.browse_history(self.history_index + 1);
+                None
+            }
+            (_, Key::Down) if self.history_index > 0 => {
+                self.browse_history(self.history_index - 1);
+                None
+            }
+            (_, Key::PageUp) => {
+                self.scroll += PAGE_ROWS;
+                None
+            }
+            (_, Key::PageDown) => {
+                self.scroll = self.scroll.saturating_sub(PAGE_ROWS);
+                None
+            }
+            (_, Key::Tab) => {
+                self.complete(config);
+                None
+            }
+            (_, Key::Enter) => {
+                self.submit(config, state);
+                None
+            }
+            _ => None,
+        };
+        if let Some(line_edit) = line_edit {
+            self.cursor = self
+                .kill_ring
+                .apply(line_edit, &mut self.input, self.cursor);
+        }
+        KeyOutcome::Handled
+    }
+
+    fn draw(&mut self, config: &ConsoleConfiguration, state: &ConsoleState) {
+        let (prompt, secret) = match &state.input_prompt {
+            Some(pending) => (
+                format!("{} ", pending.prompt.message),
+                pending.prompt.secret,
+            ),
+            None => (strip_ansi(&state.prompt), false),
+        };
+        let input = match secret {
+            true => "*".repeat(self.input.chars().count()),
+            false => self.input.clone(),
+        };
+        let suggestions = match self.completions(config) {
+            Some((_, completions)) if !completions.is_empty() => completions.join("  "),
+            _ => argument_hint(&config.commands, &self.input).unwrap_or_default(),
+        };
+        let cursor = prompt.chars().count() + self.cursor;
+        let Self {
+            terminal,
+            scrollback,
+            scroll,
+            ..
+        } = self;
+        let Some(terminal) = terminal else {
+            return;
+        };
+
+        let drawn = terminal.draw(|frame| {
+            let [scrollback_area, input_area, suggestion_area] = Layout::vertical([
+                Constraint::Min(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .areas(frame.area());
+
+            // wrap lines from the bottom up until the visible rows are filled
+            let height = scrollback_area.height as usize;
+            let mut rows = Vec::new();
+            for line in scrollback.iter().rev() {
+                if rows.len() >= height + *scroll {
+                    break;
+                }
+                let style = line_style(config, line);
+                let severity = config.theme.severity_style(line.severity);
+                let text = format!(
+                    "{}{}",
+                    severity.prefix.unwrap_or_default(),
+                    strip_ansi(&line.line)
+                );
+                let wrapped = wrap(&text, scrollback_area.width as usize);
+                rows.extend(
+                    wrapped
+                        .into_iter()
+                        .rev()
+                        .map(|row| Line::styled(row, style)),
+                );
+            }
+            *scroll = (*scroll).min(rows.len().saturating_sub(height));
+            let visible = rows.into_iter().skip(*scroll).take(height).rev();
+            frame.render_widget(Paragraph::new(visible.collect::<Vec<_>>()), scrollback_area);
+
+            let input_line = Line::from(vec![
+                Span::styled(prompt, Style::new().add_modifier(Modifier::BOLD)),
+                Span::raw(input),
+            ]);
+            frame.render_widget(Paragraph::new(input_line), input_area);
+            frame.set_cursor_position(Position::new(
+                input_area.x + cursor.min(u16::MAX as usize) as u16,
+                input_area.y,
+            ));
+
+            let suggestions = Span::styled(suggestions, Style::new().fg(Color::DarkGray));
+            frame.render_widget(Paragraph::new(suggestions), suggestion_area);
+        });
+        if let Err(error) = drawn {
+            warn!("Failed to draw the terminal UI: {error}");
+        }
+        self.dirty = false;
+    }
+}
+
+impl Drop for TerminalUi {
+    fn drop(&mut self) {
+        if self.terminal.is_some() {
+            restore_terminal();
+        }
+    }
+}
+
+impl ConsoleFrontend for TerminalUi {
+    fn source(&self) -> CommandSource {
+        CommandSource::Terminal
+    }
+
+    fn next_line(&mut self) -> Option<String> {
+        self.submitted.pop_front()
+    }
+
+    fn accepts(&self, line: &PrintConsoleLine, config: &ConsoleConfiguration) -> bool {
+        config.mirror_replies
+            || line
+                .target
+                .as_ref()
+                .map_or(true, |target| *target == CommandSource::Terminal)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.scrollback.reserve(additional);
+    }
+
+    fn print_line(&mut self, line: &PrintConsoleLine, _config: &ConsoleConfiguration) {
+        let (clear_screen, erased) = line.erased();
+        if clear_screen {
+            self.scrollback.clear();
+            self.scroll = 0;
+            self.dirty = true;
+            if erased.line.is_empty() {
+                return;
+            }
+        }
+        match self.scrollback.back_mut() {
+            Some(last) if line.updates(last) => {
+                *last = erased;
+                self.dirty = true;
+            }
+            _ => self.push_line(erased),
+        }
+    }
+}
+
+fn line_style(config: &ConsoleConfiguration, line: &PrintConsoleLine) -> Style {
+    let style = match config.theme.severity_style(line.severity).color {
+        Some(color) => Style::new().fg(color.into()),
+        // plain lines keep the colors of the terminal
+        None => Style::new(),
+    };
+    match line.is_command {
+        true => style.add_modifier(Modifier::BOLD),
+        false => style,
+    }
+}
+
+/// Splits text into rows of at most `width` characters, breaking at line breaks
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    text.split('\n')
+        .flat_map(|line| {
+            let chars = line.chars().collect::<Vec<_>>();
+            if chars.is_empty() {
+                return vec![String::new()];
+            }
+            chars
+                .chunks(width)
+                .map(|row| row.iter().collect())
+                .collect()
+        })
+        .collect()
+}
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen);
+}
+
+/// Takes over the terminal, if stdout is one
+fn open_terminal() -> io::Result<Terminal<CrosstermBackend<Stdout>>> {
+    if !io::stdout().is_terminal() {
+        return Err(io::Error::other("stdout isn't a terminal"));
+    }
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    // leave the terminal usable when the app panics
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        hook(info);
+    }));
+    Terminal::new(CrosstermBackend::new(io::stdout()))
+}
+
+/// Edits the input with the pressed keys, sending the same events as the `rustyline` terminal
+fn read_tui_keys(
+    mut tui: ResMut<TerminalUi>,
+    config: Res<ConsoleConfiguration>,
+    state: Res<ConsoleState>,
+    mut console_open: ResMut<ConsoleOpen>,
+    mut interrupt: EventWriter<ConsoleInterrupted>,
+    mut eof: EventWriter<ConsoleEof>,
+    mut exit: EventWriter<AppExit>,
+) {
+    while event::poll(Duration::ZERO).unwrap_or(false) {
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(Event::Resize(..)) => {
+                tui.dirty = true;
+                continue;
+            }
+            Ok(_) => continue,
+            Err(_) => return,
+        };
+        match tui.key(key, &config, &state) {
+            KeyOutcome::Handled => {}
+            KeyOutcome::Interrupt => {
+                interrupt.send(ConsoleInterrupted);
+            }
+            KeyOutcome::Eof => {
+                eof.send(ConsoleEof);
+                match config.eof_action {
+                    EofAction::Ignore => {}
+                    EofAction::CloseConsole => console_open.open = false,
+                    EofAction::Exit => {
+                        exit.send(AppExit::Success);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn draw_tui(
+    mut tui: ResMut<TerminalUi>,
+    config: Res<ConsoleConfiguration>,
+    state: Res<ConsoleState>,
+) {
+    if tui.dirty || state.is_changed() {
+        tui.draw(&config, &state);
+    }
+}
+
+pub(super) fn setup_tui(app: &mut App) {
+    let terminal = match open_terminal() {
+        Ok(terminal) => terminal,
+        Err(error) => {
+            restore_terminal();
+            warn!("Terminal UI is not available: {error}");
+            return;
+        }
+    };
+    app.add_console_frontend(TerminalUi::new(Some(terminal)))
+        .add_event::<ConsoleInterrupted>()
+        .add_event::<ConsoleEof>()
+        .add_systems(
+            Update,
+            (
+                read_tui_keys.in_set(ConsoleSet::ConsoleUI),
+                draw_tui
+                    .in_set(ConsoleSet::PostCommands)
+                    .after(print_to_frontend::<TerminalUi>),
+            ),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(tui: &mut TerminalUi, code: Key, modifiers: KeyModifiers) -> KeyOutcome {
+        let config = ConsoleConfiguration::default();
+        tui.key(
+            KeyEvent::new(code, modifiers),
+            &config,
+            &ConsoleState::default(),
+        )
+    }
+
+    fn type_text(tui: &mut TerminalUi, text: &str) {
+        for c in text.chars() {
+            press(tui, Key::Char(c), KeyModifiers::NONE);
+        }
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("abcdef", 4), ["abcd", "ef"]);
+        assert_eq!(wrap("ab\n\ncd", 4), ["ab", "", "cd"]);
+        assert_eq!(wrap("", 4), [""]);
+    }
+
+    #[test]
+    fn test_tui_keys() {
+        let mut tui = TerminalUi::new(None);
+        type_text(&mut tui, "help fps");
+        press(&mut tui, Key::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(tui.input, "help ");
+        press(&mut tui, Key::Enter, KeyModifiers::NONE);
+        assert_eq!(tui.next_line().as_deref(), Some("help "));
+        assert!(tui.scrollback.back().unwrap().is_command);
+
+        type_text(&mut tui, "clear");
+        press(&mut tui, Key::Enter, KeyModifiers::NONE);
+        type_text(&mut tui, "dr");
+        press(&mut tui, Key::Up, KeyModifiers::NONE);
+        assert_eq!(tui.input, "clear");
+        press(&mut tui, Key::Up, KeyModifiers::NONE);
+        assert_eq!(tui.input, "help ");
+        press(&mut tui, Key::Down, KeyModifiers::NONE);
+        press(&mut tui, Key::Down, KeyModifiers::NONE);
+        assert_eq!((tui.input.as_str(), tui.cursor), ("dr", 2));
+
+        assert_eq!(
+            press(&mut tui, Key::Char('c'), KeyModifiers::CONTROL),
+            KeyOutcome::Handled
+        );
+        assert_eq!(tui.input, "");
+        assert_eq!(
+            press(&mut tui, Key::Char('c'), KeyModifiers::CONTROL),
+            KeyOutcome::Interrupt
+        );
+        assert_eq!(
+            press(&mut tui, Key::Char('d'), KeyModifiers::CONTROL),
+            KeyOutcome::Eof
+        );
+    }
+
+    #[test]
+    fn test_tui_complete() {
+        let mut config = ConsoleConfiguration::default();
+        for name in ["spawn", "speed", "difficulty"] {
+            config.commands.insert(name, clap::Command::new(name));
+        }
+        config.commands.insert(
+            "difficulty",
+            clap::Command::new("difficulty")
+                .arg(clap::Arg::new("level").value_parser(["easy", "hard"])),
+        );
+        let mut tui = TerminalUi::new(None);
+        tui.set_input("s".to_owned());
+        tui.complete(&config);
+        assert_eq!(tui.input, "sp");
+        tui.set_input("dif".to_owned());
+        tui.complete(&config);
+        assert_eq!(tui.input, "difficulty ");
+        tui.set_input("difficulty h".to_owned());
+        tui.complete(&config);
+        assert_eq!(tui.input, "difficulty hard ");
+    }
+}